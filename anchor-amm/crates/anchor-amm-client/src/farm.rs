@@ -0,0 +1,151 @@
+//! Instruction builders for one LP farm.
+
+use anchor_amm::{accounts, instruction};
+use anchor_lang::{
+    prelude::Pubkey, solana_program::instruction::Instruction, system_program, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address, get_associated_token_address_with_program_id},
+    token::spl_token,
+};
+
+use crate::{
+    pda,
+    pool::{ix, Pool},
+};
+
+/// Addresses of a farm of a pool's LP, derived from the pool and the
+/// reward mint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Farm {
+    pub farm: Pubkey,
+    pub config: Pubkey,
+    pub mint_lp: Pubkey,
+    pub reward_mint: Pubkey,
+    pub reward_token_program: Pubkey,
+    pub lp_vault: Pubkey,
+    pub reward_vault: Pubkey,
+}
+
+impl Farm {
+    pub fn new(pool: &Pool, (reward_mint, reward_token_program): (Pubkey, Pubkey)) -> Self {
+        let farm = pda::farm(&pool.config, &reward_mint);
+
+        Self {
+            farm,
+            config: pool.config,
+            mint_lp: pool.mint_lp,
+            reward_mint,
+            reward_token_program,
+            lp_vault: get_associated_token_address(&farm, &pool.mint_lp),
+            reward_vault: get_associated_token_address_with_program_id(&farm, &reward_mint, &reward_token_program),
+        }
+    }
+
+    pub fn stake_address(&self, owner: &Pubkey) -> Pubkey {
+        pda::farm_stake(&self.farm, owner)
+    }
+
+    pub fn owner_rewards(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &self.reward_mint, &self.reward_token_program)
+    }
+
+    pub fn create(&self, authority: &Pubkey) -> Instruction {
+        ix(
+            accounts::CreateFarm {
+                authority: *authority,
+                config: self.config,
+                mint_lp: self.mint_lp,
+                reward_mint: self.reward_mint,
+                farm: self.farm,
+                lp_vault: self.lp_vault,
+                reward_vault: self.reward_vault,
+                token_program: spl_token::ID,
+                reward_token_program: self.reward_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::CreateFarm {},
+        )
+    }
+
+    /// Adds `amount` of the reward mint from `funder_rewards`.
+    pub fn fund(&self, funder: &Pubkey, funder_rewards: &Pubkey, amount: u64) -> Instruction {
+        ix(
+            accounts::FundFarm {
+                funder: *funder,
+                farm: self.farm,
+                reward_mint: self.reward_mint,
+                reward_vault: self.reward_vault,
+                funder_rewards: *funder_rewards,
+                reward_token_program: self.reward_token_program,
+            }
+            .to_account_metas(None),
+            instruction::FundFarm { amount },
+        )
+    }
+
+    pub fn set_emission(&self, authority: &Pubkey, reward_rate: u64, emission_end: i64) -> Instruction {
+        ix(
+            accounts::UpdateFarm {
+                authority: *authority,
+                config: self.config,
+                farm: self.farm,
+                reward_mint: self.reward_mint,
+                reward_vault: self.reward_vault,
+                reward_token_program: self.reward_token_program,
+            }
+            .to_account_metas(None),
+            instruction::SetEmission {
+                reward_rate,
+                emission_end,
+            },
+        )
+    }
+
+    fn stake_accounts(&self, owner: &Pubkey) -> accounts::Stake {
+        accounts::Stake {
+            owner: *owner,
+            farm: self.farm,
+            stake: self.stake_address(owner),
+            mint_lp: self.mint_lp,
+            owner_lp: get_associated_token_address(owner, &self.mint_lp),
+            lp_vault: self.lp_vault,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        }
+    }
+
+    pub fn stake(&self, owner: &Pubkey, amount: u64) -> Instruction {
+        ix(
+            self.stake_accounts(owner).to_account_metas(None),
+            instruction::Stake { amount },
+        )
+    }
+
+    pub fn unstake(&self, owner: &Pubkey, amount: u64) -> Instruction {
+        ix(
+            self.stake_accounts(owner).to_account_metas(None),
+            instruction::Unstake { amount },
+        )
+    }
+
+    pub fn harvest(&self, owner: &Pubkey) -> Instruction {
+        ix(
+            accounts::Harvest {
+                owner: *owner,
+                farm: self.farm,
+                stake: self.stake_address(owner),
+                reward_mint: self.reward_mint,
+                reward_vault: self.reward_vault,
+                owner_rewards: self.owner_rewards(owner),
+                reward_token_program: self.reward_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::Harvest {},
+        )
+    }
+}
@@ -0,0 +1,32 @@
+//! Off-chain client for the anchor-amm program: account addresses,
+//! instruction builders, account decoding and quotes.
+//!
+//! Quotes run the program's own `PoolView` on the decoded accounts, so they
+//! give what the program would compute for the same accounts and clock:
+//!
+//! ```ignore
+//! let pool = Pool::new(seed, (mint_x, token_program_x), (mint_y, token_program_y));
+//! let accounts = rpc.get_multiple_accounts(&pool.state_accounts())?;
+//! let [config, vault_x, vault_y, mint_lp, mint_x, mint_y] = /* account data */;
+//! let state = PoolState::decode(config, vault_x, vault_y, mint_lp, mint_x, mint_y)?;
+//! let quote = state.view(&clock).quote_swap(true, 10_000, true)?;
+//! let ix = pool.swap(&user, true, 10_000, quote.amount_out, expiration);
+//! ```
+
+pub mod farm;
+pub mod multi_pool;
+pub mod order;
+pub mod pda;
+pub mod pool;
+pub mod state;
+
+pub use anchor_amm::{
+    self,
+    state::{Config, CurveType},
+    LiquidityQuote, PoolView, SwapQuote, Twap, ID,
+};
+pub use farm::*;
+pub use multi_pool::*;
+pub use order::*;
+pub use pool::*;
+pub use state::*;
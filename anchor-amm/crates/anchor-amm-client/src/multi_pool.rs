@@ -0,0 +1,231 @@
+//! Instruction builders for one multi-asset pool.
+
+use anchor_amm::{accounts, instruction};
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::instruction::{AccountMeta, Instruction},
+    system_program, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address, get_associated_token_address_with_program_id},
+    token::spl_token,
+    token_2022::spl_token_2022,
+};
+
+use crate::{pda, pool::ix};
+
+/// Addresses of a multi-asset pool, derived from its seed and mints.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiPool {
+    pub seed: u64,
+    pub pool: Pubkey,
+    /// Each token's mint and the token program that owns it, in pool
+    /// order.
+    pub tokens: Vec<(Pubkey, Pubkey)>,
+    pub vaults: Vec<Pubkey>,
+    pub mint_lp: Pubkey,
+    pub dead: Pubkey,
+    pub locked_lp: Pubkey,
+}
+
+impl MultiPool {
+    pub fn new(seed: u64, tokens: Vec<(Pubkey, Pubkey)>) -> Self {
+        let pool = pda::multi_pool(seed);
+
+        Self {
+            seed,
+            pool,
+            vaults: tokens
+                .iter()
+                .map(|(mint, token_program)| pda::vault(&pool, mint, token_program))
+                .collect(),
+            tokens,
+            mint_lp: pda::lp_mint(&pool),
+            dead: pda::dead(&pool),
+            locked_lp: pda::locked_lp(&pool),
+        }
+    }
+
+    pub fn user_token(&self, user: &Pubkey, index: usize) -> Pubkey {
+        let (mint, token_program) = &self.tokens[index];
+        get_associated_token_address_with_program_id(user, mint, token_program)
+    }
+
+    pub fn user_lp(&self, user: &Pubkey) -> Pubkey {
+        get_associated_token_address(user, &self.mint_lp)
+    }
+
+    /// Remaining accounts of token `index` for `user`.
+    pub fn token_metas(&self, user: &Pubkey, index: usize) -> [AccountMeta; 3] {
+        [
+            AccountMeta::new_readonly(self.tokens[index].0, false),
+            AccountMeta::new(self.vaults[index], false),
+            AccountMeta::new(self.user_token(user, index), false),
+        ]
+    }
+
+    fn all_token_metas(&self, user: &Pubkey) -> impl Iterator<Item = AccountMeta> + '_ {
+        let user = *user;
+        (0..self.tokens.len()).flat_map(move |index| self.token_metas(&user, index))
+    }
+
+    pub fn initialize(
+        &self,
+        initializer: &Pubkey,
+        fee: u16,
+        authority: Option<Pubkey>,
+        weights: Vec<u16>,
+    ) -> Instruction {
+        let mut metas = accounts::InitializeMultiPool {
+            initializer: *initializer,
+            pool: self.pool,
+            mint_lp: self.mint_lp,
+            dead: self.dead,
+            locked_lp: self.locked_lp,
+            token_program: spl_token::ID,
+            token_program_2022: spl_token_2022::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None);
+        for ((mint, _), vault) in self.tokens.iter().zip(&self.vaults) {
+            metas.push(AccountMeta::new_readonly(*mint, false));
+            metas.push(AccountMeta::new(*vault, false));
+        }
+
+        ix(
+            metas,
+            instruction::InitializeMultiPool {
+                seed: self.seed,
+                fee,
+                authority,
+                weights,
+            },
+        )
+    }
+
+    fn liquidity_accounts(&self, user: &Pubkey) -> Vec<AccountMeta> {
+        accounts::MultiPoolLiquidity {
+            user: *user,
+            pool: self.pool,
+            mint_lp: self.mint_lp,
+            user_lp: self.user_lp(user),
+            dead: self.dead,
+            locked_lp: self.locked_lp,
+            token_program: spl_token::ID,
+            token_program_2022: spl_token_2022::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None)
+    }
+
+    fn liquidity(&self, user: &Pubkey, index: Option<u8>, data: impl InstructionData) -> Instruction {
+        let mut metas = self.liquidity_accounts(user);
+        match index {
+            Some(index) => metas.extend(self.token_metas(user, index as usize)),
+            None => metas.extend(self.all_token_metas(user)),
+        }
+        ix(metas, data)
+    }
+
+    pub fn deposit(&self, user: &Pubkey, lp: u64, max_amounts: Vec<u64>, expiration: i64) -> Instruction {
+        self.liquidity(
+            user,
+            None,
+            instruction::DepositMulti {
+                lp,
+                max_amounts,
+                expiration,
+            },
+        )
+    }
+
+    pub fn withdraw(&self, user: &Pubkey, lp: u64, min_amounts: Vec<u64>, expiration: i64) -> Instruction {
+        self.liquidity(
+            user,
+            None,
+            instruction::WithdrawMulti {
+                lp,
+                min_amounts,
+                expiration,
+            },
+        )
+    }
+
+    pub fn deposit_single(
+        &self,
+        user: &Pubkey,
+        index: u8,
+        amount_in: u64,
+        min_lp: u64,
+        expiration: i64,
+    ) -> Instruction {
+        self.liquidity(
+            user,
+            Some(index),
+            instruction::DepositMultiSingle {
+                index,
+                amount_in,
+                min_lp,
+                expiration,
+            },
+        )
+    }
+
+    pub fn withdraw_single(&self, user: &Pubkey, index: u8, lp: u64, min_out: u64, expiration: i64) -> Instruction {
+        self.liquidity(
+            user,
+            Some(index),
+            instruction::WithdrawMultiSingle {
+                index,
+                lp,
+                min_out,
+                expiration,
+            },
+        )
+    }
+
+    /// Sends `amount_in` of token `index_in` for at least `min_out` of
+    /// token `index_out`.
+    pub fn swap(
+        &self,
+        user: &Pubkey,
+        (index_in, index_out): (u8, u8),
+        amount_in: u64,
+        min_out: u64,
+        expiration: i64,
+    ) -> Instruction {
+        let mut metas = accounts::SwapMulti {
+            user: *user,
+            pool: self.pool,
+            token_program: spl_token::ID,
+            token_program_2022: spl_token_2022::ID,
+        }
+        .to_account_metas(None);
+        metas.extend(self.token_metas(user, index_in as usize));
+        metas.extend(self.token_metas(user, index_out as usize));
+
+        ix(
+            metas,
+            instruction::SwapMulti {
+                index_in,
+                index_out,
+                amount_in,
+                min_out,
+                expiration,
+            },
+        )
+    }
+
+    pub fn set_locked(&self, authority: &Pubkey, locked: bool) -> Instruction {
+        ix(
+            accounts::UpdateMultiPool {
+                user: *authority,
+                pool: self.pool,
+            }
+            .to_account_metas(None),
+            instruction::SetMultiPoolLocked { locked },
+        )
+    }
+}
@@ -0,0 +1,116 @@
+//! Instruction builders for one limit order.
+
+use anchor_amm::{accounts, instruction};
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction, system_program, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+
+use crate::{
+    pda,
+    pool::{ix, Pool},
+};
+
+/// Addresses of a maker's limit order on a pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Order {
+    pub pool: Pool,
+    pub order: Pubkey,
+    pub maker: Pubkey,
+    pub id: u64,
+    /// Whether the order sells the pool's x for its y.
+    pub is_x: bool,
+    pub escrow: Pubkey,
+}
+
+impl Order {
+    pub fn new(pool: &Pool, maker: &Pubkey, id: u64, is_x: bool) -> Self {
+        let order = pda::order(&pool.config, maker, id);
+        let (mint_in, token_program_in) = match is_x {
+            true => (pool.mint_x, pool.token_program_x),
+            false => (pool.mint_y, pool.token_program_y),
+        };
+
+        Self {
+            pool: *pool,
+            order,
+            maker: *maker,
+            id,
+            is_x,
+            escrow: get_associated_token_address_with_program_id(&order, &mint_in, &token_program_in),
+        }
+    }
+
+    /// Mint and token program of the token sold, then of the token bought.
+    fn mints(&self) -> [(Pubkey, Pubkey); 2] {
+        let x = (self.pool.mint_x, self.pool.token_program_x);
+        let y = (self.pool.mint_y, self.pool.token_program_y);
+        match self.is_x {
+            true => [x, y],
+            false => [y, x],
+        }
+    }
+
+    /// Escrows `amount` from `maker_in` to sell for at least `min_out`.
+    pub fn place(&self, maker_in: &Pubkey, amount: u64, min_out: u64) -> Instruction {
+        let [(mint_in, token_program), _] = self.mints();
+        ix(
+            accounts::PlaceOrder {
+                maker: self.maker,
+                config: self.pool.config,
+                mint_in,
+                order: self.order,
+                maker_in: *maker_in,
+                escrow: self.escrow,
+                token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::PlaceOrder {
+                id: self.id,
+                is_x: self.is_x,
+                amount,
+                min_out,
+            },
+        )
+    }
+
+    /// Append `Pool::tick_metas` to fill on a concentrated liquidity pool.
+    pub fn fill(&self, keeper: &Pubkey) -> Instruction {
+        let [(mint_in, token_program_in), (mint_out, token_program_out)] = self.mints();
+        let mut metas = accounts::FillOrder {
+            keeper: *keeper,
+            maker: self.maker,
+            order: self.order,
+            mint_in,
+            mint_out,
+            escrow: self.escrow,
+            maker_out: get_associated_token_address_with_program_id(&self.maker, &mint_out, &token_program_out),
+            token_program_in,
+            token_program_out,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None);
+        metas.extend(self.pool.swap_accounts(keeper));
+
+        ix(metas, instruction::FillOrder {})
+    }
+
+    pub fn cancel(&self) -> Instruction {
+        let [(mint_in, token_program), _] = self.mints();
+        ix(
+            accounts::CancelOrder {
+                maker: self.maker,
+                order: self.order,
+                mint_in,
+                escrow: self.escrow,
+                maker_in: get_associated_token_address_with_program_id(&self.maker, &mint_in, &token_program),
+                token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::CancelOrder {},
+        )
+    }
+}
@@ -0,0 +1,93 @@
+//! Addresses of the program's accounts, derived with the same helpers the
+//! program checks them with.
+
+use anchor_amm::state::{
+    Config, CurveType, Factory, Farm, FarmStake, MultiPool, Oracle, Order, PoolKey, Position, RegistryPage,
+    Tick,
+};
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+};
+
+pub fn config(seed: u64) -> Pubkey {
+    Config::find_address(seed).0
+}
+
+pub fn lp_mint(config: &Pubkey) -> Pubkey {
+    Config::find_lp_mint(config).0
+}
+
+/// Owner of the minimum liquidity locked by the first deposit.
+pub fn dead(config: &Pubkey) -> Pubkey {
+    Config::find_dead(config).0
+}
+
+/// LP account of the dead PDA.
+pub fn locked_lp(config: &Pubkey) -> Pubkey {
+    get_associated_token_address(&dead(config), &lp_mint(config))
+}
+
+pub fn oracle(config: &Pubkey) -> Pubkey {
+    Oracle::find_address(config).0
+}
+
+/// Vault of `mint` under `config`, an associated token account of
+/// `token_program`.
+pub fn vault(config: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(config, mint, token_program)
+}
+
+pub fn tick(config: &Pubkey, index: i32) -> Pubkey {
+    Tick::find_address(config, index).0
+}
+
+pub fn position(config: &Pubkey, owner: &Pubkey, tick_lower: i32, tick_upper: i32) -> Pubkey {
+    Position::find_address(config, owner, tick_lower, tick_upper).0
+}
+
+/// Temporary wrapped SOL account of `user`, which only exists during a
+/// `swap_sol`, `deposit_sol` or `withdraw_sol`.
+pub fn wsol(user: &Pubkey) -> Pubkey {
+    anchor_amm::native::find_wsol(user).0
+}
+
+pub fn factory() -> Pubkey {
+    Factory::find_address().0
+}
+
+/// The program's `ProgramData`, holding the upgrade authority that creates
+/// the factory.
+pub fn program_data() -> Pubkey {
+    Factory::find_program_data().0
+}
+
+/// Key reserving the factory's pool for the sorted pair, fee tier and
+/// curve.
+pub fn pool_key(mint_x: &Pubkey, mint_y: &Pubkey, fee: u16, curve: CurveType) -> Pubkey {
+    PoolKey::find_address(mint_x, mint_y, fee, curve).0
+}
+
+pub fn registry_page(index: u32) -> Pubkey {
+    RegistryPage::find_address(index).0
+}
+
+/// Multi-asset pool `seed`. Its LP mint, dead PDA, locked LP and vaults
+/// derive from its address like a two-token pool's from its config.
+pub fn multi_pool(seed: u64) -> Pubkey {
+    MultiPool::find_address(seed).0
+}
+
+/// Farm of `config`'s LP paying `reward_mint`.
+pub fn farm(config: &Pubkey, reward_mint: &Pubkey) -> Pubkey {
+    Farm::find_address(config, reward_mint).0
+}
+
+pub fn farm_stake(farm: &Pubkey, owner: &Pubkey) -> Pubkey {
+    FarmStake::find_address(farm, owner).0
+}
+
+/// Limit order `id` of `maker` on `config`.
+pub fn order(config: &Pubkey, maker: &Pubkey, id: u64) -> Pubkey {
+    Order::find_address(config, maker, id).0
+}
@@ -0,0 +1,887 @@
+//! Instruction builders for one pool.
+
+use anchor_amm::{
+    accounts, instruction,
+    state::{CurveType, RegistryPage, Tick},
+    Hop,
+};
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        sysvar,
+    },
+    system_program, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address, get_associated_token_address_with_program_id},
+    token::spl_token,
+};
+
+use crate::pda;
+
+/// Addresses of a pool, derived from its seed and mints.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pool {
+    pub seed: u64,
+    pub config: Pubkey,
+    pub mint_x: Pubkey,
+    pub mint_y: Pubkey,
+    pub token_program_x: Pubkey,
+    pub token_program_y: Pubkey,
+    pub mint_lp: Pubkey,
+    pub oracle: Pubkey,
+    pub vault_x: Pubkey,
+    pub vault_y: Pubkey,
+    pub dead: Pubkey,
+    pub locked_lp: Pubkey,
+}
+
+/// One pool of a `route_swap`.
+#[derive(Clone, Copy, Debug)]
+pub struct RouteHop<'a> {
+    pub pool: &'a Pool,
+    /// Whether the hop sends the pool's x for its y.
+    pub is_x: bool,
+    /// Ticks the hop may cross on a concentrated liquidity pool.
+    pub ticks: &'a [i32],
+}
+
+pub(crate) fn ix(accounts: Vec<AccountMeta>, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: anchor_amm::ID,
+        accounts,
+        data: data.data(),
+    }
+}
+
+impl Pool {
+    /// Pool `seed` for `mint_x` and `mint_y`, each with the token program
+    /// that owns it.
+    pub fn new(
+        seed: u64,
+        (mint_x, token_program_x): (Pubkey, Pubkey),
+        (mint_y, token_program_y): (Pubkey, Pubkey),
+    ) -> Self {
+        let config = pda::config(seed);
+
+        Self {
+            seed,
+            config,
+            mint_x,
+            mint_y,
+            token_program_x,
+            token_program_y,
+            mint_lp: pda::lp_mint(&config),
+            oracle: pda::oracle(&config),
+            vault_x: pda::vault(&config, &mint_x, &token_program_x),
+            vault_y: pda::vault(&config, &mint_y, &token_program_y),
+            dead: pda::dead(&config),
+            locked_lp: pda::locked_lp(&config),
+        }
+    }
+
+    pub fn user_x(&self, user: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(user, &self.mint_x, &self.token_program_x)
+    }
+
+    pub fn user_y(&self, user: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(user, &self.mint_y, &self.token_program_y)
+    }
+
+    pub fn user_lp(&self, user: &Pubkey) -> Pubkey {
+        get_associated_token_address(user, &self.mint_lp)
+    }
+
+    pub fn tick(&self, index: i32) -> Pubkey {
+        pda::tick(&self.config, index)
+    }
+
+    pub fn position(&self, owner: &Pubkey, tick_lower: i32, tick_upper: i32) -> Pubkey {
+        pda::position(&self.config, owner, tick_lower, tick_upper)
+    }
+
+    /// Accounts `PoolState::decode` takes, in order.
+    pub fn state_accounts(&self) -> [Pubkey; 6] {
+        [
+            self.config,
+            self.vault_x,
+            self.vault_y,
+            self.mint_lp,
+            self.mint_x,
+            self.mint_y,
+        ]
+    }
+
+    /// Tick accounts to append to a swap on a concentrated liquidity pool,
+    /// in the order it crosses them.
+    pub fn tick_metas(&self, ticks: &[i32]) -> Vec<AccountMeta> {
+        ticks
+            .iter()
+            .map(|index| AccountMeta::new(self.tick(*index), false))
+            .collect()
+    }
+
+    fn initialize_accounts(&self, initializer: &Pubkey) -> accounts::Initialize {
+        accounts::Initialize {
+            initializer: *initializer,
+            mint_x: self.mint_x,
+            mint_y: self.mint_y,
+            mint_lp: self.mint_lp,
+            config: self.config,
+            oracle: self.oracle,
+            vault_x: self.vault_x,
+            vault_y: self.vault_y,
+            dead: self.dead,
+            locked_lp: self.locked_lp,
+            token_program: spl_token::ID,
+            token_program_x: self.token_program_x,
+            token_program_y: self.token_program_y,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+    }
+
+
+    /// Creates the pool through the factory, which has created
+    /// `pool_count` pools so far.
+    pub fn create_pool(
+        &self,
+        initializer: &Pubkey,
+        fee: u16,
+        curve: CurveType,
+        amp: u64,
+        pool_count: u64,
+    ) -> Instruction {
+        ix(
+            accounts::CreatePool {
+                pool: self.initialize_accounts(initializer),
+                factory: pda::factory(),
+                pool_key: pda::pool_key(&self.mint_x, &self.mint_y, fee, curve),
+                registry_page: pda::registry_page(RegistryPage::page_of(pool_count)),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::CreatePool {
+                seed: self.seed,
+                fee,
+                curve,
+                amp,
+            },
+        )
+    }
+
+    /// `create_pool` for a weighted pool holding `weight_x` basis points of
+    /// its value in x.
+    pub fn create_weighted_pool(&self, initializer: &Pubkey, fee: u16, weight_x: u16, pool_count: u64) -> Instruction {
+        ix(
+            accounts::CreateWeightedPool {
+                pool: self.initialize_accounts(initializer),
+                factory: pda::factory(),
+                pool_key: pda::pool_key(&self.mint_x, &self.mint_y, fee, CurveType::Weighted),
+                registry_page: pda::registry_page(RegistryPage::page_of(pool_count)),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::CreateWeightedPool {
+                seed: self.seed,
+                fee,
+                weight_x,
+            },
+        )
+    }
+
+    /// `create_pool` for a concentrated liquidity pool.
+    pub fn create_concentrated_pool(
+        &self,
+        initializer: &Pubkey,
+        fee: u16,
+        tick_spacing: u16,
+        sqrt_price: u128,
+        pool_count: u64,
+    ) -> Instruction {
+        let (min_tick, max_tick) = Tick::bounds(tick_spacing);
+        ix(
+            accounts::CreateConcentratedPool {
+                pool: accounts::InitializeConcentrated {
+                    initializer: *initializer,
+                    mint_x: self.mint_x,
+                    mint_y: self.mint_y,
+                    config: self.config,
+                    oracle: self.oracle,
+                    min_tick: self.tick(min_tick),
+                    max_tick: self.tick(max_tick),
+                    vault_x: self.vault_x,
+                    vault_y: self.vault_y,
+                    token_program_x: self.token_program_x,
+                    token_program_y: self.token_program_y,
+                    associated_token_program: associated_token::ID,
+                    system_program: system_program::ID,
+                },
+                factory: pda::factory(),
+                pool_key: pda::pool_key(&self.mint_x, &self.mint_y, fee, CurveType::Concentrated),
+                registry_page: pda::registry_page(RegistryPage::page_of(pool_count)),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::CreateConcentratedPool {
+                seed: self.seed,
+                fee,
+                tick_spacing,
+                sqrt_price,
+            },
+        )
+    }
+
+    /// Initializes tick `index` between the initialized ticks `prev` and
+    /// `next`.
+    pub fn initialize_tick(&self, payer: &Pubkey, index: i32, prev: i32, next: i32) -> Instruction {
+        ix(
+            accounts::InitializeTick {
+                payer: *payer,
+                config: self.config,
+                tick: self.tick(index),
+                prev: self.tick(prev),
+                next: self.tick(next),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::InitializeTick { index },
+        )
+    }
+
+    pub fn open_position(&self, owner: &Pubkey, tick_lower: i32, tick_upper: i32) -> Instruction {
+        ix(
+            accounts::OpenPosition {
+                owner: *owner,
+                config: self.config,
+                position: self.position(owner, tick_lower, tick_upper),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::OpenPosition {
+                tick_lower,
+                tick_upper,
+            },
+        )
+    }
+
+    fn modify_position_accounts(&self, owner: &Pubkey, (tick_lower, tick_upper): (i32, i32)) -> Vec<AccountMeta> {
+        accounts::ModifyPosition {
+            owner: *owner,
+            mint_x: self.mint_x,
+            mint_y: self.mint_y,
+            config: self.config,
+            oracle: self.oracle,
+            position: self.position(owner, tick_lower, tick_upper),
+            tick_lower: self.tick(tick_lower),
+            tick_upper: self.tick(tick_upper),
+            vault_x: self.vault_x,
+            vault_y: self.vault_y,
+            user_x: self.user_x(owner),
+            user_y: self.user_y(owner),
+            token_program_x: self.token_program_x,
+            token_program_y: self.token_program_y,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None)
+    }
+
+    pub fn add_liquidity(
+        &self,
+        owner: &Pubkey,
+        range: (i32, i32),
+        liquidity: u64,
+        max_x: u64,
+        max_y: u64,
+        expiration: i64,
+    ) -> Instruction {
+        ix(
+            self.modify_position_accounts(owner, range),
+            instruction::AddLiquidity {
+                liquidity,
+                max_x,
+                max_y,
+                expiration,
+            },
+        )
+    }
+
+    pub fn remove_liquidity(
+        &self,
+        owner: &Pubkey,
+        range: (i32, i32),
+        liquidity: u64,
+        min_x: u64,
+        min_y: u64,
+        expiration: i64,
+    ) -> Instruction {
+        ix(
+            self.modify_position_accounts(owner, range),
+            instruction::RemoveLiquidity {
+                liquidity,
+                min_x,
+                min_y,
+                expiration,
+            },
+        )
+    }
+
+    pub fn collect_fees(&self, owner: &Pubkey, range: (i32, i32)) -> Instruction {
+        ix(
+            self.modify_position_accounts(owner, range),
+            instruction::CollectFees {},
+        )
+    }
+
+    pub fn close_position(&self, owner: &Pubkey, (tick_lower, tick_upper): (i32, i32)) -> Instruction {
+        ix(
+            accounts::ClosePosition {
+                owner: *owner,
+                position: self.position(owner, tick_lower, tick_upper),
+            }
+            .to_account_metas(None),
+            instruction::ClosePosition {},
+        )
+    }
+
+    fn deposit_accounts(&self, user: &Pubkey) -> Vec<AccountMeta> {
+        accounts::Deposit {
+            user: *user,
+            mint_x: self.mint_x,
+            mint_y: self.mint_y,
+            mint_lp: self.mint_lp,
+            config: self.config,
+            oracle: self.oracle,
+            vault_x: self.vault_x,
+            vault_y: self.vault_y,
+            user_x: self.user_x(user),
+            user_y: self.user_y(user),
+            user_lp: self.user_lp(user),
+            dead: self.dead,
+            locked_lp: self.locked_lp,
+            token_program: spl_token::ID,
+            token_program_x: self.token_program_x,
+            token_program_y: self.token_program_y,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None)
+    }
+
+    fn withdraw_accounts(&self, user: &Pubkey) -> Vec<AccountMeta> {
+        accounts::Withdraw {
+            user: *user,
+            mint_x: self.mint_x,
+            mint_y: self.mint_y,
+            mint_lp: self.mint_lp,
+            config: self.config,
+            oracle: self.oracle,
+            vault_x: self.vault_x,
+            vault_y: self.vault_y,
+            user_x: self.user_x(user),
+            user_y: self.user_y(user),
+            user_lp: self.user_lp(user),
+            token_program: spl_token::ID,
+            token_program_x: self.token_program_x,
+            token_program_y: self.token_program_y,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None)
+    }
+
+    pub fn deposit(&self, user: &Pubkey, amount: u64, max_x: u64, max_y: u64, expiration: i64) -> Instruction {
+        ix(
+            self.deposit_accounts(user),
+            instruction::Deposit {
+                amount,
+                max_x,
+                max_y,
+                expiration,
+            },
+        )
+    }
+
+    pub fn withdraw(&self, user: &Pubkey, amount: u64, min_x: u64, min_y: u64, expiration: i64) -> Instruction {
+        ix(
+            self.withdraw_accounts(user),
+            instruction::Withdraw {
+                amount,
+                min_x,
+                min_y,
+                expiration,
+            },
+        )
+    }
+
+    /// The user's x and y accounts for an instruction moving lamports on
+    /// the wrapped SOL side, which is left out, and that side's mint and
+    /// token program.
+    fn native_accounts(&self, user: &Pubkey) -> (Option<Pubkey>, Option<Pubkey>, Pubkey, Pubkey) {
+        match anchor_amm::native::is_native(&self.mint_x) {
+            true => (None, Some(self.user_y(user)), self.mint_x, self.token_program_x),
+            false => (Some(self.user_x(user)), None, self.mint_y, self.token_program_y),
+        }
+    }
+
+    /// `deposit` sending lamports on the wrapped SOL side.
+    pub fn deposit_sol(&self, user: &Pubkey, amount: u64, max_x: u64, max_y: u64, expiration: i64) -> Instruction {
+        let (user_x, user_y, native_mint, native_token_program) = self.native_accounts(user);
+        ix(
+            accounts::DepositSol {
+                user: *user,
+                mint_x: self.mint_x,
+                mint_y: self.mint_y,
+                mint_lp: self.mint_lp,
+                config: self.config,
+                oracle: self.oracle,
+                vault_x: self.vault_x,
+                vault_y: self.vault_y,
+                user_x,
+                user_y,
+                user_lp: self.user_lp(user),
+                dead: self.dead,
+                locked_lp: self.locked_lp,
+                native_mint,
+                wsol: pda::wsol(user),
+                token_program: spl_token::ID,
+                token_program_x: self.token_program_x,
+                token_program_y: self.token_program_y,
+                native_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::DepositSol {
+                amount,
+                max_x,
+                max_y,
+                expiration,
+            },
+        )
+    }
+
+    /// `withdraw` receiving lamports on the wrapped SOL side.
+    pub fn withdraw_sol(&self, user: &Pubkey, amount: u64, min_x: u64, min_y: u64, expiration: i64) -> Instruction {
+        let (user_x, user_y, native_mint, native_token_program) = self.native_accounts(user);
+        ix(
+            accounts::WithdrawSol {
+                user: *user,
+                mint_x: self.mint_x,
+                mint_y: self.mint_y,
+                mint_lp: self.mint_lp,
+                config: self.config,
+                oracle: self.oracle,
+                vault_x: self.vault_x,
+                vault_y: self.vault_y,
+                user_x,
+                user_y,
+                user_lp: self.user_lp(user),
+                native_mint,
+                wsol: pda::wsol(user),
+                token_program: spl_token::ID,
+                token_program_x: self.token_program_x,
+                token_program_y: self.token_program_y,
+                native_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::WithdrawSol {
+                amount,
+                min_x,
+                min_y,
+                expiration,
+            },
+        )
+    }
+
+    pub fn deposit_single(
+        &self,
+        user: &Pubkey,
+        is_x: bool,
+        amount_in: u64,
+        min_lp: u64,
+        expiration: i64,
+    ) -> Instruction {
+        ix(
+            self.deposit_accounts(user),
+            instruction::DepositSingle {
+                is_x,
+                amount_in,
+                min_lp,
+                expiration,
+            },
+        )
+    }
+
+    pub fn withdraw_single(
+        &self,
+        user: &Pubkey,
+        is_x: bool,
+        amount: u64,
+        min_out: u64,
+        expiration: i64,
+    ) -> Instruction {
+        ix(
+            self.withdraw_accounts(user),
+            instruction::WithdrawSingle {
+                is_x,
+                amount,
+                min_out,
+                expiration,
+            },
+        )
+    }
+
+    pub(crate) fn swap_accounts(&self, user: &Pubkey) -> Vec<AccountMeta> {
+        accounts::Swap {
+            user: *user,
+            mint_x: self.mint_x,
+            mint_y: self.mint_y,
+            user_x: self.user_x(user),
+            user_y: self.user_y(user),
+            vault_x: self.vault_x,
+            vault_y: self.vault_y,
+            config: self.config,
+            oracle: self.oracle,
+            token_program_x: self.token_program_x,
+            token_program_y: self.token_program_y,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None)
+    }
+
+    /// Append `tick_metas` to swap on a concentrated liquidity pool.
+    pub fn swap(&self, user: &Pubkey, is_x: bool, amount: u64, min: u64, expiration: i64) -> Instruction {
+        ix(
+            self.swap_accounts(user),
+            instruction::Swap {
+                is_x,
+                amount,
+                min,
+                expiration,
+            },
+        )
+    }
+
+    /// `swap` sending or receiving lamports on the wrapped SOL side.
+    pub fn swap_sol(&self, user: &Pubkey, is_x: bool, amount: u64, min: u64, expiration: i64) -> Instruction {
+        let (user_x, user_y, native_mint, native_token_program) = self.native_accounts(user);
+        ix(
+            accounts::SwapSol {
+                user: *user,
+                mint_x: self.mint_x,
+                mint_y: self.mint_y,
+                user_x,
+                user_y,
+                vault_x: self.vault_x,
+                vault_y: self.vault_y,
+                config: self.config,
+                oracle: self.oracle,
+                native_mint,
+                wsol: pda::wsol(user),
+                token_program_x: self.token_program_x,
+                token_program_y: self.token_program_y,
+                native_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::SwapSol {
+                is_x,
+                amount,
+                min,
+                expiration,
+            },
+        )
+    }
+
+    /// Append `tick_metas` to swap on a concentrated liquidity pool.
+    pub fn swap_exact_out(
+        &self,
+        user: &Pubkey,
+        is_x: bool,
+        amount_out: u64,
+        max_in: u64,
+        expiration: i64,
+    ) -> Instruction {
+        ix(
+            self.swap_accounts(user),
+            instruction::SwapExactOut {
+                is_x,
+                amount_out,
+                max_in,
+                expiration,
+            },
+        )
+    }
+
+    pub fn flash_borrow(&self, user: &Pubkey, amount_x: u64, amount_y: u64) -> Instruction {
+        ix(
+            accounts::FlashBorrow {
+                user: *user,
+                mint_x: self.mint_x,
+                mint_y: self.mint_y,
+                user_x: self.user_x(user),
+                user_y: self.user_y(user),
+                vault_x: self.vault_x,
+                vault_y: self.vault_y,
+                config: self.config,
+                oracle: self.oracle,
+                instructions: sysvar::instructions::ID,
+                token_program_x: self.token_program_x,
+                token_program_y: self.token_program_y,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::FlashBorrow { amount_x, amount_y },
+        )
+    }
+
+    pub fn flash_repay(&self, user: &Pubkey) -> Instruction {
+        ix(
+            accounts::FlashRepay {
+                user: *user,
+                mint_x: self.mint_x,
+                mint_y: self.mint_y,
+                user_x: self.user_x(user),
+                user_y: self.user_y(user),
+                vault_x: self.vault_x,
+                vault_y: self.vault_y,
+                config: self.config,
+                token_program_x: self.token_program_x,
+                token_program_y: self.token_program_y,
+            }
+            .to_account_metas(None),
+            instruction::FlashRepay {},
+        )
+    }
+
+    /// Returns a `Twap` as return data.
+    pub fn observe(&self, window: u32) -> Instruction {
+        ix(
+            accounts::Observe {
+                mint_x: self.mint_x,
+                mint_y: self.mint_y,
+                config: self.config,
+                oracle: self.oracle,
+                vault_x: self.vault_x,
+                vault_y: self.vault_y,
+                token_program_x: self.token_program_x,
+                token_program_y: self.token_program_y,
+            }
+            .to_account_metas(None),
+            instruction::Observe { window },
+        )
+    }
+
+    fn quote_accounts(&self) -> Vec<AccountMeta> {
+        accounts::Quote {
+            mint_x: self.mint_x,
+            mint_y: self.mint_y,
+            config: self.config,
+            mint_lp: self.mint_lp,
+            vault_x: self.vault_x,
+            vault_y: self.vault_y,
+            token_program_x: self.token_program_x,
+            token_program_y: self.token_program_y,
+        }
+        .to_account_metas(None)
+    }
+
+    /// Returns a `SwapQuote` as return data.
+    pub fn quote_swap(&self, is_x: bool, amount: u64, exact_in: bool) -> Instruction {
+        ix(
+            self.quote_accounts(),
+            instruction::QuoteSwap {
+                is_x,
+                amount,
+                exact_in,
+            },
+        )
+    }
+
+    /// Returns a `LiquidityQuote` as return data.
+    pub fn quote_deposit(&self, lp: u64) -> Instruction {
+        ix(self.quote_accounts(), instruction::QuoteDeposit { lp })
+    }
+
+    /// Returns a `LiquidityQuote` as return data.
+    pub fn quote_withdraw(&self, lp: u64) -> Instruction {
+        ix(self.quote_accounts(), instruction::QuoteWithdraw { lp })
+    }
+
+    fn update(&self, user: &Pubkey, data: impl InstructionData) -> Instruction {
+        ix(
+            accounts::Update {
+                user: *user,
+                config: self.config,
+            }
+            .to_account_metas(None),
+            data,
+        )
+    }
+
+    pub fn lock(&self, authority: &Pubkey) -> Instruction {
+        self.update(authority, instruction::Lock {})
+    }
+
+    pub fn unlock(&self, authority: &Pubkey) -> Instruction {
+        self.update(authority, instruction::Unlock {})
+    }
+
+    pub fn set_paused(&self, authority: &Pubkey, swaps: bool, deposits: bool, withdrawals: bool) -> Instruction {
+        self.update(
+            authority,
+            instruction::SetPaused {
+                swaps,
+                deposits,
+                withdrawals,
+            },
+        )
+    }
+
+    pub fn transfer_authority(&self, authority: &Pubkey, new_authority: Option<Pubkey>) -> Instruction {
+        self.update(authority, instruction::TransferAuthority { new_authority })
+    }
+
+    /// Signed by the pending authority.
+    pub fn accept_authority(&self, pending_authority: &Pubkey) -> Instruction {
+        self.update(pending_authority, instruction::AcceptAuthority {})
+    }
+
+    pub fn renounce_authority(&self, authority: &Pubkey) -> Instruction {
+        self.update(authority, instruction::RenounceAuthority {})
+    }
+
+    pub fn set_fee(&self, authority: &Pubkey, fee: u16) -> Instruction {
+        self.update(authority, instruction::SetFee { fee })
+    }
+
+    pub fn set_dynamic_fee(&self, authority: &Pubkey, min_fee: u16, max_fee: u16) -> Instruction {
+        self.update(authority, instruction::SetDynamicFee { min_fee, max_fee })
+    }
+
+    pub fn set_protocol_fee(&self, authority: &Pubkey, protocol_fee: u16) -> Instruction {
+        self.update(authority, instruction::SetProtocolFee { protocol_fee })
+    }
+
+    pub fn ramp_amp(&self, authority: &Pubkey, target_amp: u64, stop_timestamp: i64) -> Instruction {
+        self.update(
+            authority,
+            instruction::RampAmp {
+                target_amp,
+                stop_timestamp,
+            },
+        )
+    }
+
+    pub fn stop_ramp_amp(&self, authority: &Pubkey) -> Instruction {
+        self.update(authority, instruction::StopRampAmp {})
+    }
+
+    pub fn collect_protocol_fees(
+        &self,
+        authority: &Pubkey,
+        treasury_x: &Pubkey,
+        treasury_y: &Pubkey,
+    ) -> Instruction {
+        ix(
+            accounts::CollectProtocolFees {
+                authority: *authority,
+                mint_x: self.mint_x,
+                mint_y: self.mint_y,
+                config: self.config,
+                vault_x: self.vault_x,
+                vault_y: self.vault_y,
+                treasury_x: *treasury_x,
+                treasury_y: *treasury_y,
+                token_program_x: self.token_program_x,
+                token_program_y: self.token_program_y,
+                associated_token_program: associated_token::ID,
+            }
+            .to_account_metas(None),
+            instruction::CollectProtocolFees {},
+        )
+    }
+}
+
+/// Sends `amount` through `hops` in order, for at least `min` out of the
+/// last.
+pub fn route_swap(user: &Pubkey, hops: &[RouteHop], amount: u64, min: u64, expiration: i64) -> Instruction {
+    let mut metas = accounts::RouteSwap { user: *user }.to_account_metas(None);
+    for hop in hops {
+        metas.extend(hop.pool.swap_accounts(user));
+        metas.extend(hop.pool.tick_metas(hop.ticks));
+    }
+
+    ix(
+        metas,
+        instruction::RouteSwap {
+            hops: hops
+                .iter()
+                .map(|hop| Hop {
+                    is_x: hop.is_x,
+                    ticks: hop.ticks.len() as u8,
+                })
+                .collect(),
+            amount,
+            min,
+            expiration,
+        },
+    )
+}
+
+/// `authority` must be the program's upgrade authority.
+pub fn initialize_factory(authority: &Pubkey, fee_tiers: Vec<u16>) -> Instruction {
+    ix(
+        accounts::InitializeFactory {
+            authority: *authority,
+            factory: pda::factory(),
+            program_data: pda::program_data(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        instruction::InitializeFactory { fee_tiers },
+    )
+}
+
+pub fn add_fee_tier(authority: &Pubkey, fee: u16) -> Instruction {
+    ix(
+        accounts::UpdateFactory {
+            authority: *authority,
+            factory: pda::factory(),
+        }
+        .to_account_metas(None),
+        instruction::AddFeeTier { fee },
+    )
+}
+
+/// Brings the config of pool `seed` up to the current `Config` layout and
+/// creates the pool accounts its version lacked. `authority` must be the
+/// program's upgrade authority and covers the rent.
+pub fn migrate_config(authority: &Pubkey, seed: u64) -> Instruction {
+    let config = pda::config(seed);
+
+    ix(
+        accounts::MigrateConfig {
+            authority: *authority,
+            program_data: pda::program_data(),
+            config,
+            mint_lp: pda::lp_mint(&config),
+            oracle: pda::oracle(&config),
+            dead: pda::dead(&config),
+            locked_lp: pda::locked_lp(&config),
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        instruction::MigrateConfig {},
+    )
+}
@@ -0,0 +1,72 @@
+//! Decoding fetched accounts and quoting pools off-chain.
+
+use anchor_amm::{state::Config, PoolView};
+use anchor_lang::{prelude::Clock, AccountDeserialize, Result};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::StateWithExtensions,
+    state::{Account, Mint},
+};
+
+/// Decodes an account of the program, checking its discriminator.
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+/// Balance of a token account of either token program.
+pub fn token_amount(data: &[u8]) -> Result<u64> {
+    Ok(StateWithExtensions::<Account>::unpack(data)?.base.amount)
+}
+
+/// Supply of a mint of either token program.
+pub fn mint_supply(data: &[u8]) -> Result<u64> {
+    Ok(StateWithExtensions::<Mint>::unpack(data)?.base.supply)
+}
+
+/// The accounts a pool's quotes read, fetched at once. Concentrated
+/// liquidity pools have no LP mint and cannot be quoted.
+#[derive(Clone)]
+pub struct PoolState {
+    pub config: Config,
+    pub vault_x: u64,
+    pub vault_y: u64,
+    pub lp_supply: u64,
+    pub mint_x: Vec<u8>,
+    pub mint_y: Vec<u8>,
+}
+
+impl PoolState {
+    /// Decodes the data of the accounts `Pool::state_accounts` lists.
+    pub fn decode(
+        config: &[u8],
+        vault_x: &[u8],
+        vault_y: &[u8],
+        mint_lp: &[u8],
+        mint_x: &[u8],
+        mint_y: &[u8],
+    ) -> Result<Self> {
+        Ok(Self {
+            config: decode(config)?,
+            vault_x: token_amount(vault_x)?,
+            vault_y: token_amount(vault_y)?,
+            lp_supply: mint_supply(mint_lp)?,
+            mint_x: mint_x.to_vec(),
+            mint_y: mint_y.to_vec(),
+        })
+    }
+
+    /// The pool as the program would quote it at `clock`, which sets the
+    /// transfer fee epoch and the StableSwap amplification ramp.
+    pub fn view(&self, clock: &Clock) -> PoolView<'_> {
+        PoolView {
+            config: &self.config,
+            vault_x: self.vault_x,
+            vault_y: self.vault_y,
+            lp_supply: self.lp_supply,
+            mint_x: &self.mint_x,
+            mint_y: &self.mint_y,
+            epoch: clock.epoch,
+            now: clock.unix_timestamp,
+            slot: clock.slot,
+        }
+    }
+}
@@ -0,0 +1,335 @@
+//! Tests checking the client's addresses and instructions against ones built
+//! by hand, and its quotes against the program running in LiteSVM.
+
+#[path = "../../../programs/anchor-amm/tests/common/mod.rs"]
+mod common;
+
+use anchor_amm_client::{CurveType, LiquidityQuote, Pool, PoolState, SwapQuote};
+use anchor_lang::{
+    error::Error,
+    prelude::{Clock, Pubkey},
+    solana_program::instruction::InstructionError,
+};
+use common::{
+    create_mint, create_transfer_fee_mint, factory_with_tier, hand_built, mint_supply, send, setup, simulate,
+    sort_mints, token_balance, token_program_of, PoolExt, NO_EXPIRATION as EXPIRATION,
+};
+use litesvm::LiteSVM;
+use proptest::prelude::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transaction::TransactionError};
+
+/// A funded pool, built with the client, and a user holding `x`/`y`.
+struct Fixture {
+    svm: LiteSVM,
+    admin: Keypair,
+    user: Keypair,
+    pool: Pool,
+}
+
+impl Fixture {
+    fn new(curve: CurveType, fee: u16, transfer_fee: u16, (x, y): (u64, u64)) -> Self {
+        let (mut svm, admin) = setup();
+        let (mint_x, mint_y) = sort_mints(
+            &mut svm,
+            |svm| create_transfer_fee_mint(svm, &admin, transfer_fee, u64::MAX),
+            |svm| create_mint(svm, &admin),
+        );
+        let pool = Pool::for_mints(&svm, 1, mint_x, mint_y);
+
+        let amp = match curve {
+            CurveType::StableSwap => 100,
+            _ => 0,
+        };
+        let pool_count = factory_with_tier(&mut svm, &admin, fee);
+        let ix = pool.create_pool(&admin.pubkey(), fee, curve, amp, pool_count);
+        send(&mut svm, &admin, &[ix]).unwrap();
+
+        let lp = pool.create_user(&mut svm, &admin, x, y);
+        let ix = pool.deposit(&lp.pubkey(), 1, x, y, EXPIRATION);
+        send(&mut svm, &lp, &[ix]).unwrap();
+
+        // A quarter of the pool, so the user holds LP and tokens alike.
+        let user = pool.create_user(&mut svm, &admin, x, y);
+        let amount = mint_supply(&svm, &pool.mint_lp) / 4;
+        let ix = pool.deposit(&user.pubkey(), amount, x, y, EXPIRATION);
+        send(&mut svm, &user, &[ix]).unwrap();
+
+        Self {
+            svm,
+            admin,
+            user,
+            pool,
+        }
+    }
+
+    fn state(&self) -> PoolState {
+        let [config, vault_x, vault_y, mint_lp, mint_x, mint_y] = self
+            .pool
+            .state_accounts()
+            .map(|address| self.svm.get_account(&address).unwrap().data);
+        PoolState::decode(&config, &vault_x, &vault_y, &mint_lp, &mint_x, &mint_y).unwrap()
+    }
+
+    fn clock(&self) -> Clock {
+        self.svm.get_sysvar::<Clock>()
+    }
+
+    fn balances(&self) -> (u64, u64, u64) {
+        let user = self.user.pubkey();
+        (
+            token_balance(&self.svm, &self.pool.user_x(&user)),
+            token_balance(&self.svm, &self.pool.user_y(&user)),
+            token_balance(&self.svm, &self.pool.user_lp(&user)),
+        )
+    }
+}
+
+/// The program's error for an error the client returned.
+fn program_error(error: Error) -> TransactionError {
+    let Error::AnchorError(error) = error else {
+        panic!("{error:?}");
+    };
+    TransactionError::InstructionError(0, InstructionError::Custom(error.error_code_number))
+}
+
+fn curve() -> impl Strategy<Value = CurveType> {
+    prop_oneof![Just(CurveType::ConstantProduct), Just(CurveType::StableSwap)]
+}
+
+/// Reserves priced between 1:10 and 10:1.
+fn reserves() -> impl Strategy<Value = (u64, u64)> {
+    (100_000u64..100_000_000_000, 10u64..=1_000).prop_map(|(x, ratio)| (x, x * ratio / 100))
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn swap_quotes_match_the_program(
+        curve in curve(),
+        fee in 0u16..=1_000,
+        transfer_fee in 0u16..=500,
+        reserves in reserves(),
+        is_x: bool,
+        exact_in: bool,
+        amount in 1u64..1_000_000_000_000,
+    ) {
+        let mut f = Fixture::new(curve, fee, transfer_fee, reserves);
+        let pool = f.pool;
+        let user = f.user.pubkey();
+
+        // `quote_swap` prices with the same `PoolView` as the client, so
+        // agreeing with it only shows the client decodes the accounts and
+        // clock the program reads. The swap itself is the independent check.
+        let quote = f.state().view(&f.clock()).quote_swap(is_x, amount, exact_in);
+        let onchain = simulate::<SwapQuote>(&f.svm, &f.admin, pool.quote_swap(is_x, amount, exact_in));
+
+        let (x, y, _) = f.balances();
+        let quote = match quote {
+            Ok(quote) => quote,
+            Err(error) => {
+                prop_assert_eq!(onchain, Err(program_error(error)));
+                // Whatever the quote rejects, the swap rejects too.
+                let ix = match exact_in {
+                    true => pool.swap(&user, is_x, amount, 0, EXPIRATION),
+                    false => pool.swap_exact_out(&user, is_x, amount, u64::MAX, EXPIRATION),
+                };
+                prop_assert!(send(&mut f.svm, &f.user, &[ix]).is_err());
+                return Ok(());
+            }
+        };
+        prop_assert_eq!(onchain, Ok(quote));
+
+        // Executing at exactly the quoted limit moves exactly the quoted
+        // amounts, unless the user cannot afford the input.
+        let ix = match exact_in {
+            true => pool.swap(&user, is_x, amount, quote.amount_out, EXPIRATION),
+            false => pool.swap_exact_out(&user, is_x, amount, quote.amount_in, EXPIRATION),
+        };
+        let balance_in = match is_x {
+            true => x,
+            false => y,
+        };
+        if quote.amount_in > balance_in {
+            prop_assert!(send(&mut f.svm, &f.user, &[ix]).is_err());
+            return Ok(());
+        }
+        send(&mut f.svm, &f.user, &[ix]).unwrap();
+
+        let (x_after, y_after, _) = f.balances();
+        let (sent, received) = match is_x {
+            true => (x - x_after, y_after - y),
+            false => (y - y_after, x_after - x),
+        };
+        prop_assert_eq!((sent, received), (quote.amount_in, quote.amount_out));
+    }
+
+    #[test]
+    fn liquidity_quotes_match_the_program(
+        curve in curve(),
+        transfer_fee in 0u16..=500,
+        reserves in reserves(),
+        lp_share in 1u64..=100,
+    ) {
+        let mut f = Fixture::new(curve, 30, transfer_fee, reserves);
+        let pool = f.pool;
+        let user = f.user.pubkey();
+        let (x, y, lp) = f.balances();
+        let amount = (lp * lp_share / 100).max(1);
+
+        // Withdrawing part of the user's LP pays out the quote. As for swaps,
+        // executing it is what checks the quote.
+        let quote = f.state().view(&f.clock()).quote_withdraw(amount).map_err(program_error);
+        let onchain = simulate::<LiquidityQuote>(&f.svm, &f.admin, pool.quote_withdraw(amount));
+        prop_assert_eq!(&onchain, &quote);
+        let Ok(quote) = quote else {
+            let ix = pool.withdraw(&user, amount, 0, 0, EXPIRATION);
+            prop_assert!(send(&mut f.svm, &f.user, &[ix]).is_err());
+            return Ok(());
+        };
+
+        let ix = pool.withdraw(&user, amount, quote.amount_x, quote.amount_y, EXPIRATION);
+        if quote.amount_x == 0 && quote.amount_y == 0 {
+            prop_assert!(send(&mut f.svm, &f.user, &[ix]).is_err());
+            return Ok(());
+        }
+        send(&mut f.svm, &f.user, &[ix]).unwrap();
+        prop_assert_eq!(f.balances(), (x + quote.amount_x, y + quote.amount_y, lp - amount));
+
+        // Depositing the same LP back takes the quote.
+        let (x, y, lp) = f.balances();
+        let quote = f.state().view(&f.clock()).quote_deposit(amount).map_err(program_error);
+        let onchain = simulate::<LiquidityQuote>(&f.svm, &f.admin, pool.quote_deposit(amount));
+        prop_assert_eq!(&onchain, &quote);
+        let Ok(quote) = quote else {
+            return Ok(());
+        };
+        prop_assume!(quote.amount_x <= x && quote.amount_y <= y);
+
+        let ix = pool.deposit(&user, amount, quote.amount_x, quote.amount_y, EXPIRATION);
+        send(&mut f.svm, &f.user, &[ix]).unwrap();
+        prop_assert_eq!(f.balances(), (x - quote.amount_x, y - quote.amount_y, lp + amount));
+    }
+}
+
+#[test]
+fn addresses_match_the_hand_built_ones() {
+    let (mut svm, admin) = setup();
+    let mint_x = create_transfer_fee_mint(&mut svm, &admin, 100, u64::MAX);
+    let mint_y = create_mint(&mut svm, &admin);
+    let (x, y) = ((mint_x, token_program_of(&svm, &mint_x)), (mint_y, token_program_of(&svm, &mint_y)));
+    let expected = hand_built::Pool::new(7, x, y);
+    let pool = Pool::new(7, x, y);
+
+    let addresses = |pool: &Pool| -> [Pubkey; 8] {
+        [
+            pool.config,
+            pool.mint_lp,
+            pool.oracle,
+            pool.vault_x,
+            pool.vault_y,
+            pool.dead,
+            pool.locked_lp,
+            pool.tick(-60),
+        ]
+    };
+    assert_eq!(
+        addresses(&pool),
+        [
+            expected.config,
+            expected.mint_lp,
+            expected.oracle,
+            expected.vault_x,
+            expected.vault_y,
+            expected.dead,
+            expected.locked_lp,
+            expected.tick(-60),
+        ]
+    );
+
+    // Every builder matches the hand-built instruction.
+    let user = Keypair::new().pubkey();
+    assert_eq!(pool.swap(&user, true, 5, 4, EXPIRATION), expected.swap_ix(&user, true, 5, 4));
+    assert_eq!(pool.deposit(&user, 5, 4, 3, EXPIRATION), expected.deposit_ix(&user, 5, 4, 3));
+    assert_eq!(pool.withdraw(&user, 5, 4, 3, EXPIRATION), expected.withdraw_ix(&user, 5, 4, 3));
+    assert_eq!(pool.swap_sol(&user, true, 5, 4, EXPIRATION), expected.swap_sol_ix(&user, true, 5, 4));
+    assert_eq!(pool.deposit_sol(&user, 5, 4, 3, EXPIRATION), expected.deposit_sol_ix(&user, 5, 4, 3));
+    assert_eq!(pool.withdraw_sol(&user, 5, 4, 3, EXPIRATION), expected.withdraw_sol_ix(&user, 5, 4, 3));
+    assert_eq!(pool.lock(&admin.pubkey()), expected.lock_ix(&admin.pubkey()));
+}
+
+#[test]
+fn multi_pool_builders_match_the_hand_built_ones() {
+    let (mut svm, admin) = setup();
+    let mints = [
+        create_mint(&mut svm, &admin),
+        create_transfer_fee_mint(&mut svm, &admin, 100, u64::MAX),
+        create_mint(&mut svm, &admin),
+    ];
+    let tokens: Vec<_> = mints.iter().map(|mint| (*mint, token_program_of(&svm, mint))).collect();
+    let expected = hand_built::MultiPool::new(3, &tokens);
+    let pool = anchor_amm_client::MultiPool::new(3, tokens);
+    assert_eq!(
+        (pool.pool, pool.mint_lp, pool.locked_lp, &pool.vaults),
+        (expected.pool, expected.mint_lp, expected.locked_lp, &expected.vaults)
+    );
+
+    let user = Keypair::new().pubkey();
+    let weights = vec![5_000, 3_000, 2_000];
+    assert_eq!(
+        pool.initialize(&admin.pubkey(), 30, None, weights.clone()),
+        expected.initialize_ix(&admin.pubkey(), 30, None, weights)
+    );
+    assert_eq!(pool.swap(&user, (2, 0), 5, 4, EXPIRATION), expected.swap_ix(&user, 2, 0, 5, 4));
+    assert_eq!(pool.deposit(&user, 5, vec![4; 3], EXPIRATION), expected.deposit_ix(&user, 5, vec![4; 3]));
+    assert_eq!(
+        pool.withdraw_single(&user, 1, 5, 4, EXPIRATION),
+        expected.withdraw_single_ix(&user, 1, 5, 4)
+    );
+}
+
+#[test]
+fn farm_builders_match_the_hand_built_ones() {
+    let (mut svm, admin) = setup();
+    let (mint_x, mint_y) = (create_mint(&mut svm, &admin), create_mint(&mut svm, &admin));
+    let (x, y) = ((mint_x, token_program_of(&svm, &mint_x)), (mint_y, token_program_of(&svm, &mint_y)));
+    let reward_mint = create_transfer_fee_mint(&mut svm, &admin, 100, u64::MAX);
+    let reward = (reward_mint, token_program_of(&svm, &reward_mint));
+    let expected = hand_built::Farm::new(&hand_built::Pool::new(1, x, y), reward);
+    let farm = anchor_amm_client::Farm::new(&Pool::new(1, x, y), reward);
+    assert_eq!(
+        (farm.farm, farm.lp_vault, farm.reward_vault),
+        (expected.farm, expected.lp_vault, expected.reward_vault)
+    );
+
+    let user = Keypair::new().pubkey();
+    let authority = admin.pubkey();
+    assert_eq!(farm.stake_address(&user), expected.stake_address(&user));
+    assert_eq!(farm.create(&authority), expected.create_ix(&authority));
+    assert_eq!(farm.fund(&user, &authority, 5), expected.fund_ix(&user, &authority, 5));
+    assert_eq!(farm.set_emission(&authority, 5, 4), expected.set_emission_ix(&authority, 5, 4));
+    assert_eq!(farm.stake(&user, 5), expected.stake_ix(&user, 5));
+    assert_eq!(farm.unstake(&user, 5), expected.unstake_ix(&user, 5));
+    assert_eq!(farm.harvest(&user), expected.harvest_ix(&user));
+}
+
+#[test]
+fn order_builders_match_the_hand_built_ones() {
+    let (mut svm, admin) = setup();
+    let mint_x = create_transfer_fee_mint(&mut svm, &admin, 100, u64::MAX);
+    let mint_y = create_mint(&mut svm, &admin);
+    let (x, y) = ((mint_x, token_program_of(&svm, &mint_x)), (mint_y, token_program_of(&svm, &mint_y)));
+    let expected_pool = hand_built::Pool::new(1, x, y);
+    let pool = Pool::new(1, x, y);
+
+    let (maker, keeper) = (Keypair::new().pubkey(), Keypair::new().pubkey());
+    for is_x in [true, false] {
+        let expected = hand_built::Order::new(&expected_pool, &maker, 3, is_x);
+        let order = anchor_amm_client::Order::new(&pool, &maker, 3, is_x);
+        assert_eq!((order.order, order.escrow), (expected.order, expected.escrow));
+
+        assert_eq!(order.place(&expected.maker_in(), 5, 4), expected.place_ix(5, 4));
+        assert_eq!(order.fill(&keeper), expected.fill_ix(&expected_pool, &keeper));
+        assert_eq!(order.cancel(), expected.cancel_ix());
+    }
+}
@@ -2,3 +2,135 @@ use anchor_lang::prelude::*;
 
 #[constant]
 pub const SEED: &str = "anchor";
+
+#[constant]
+pub const CONFIG_SEED: &[u8] = b"config";
+
+/// Current layout of `Config`, see `Config::version`.
+#[constant]
+pub const CONFIG_VERSION: u8 = 1;
+
+#[constant]
+pub const LP_SEED: &[u8] = b"lp";
+
+#[constant]
+pub const DEAD_SEED: &[u8] = b"dead";
+
+/// LP minted to the dead PDA on the first deposit and never redeemable.
+#[constant]
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+#[constant]
+pub const ORACLE_SEED: &[u8] = b"oracle";
+
+/// Observations kept by each pool's oracle.
+#[constant]
+pub const ORACLE_CAPACITY: usize = 64;
+
+/// Minimum seconds between two oracle observations.
+#[constant]
+pub const ORACLE_INTERVAL: i64 = 30;
+
+/// Bounds on a StableSwap pool's amplification coefficient.
+#[constant]
+pub const MIN_AMP: u64 = 1;
+
+#[constant]
+pub const MAX_AMP: u64 = 1_000_000;
+
+/// Largest factor a single ramp may scale the amplification by.
+#[constant]
+pub const MAX_AMP_CHANGE: u64 = 10;
+
+/// Shortest duration of an amplification ramp, in seconds.
+#[constant]
+pub const MIN_RAMP_DURATION: i64 = 86_400;
+
+/// Sum of a weighted pool's weights, in basis points.
+#[constant]
+pub const TOTAL_WEIGHT: u16 = 10_000;
+
+/// Lowest weight either side of a weighted pool may have, so neither
+/// exponent of the invariant exceeds 99.
+#[constant]
+pub const MIN_WEIGHT: u16 = 100;
+
+/// Highest swap fee, in basis points, a pool can be created with or
+/// set to.
+#[constant]
+pub const MAX_FEE: u16 = 1_000;
+
+/// Seconds over which a pool's tracked volatility decays to zero.
+#[constant]
+pub const VOLATILITY_WINDOW: i64 = 300;
+
+/// Share of the tracked volatility, in basis points, a dynamic fee pool
+/// charges on top of its minimum fee: at 1_000 a 1% price move adds 0.1%.
+#[constant]
+pub const VOLATILITY_FEE_SHARE: u16 = 1_000;
+
+#[constant]
+pub const TICK_SEED: &[u8] = b"tick";
+
+#[constant]
+pub const POSITION_SEED: &[u8] = b"position";
+
+/// Largest tick spacing a concentrated liquidity pool may use.
+#[constant]
+pub const MAX_TICK_SPACING: u16 = 16_384;
+
+/// Bisection steps `deposit_single` spends finding the swap for curves
+/// without a closed form. What the last step leaves unmatched, at most
+/// `2^-ZAP_ITERATIONS` of the deposit, stays with the pool.
+#[constant]
+pub const ZAP_ITERATIONS: u32 = 20;
+
+/// Fee on flash loans, in basis points of the amount borrowed.
+#[constant]
+pub const FLASH_LOAN_FEE: u16 = 9;
+
+#[constant]
+pub const FACTORY_SEED: &[u8] = b"factory";
+
+#[constant]
+pub const POOL_KEY_SEED: &[u8] = b"pool";
+
+#[constant]
+pub const REGISTRY_SEED: &[u8] = b"registry";
+
+/// Fee tiers a factory may offer.
+#[constant]
+pub const MAX_FEE_TIERS: usize = 8;
+
+/// Pools listed by each registry page.
+#[constant]
+pub const REGISTRY_PAGE_SIZE: usize = 32;
+
+#[constant]
+pub const MULTI_POOL_SEED: &[u8] = b"multi_pool";
+
+/// Bounds on the number of tokens a multi-asset pool holds.
+#[constant]
+pub const MIN_MULTI_POOL_TOKENS: usize = 3;
+
+#[constant]
+pub const MAX_MULTI_POOL_TOKENS: usize = 8;
+
+#[constant]
+pub const FARM_SEED: &[u8] = b"farm";
+
+#[constant]
+pub const FARM_STAKE_SEED: &[u8] = b"farm_stake";
+
+/// Seed of the temporary wrapped SOL account `swap_sol`, `deposit_sol` and
+/// `withdraw_sol` open and close for the user within the instruction.
+#[constant]
+pub const WSOL_SEED: &[u8] = b"wsol";
+
+#[constant]
+pub const ORDER_SEED: &[u8] = b"order";
+
+/// Bounty a keeper earns for filling a limit order, in basis points of
+/// the order's output.
+#[constant]
+pub const ORDER_BOUNTY: u16 = 10;
@@ -0,0 +1,256 @@
+//! Concentrated liquidity math on Q64.64 square root prices, where tick `t`
+//! sits at price `1.0001^t`. Liquidity is a `u64` so `liquidity << 64`
+//! always fits in a `u128`.
+
+use crate::math::{mul_div, mul_div_ceil};
+
+pub const MIN_TICK: i32 = -443_636;
+pub const MAX_TICK: i32 = 443_636;
+
+const ONE: u128 = 1 << 64;
+
+/// `2^64 / sqrt(1.0001)^(2^i)` for each bit `i` of a tick's magnitude.
+const TICK_FACTORS: [u128; 19] = [
+    0xfffcb933bd6fad37,
+    0xfff97272373d4132,
+    0xfff2e50f5f656932,
+    0xffe5caca7e10e4e6,
+    0xffcb9843d60f6159,
+    0xff973b41fa98c081,
+    0xff2ea16466c96a38,
+    0xfe5dee046a99a2a8,
+    0xfcbe86c7900a88ae,
+    0xf987a7253ac41317,
+    0xf3392b0822b70005,
+    0xe7159475a2c29b74,
+    0xd097f3bdfd2022b8,
+    0xa9f746462d870fdf,
+    0x70d869a156d2a1b8,
+    0x31be135f97d08fd9,
+    0x9aa508b5b7a84e1,
+    0x5d6af8dedb8119,
+    0x2216e584f5fa,
+];
+
+/// Q64.64 square root price at `tick`, which must lie in `MIN_TICK..=MAX_TICK`.
+pub fn sqrt_price_at_tick(tick: i32) -> u128 {
+    let magnitude = tick.unsigned_abs();
+    let mut ratio = ONE;
+    for (bit, factor) in TICK_FACTORS.iter().enumerate() {
+        if magnitude & (1 << bit) != 0 {
+            ratio = (ratio * factor) >> 64;
+        }
+    }
+
+    match tick > 0 {
+        true => u128::MAX / ratio,
+        false => ratio,
+    }
+}
+
+/// Greatest tick whose square root price does not exceed `sqrt_price`.
+pub fn tick_at_sqrt_price(sqrt_price: u128) -> i32 {
+    let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+    while low < high {
+        let mid = (low + high + 1).div_euclid(2);
+        match sqrt_price_at_tick(mid) <= sqrt_price {
+            true => low = mid,
+            false => high = mid - 1,
+        }
+    }
+    low
+}
+
+/// Amount of x backing `liquidity` between square root prices `a` and `b`.
+pub fn delta_x(a: u128, b: u128, liquidity: u64, round_up: bool) -> Option<u64> {
+    let (a, b) = (a.min(b), a.max(b));
+    if a == 0 {
+        return None;
+    }
+    let numerator = (liquidity as u128) << 64;
+    let amount = match round_up {
+        true => mul_div_ceil(numerator, b - a, b)?.div_ceil(a),
+        false => mul_div(numerator, b - a, b)? / a,
+    };
+    u64::try_from(amount).ok()
+}
+
+/// Amount of y backing `liquidity` between square root prices `a` and `b`.
+pub fn delta_y(a: u128, b: u128, liquidity: u64, round_up: bool) -> Option<u64> {
+    let (a, b) = (a.min(b), a.max(b));
+    let amount = match round_up {
+        true => mul_div_ceil(liquidity as u128, b - a, ONE)?,
+        false => mul_div(liquidity as u128, b - a, ONE)?,
+    };
+    u64::try_from(amount).ok()
+}
+
+/// x and y backing `liquidity` over `[lower, upper)` at `sqrt_price`.
+pub fn amounts_for_liquidity(
+    sqrt_price: u128,
+    lower: u128,
+    upper: u128,
+    liquidity: u64,
+    round_up: bool,
+) -> Option<(u64, u64)> {
+    if sqrt_price <= lower {
+        Some((delta_x(lower, upper, liquidity, round_up)?, 0))
+    } else if sqrt_price < upper {
+        Some((
+            delta_x(sqrt_price, upper, liquidity, round_up)?,
+            delta_y(lower, sqrt_price, liquidity, round_up)?,
+        ))
+    } else {
+        Some((0, delta_y(lower, upper, liquidity, round_up)?))
+    }
+}
+
+/// Square root price after `amount` of x (`is_x`) or y is added to the
+/// active liquidity, rounded so the pool never gives out too much.
+pub fn next_sqrt_price(sqrt_price: u128, liquidity: u64, amount: u64, is_x: bool) -> Option<u128> {
+    if amount == 0 {
+        return Some(sqrt_price);
+    }
+    let liquidity = liquidity as u128;
+    match is_x {
+        true => {
+            // liquidity * p / (liquidity + amount * p), rounded up
+            let numerator = liquidity << 64;
+            let denominator = (amount as u128)
+                .checked_mul(sqrt_price)
+                .and_then(|product| numerator.checked_add(product));
+            match denominator {
+                Some(denominator) => mul_div_ceil(numerator, sqrt_price, denominator),
+                None => Some(numerator.div_ceil((numerator / sqrt_price).checked_add(amount as u128)?)),
+            }
+        }
+        false => sqrt_price.checked_add(mul_div(amount as u128, ONE, liquidity)?),
+    }
+}
+
+/// Square root price after `amount` of y (`is_x`) or x is taken from the
+/// active liquidity, rounded so the pool never gives out too much.
+pub fn next_sqrt_price_from_output(
+    sqrt_price: u128,
+    liquidity: u64,
+    amount: u64,
+    is_x: bool,
+) -> Option<u128> {
+    if amount == 0 {
+        return Some(sqrt_price);
+    }
+    let liquidity = liquidity as u128;
+    match is_x {
+        true => sqrt_price.checked_sub(mul_div_ceil(amount as u128, ONE, liquidity)?),
+        false => {
+            // liquidity * p / (liquidity - amount * p), rounded up
+            let numerator = liquidity << 64;
+            let denominator = (amount as u128)
+                .checked_mul(sqrt_price)
+                .map(|product| numerator.checked_sub(product));
+            match denominator {
+                Some(denominator) => mul_div_ceil(numerator, sqrt_price, denominator?),
+                None => Some(numerator.div_ceil((numerator / sqrt_price).checked_sub(amount as u128)?)),
+            }
+        }
+    }
+}
+
+pub struct SwapStep {
+    pub sqrt_price: u128,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee: u64,
+}
+
+/// Swaps up to `amount` of x (`is_x`) or y towards `target` within a single
+/// range of constant `liquidity`, taking `fee` basis points of the input.
+pub fn compute_swap_step(
+    sqrt_price: u128,
+    target: u128,
+    liquidity: u64,
+    amount: u64,
+    fee: u16,
+    is_x: bool,
+) -> Option<SwapStep> {
+    let fee = fee as u128;
+    let amount_less_fee = mul_div(amount as u128, 10_000 - fee, 10_000)? as u64;
+
+    let amount_to_target = match is_x {
+        true => delta_x(target, sqrt_price, liquidity, true)?,
+        false => delta_y(sqrt_price, target, liquidity, true)?,
+    };
+    let next = match amount_less_fee >= amount_to_target {
+        true => target,
+        false => next_sqrt_price(sqrt_price, liquidity, amount_less_fee, is_x)?,
+    };
+
+    let reached = next == target;
+    let (amount_in, amount_out) = match is_x {
+        true => (
+            match reached {
+                true => amount_to_target,
+                false => delta_x(next, sqrt_price, liquidity, true)?,
+            },
+            delta_y(next, sqrt_price, liquidity, false)?,
+        ),
+        false => (
+            match reached {
+                true => amount_to_target,
+                false => delta_y(sqrt_price, next, liquidity, true)?,
+            },
+            delta_x(sqrt_price, next, liquidity, false)?,
+        ),
+    };
+
+    // Whatever input is left over when the range is not exhausted is fee.
+    let fee = match reached {
+        true => mul_div_ceil(amount_in as u128, fee, 10_000 - fee)? as u64,
+        false => amount.checked_sub(amount_in)?,
+    };
+
+    Some(SwapStep {
+        sqrt_price: next,
+        amount_in,
+        amount_out,
+        fee,
+    })
+}
+
+/// Swaps towards `target` within a single range of constant `liquidity`
+/// until `amount_out` of y (`is_x`) or x is paid out, charging `fee` basis
+/// points on top of the input.
+pub fn compute_swap_step_exact_out(
+    sqrt_price: u128,
+    target: u128,
+    liquidity: u64,
+    amount_out: u64,
+    fee: u16,
+    is_x: bool,
+) -> Option<SwapStep> {
+    let fee = fee as u128;
+
+    let out_to_target = match is_x {
+        true => delta_y(target, sqrt_price, liquidity, false)?,
+        false => delta_x(sqrt_price, target, liquidity, false)?,
+    };
+    let (next, amount_out) = match amount_out >= out_to_target {
+        true => (target, out_to_target),
+        false => (
+            next_sqrt_price_from_output(sqrt_price, liquidity, amount_out, is_x)?,
+            amount_out,
+        ),
+    };
+
+    let amount_in = match is_x {
+        true => delta_x(next, sqrt_price, liquidity, true)?,
+        false => delta_y(sqrt_price, next, liquidity, true)?,
+    };
+
+    Some(SwapStep {
+        sqrt_price: next,
+        amount_in,
+        amount_out,
+        fee: mul_div_ceil(amount_in as u128, fee, 10_000 - fee)? as u64,
+    })
+}
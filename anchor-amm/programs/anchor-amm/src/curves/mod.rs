@@ -0,0 +1,203 @@
+pub mod concentrated;
+pub mod stable_swap;
+pub mod weighted;
+
+use anchor_lang::prelude::*;
+use constant_product_curve::{ConstantProduct, LiquidityPair, SwapResult};
+
+use crate::{
+    constants::ZAP_ITERATIONS,
+    error::AmmError,
+    math,
+    state::{Config, CurveType},
+};
+
+/// Prices a swap of `amount` of x (`is_x`) or y against reserves `x`/`y`
+/// on the pool's curve.
+pub fn swap(
+    config: &Config,
+    x: u64,
+    y: u64,
+    is_x: bool,
+    amount: u64,
+    now: i64,
+) -> Result<SwapResult> {
+    match config.curve {
+        CurveType::ConstantProduct => {
+            let mut curve =
+                ConstantProduct::init(x, y, x, config.fee, None).map_err(AmmError::from)?;
+            let p = match is_x {
+                true => LiquidityPair::X,
+                false => LiquidityPair::Y,
+            };
+            Ok(curve.swap(p, amount, 0).map_err(AmmError::from)?)
+        }
+        CurveType::StableSwap | CurveType::Weighted => {
+            let fee = math::mul_div_ceil(amount as u128, config.fee as u128, 10_000)
+                .ok_or(AmmError::Overflow)? as u64;
+            let (reserve_in, reserve_out) = match is_x {
+                true => (x, y),
+                false => (y, x),
+            };
+            let withdraw = match config.curve {
+                CurveType::StableSwap => {
+                    stable_swap::swap_out(config.amp(now), reserve_in, reserve_out, amount - fee)
+                }
+                _ => {
+                    let (weight_in, weight_out) = config.weights(is_x);
+                    weighted::swap_out(weight_in, weight_out, reserve_in, reserve_out, amount - fee)
+                }
+            }
+            .ok_or(AmmError::Overflow)?;
+
+            Ok(SwapResult {
+                deposit: amount,
+                withdraw,
+                fee,
+            })
+        }
+        // Crosses tick accounts, see `Swap::swap_concentrated`.
+        CurveType::Concentrated => err!(AmmError::InvalidCurve),
+    }
+}
+
+/// Prices a swap paying out exactly `amount_out` of y (`is_x`) or x from
+/// reserves `x`/`y`, rounding the required input up.
+pub fn swap_exact_out(
+    config: &Config,
+    x: u64,
+    y: u64,
+    is_x: bool,
+    amount_out: u64,
+    now: i64,
+) -> Result<SwapResult> {
+    let (reserve_in, reserve_out) = match is_x {
+        true => (x, y),
+        false => (y, x),
+    };
+    require!(amount_out < reserve_out, AmmError::InsufficientLiquidity);
+
+    let amount_in = match config.curve {
+        CurveType::ConstantProduct => math::mul_div_ceil(
+            reserve_in as u128,
+            amount_out as u128,
+            (reserve_out - amount_out) as u128,
+        )
+        .and_then(|amount| u64::try_from(amount).ok()),
+        CurveType::StableSwap => {
+            stable_swap::swap_in(config.amp(now), reserve_in, reserve_out, amount_out)
+        }
+        CurveType::Weighted => {
+            let (weight_in, weight_out) = config.weights(is_x);
+            weighted::swap_in(weight_in, weight_out, reserve_in, reserve_out, amount_out)
+        }
+        // Crosses tick accounts, see `Swap::swap_concentrated`.
+        CurveType::Concentrated => return err!(AmmError::InvalidCurve),
+    }
+    .ok_or(AmmError::Overflow)?;
+
+    // Gross the input up so the fee taken from it leaves `amount_in`.
+    let deposit = math::mul_div_ceil(amount_in as u128, 10_000, 10_000 - config.fee as u128)
+        .and_then(|amount| u64::try_from(amount).ok())
+        .ok_or(AmmError::Overflow)?;
+
+    Ok(SwapResult {
+        deposit,
+        withdraw: amount_out,
+        fee: deposit - amount_in,
+    })
+}
+
+/// Portion of `amount` of x (`is_x`) or y to swap so that the rest and the
+/// swap's output match the pool's ratio after the swap: in closed form for
+/// constant product pools, by a bounded bisection on the pool's own curve
+/// for the others.
+pub fn zap_swap_amount(
+    config: &Config,
+    x: u64,
+    y: u64,
+    is_x: bool,
+    amount: u64,
+    now: i64,
+) -> Result<u64> {
+    let (reserve_in, reserve_out) = match is_x {
+        true => (x, y),
+        false => (y, x),
+    };
+    if config.curve == CurveType::ConstantProduct {
+        return zap_constant_product(config.fee, reserve_in, amount).ok_or(AmmError::Overflow.into());
+    }
+
+    // Largest swap whose output is still no larger a share of the output
+    // reserve than what is left of the input is of the input reserve.
+    let (mut lo, mut hi) = (0, amount);
+    for _ in 0..ZAP_ITERATIONS {
+        if lo >= hi {
+            break;
+        }
+        let mid = lo + (hi - lo).div_ceil(2);
+        let out = swap(config, x, y, is_x, mid, now)?.withdraw;
+        let lhs = out as u128 * (reserve_in as u128 + mid as u128);
+        let rhs = (amount - mid) as u128 * (reserve_out - out) as u128;
+        match lhs <= rhs {
+            true => lo = mid,
+            false => hi = mid - 1,
+        }
+    }
+    Ok(lo)
+}
+
+/// Swap of `amount` into reserve `reserve_in` of a constant product pool
+/// charging `fee` that leaves the rest in the pool's ratio, the positive
+/// root of `g * s^2 + (10_000 + g) * r * s - 10_000 * amount * r = 0` with
+/// `g = 10_000 - fee`:
+/// `s = 20_000 * amount / (10_000 + g + sqrt((10_000 + g)^2 + 40_000 * g * amount / r))`,
+/// the square root taken in fixed point so its argument fits 128 bits.
+fn zap_constant_product(fee: u16, reserve_in: u64, amount: u64) -> Option<u64> {
+    let g = 10_000 - fee as u128;
+    let b = 10_000 + g;
+    let (r, amount) = (reserve_in as u128, amount as u128);
+
+    // t = (b^2 * r + 40_000 * g * amount) / r, scaled by 4^shift.
+    let n = (b * b).checked_mul(r)?.checked_add(40_000 * g * amount)?;
+    // n is at least b^2 * r, so the shift stays well below 64.
+    let shift = (127 + n.leading_zeros() - r.leading_zeros()) / 2;
+    let t = math::mul_div(n, 1 << (2 * shift), r)?;
+
+    let denominator = (b << shift).checked_add(math::sqrt(t))?;
+    let swapped = math::mul_div(20_000 * amount, 1 << shift, denominator)?;
+    u64::try_from(swapped.min(amount)).ok()
+}
+
+/// LP minted for the first deposit of `x` and `y`, before the minimum
+/// liquidity is locked.
+pub fn initial_liquidity(config: &Config, x: u64, y: u64, now: i64) -> Result<u64> {
+    let liquidity = match config.curve {
+        CurveType::ConstantProduct => math::sqrt(x as u128 * y as u128),
+        CurveType::StableSwap => {
+            stable_swap::compute_d(config.amp(now), x, y).ok_or(AmmError::Overflow)?
+        }
+        CurveType::Weighted => {
+            weighted::compute_invariant(config.weight_x, x, y).ok_or(AmmError::Overflow)?
+        }
+        CurveType::Concentrated => return err!(AmmError::InvalidCurve),
+    };
+    Ok(u64::try_from(liquidity).map_err(|_| AmmError::Overflow)?)
+}
+
+/// Q64.64 spot prices of x in y and of y in x, if the pool has a price.
+pub fn spot_prices(config: &Config, x: u64, y: u64, now: i64) -> Option<(u128, u128)> {
+    match config.curve {
+        CurveType::Concentrated => {
+            let price_x = math::mul_div(config.sqrt_price, config.sqrt_price, 1 << 64)?;
+            Some((price_x, math::mul_div(1 << 64, 1 << 64, price_x)?))
+        }
+        _ if x == 0 || y == 0 => None,
+        CurveType::ConstantProduct => Some((
+            ((y as u128) << 64) / x as u128,
+            ((x as u128) << 64) / y as u128,
+        )),
+        CurveType::StableSwap => stable_swap::spot_prices(config.amp(now), x, y),
+        CurveType::Weighted => weighted::spot_prices(config.weight_x, x, y),
+    }
+}
@@ -0,0 +1,177 @@
+//! Two-coin StableSwap invariant:
+//! `ann * (x + y) + d = ann * d + d^3 / (4 * x * y)` with `ann = amp * 2`.
+//!
+//! Every function returns `None` when an intermediate overflows or the
+//! iteration fails to converge.
+
+use crate::math::mul_div;
+
+const N_COINS: u128 = 2;
+const MAX_ITERATIONS: usize = 255;
+
+/// `d^3 / (4 * x * y)`, the product term of the invariant.
+fn d_product(d: u128, x: u128, y: u128) -> Option<u128> {
+    mul_div(mul_div(d, d, x.checked_mul(N_COINS)?)?, d, y.checked_mul(N_COINS)?)
+}
+
+/// Invariant `d` of reserves `x` and `y` at amplification `amp`.
+pub fn compute_d(amp: u64, x: u64, y: u64) -> Option<u128> {
+    let (x, y) = (x as u128, y as u128);
+    let sum = x + y;
+    if sum == 0 {
+        return Some(0);
+    }
+    if x == 0 || y == 0 {
+        return None;
+    }
+
+    let ann = amp as u128 * N_COINS;
+    let mut d = sum;
+    for _ in 0..MAX_ITERATIONS {
+        let d_p = d_product(d, x, y)?;
+        let prev = d;
+        // d = (ann * sum + 2 * d_p) * d / ((ann - 1) * d + 3 * d_p)
+        let numerator = ann.checked_mul(sum)?.checked_add(d_p.checked_mul(N_COINS)?)?;
+        let denominator = (ann - 1)
+            .checked_mul(d)?
+            .checked_add(d_p.checked_mul(N_COINS + 1)?)?;
+        d = mul_div(numerator, d, denominator)?;
+        if d.abs_diff(prev) <= 1 {
+            return Some(d);
+        }
+    }
+    None
+}
+
+/// Balance of the other side that keeps invariant `d` when one side holds `x`.
+pub fn compute_y(amp: u64, x: u64, d: u128) -> Option<u128> {
+    let x = x as u128;
+    if x == 0 {
+        return None;
+    }
+
+    let ann = amp as u128 * N_COINS;
+    // c = d^3 / (4 * x * ann), b = x + d / ann
+    let c = mul_div(mul_div(d, d, x.checked_mul(N_COINS)?)?, d, ann.checked_mul(N_COINS)?)?;
+    let b = x.checked_add(d / ann)?;
+
+    let mut y = d;
+    for _ in 0..MAX_ITERATIONS {
+        let prev = y;
+        // y = (y^2 + c) / (2y + b - d)
+        let numerator = y.checked_mul(y)?.checked_add(c)?;
+        let denominator = y.checked_mul(2)?.checked_add(b)?.checked_sub(d)?;
+        y = numerator.checked_div(denominator)?;
+        if y.abs_diff(prev) <= 1 {
+            return Some(y);
+        }
+    }
+    None
+}
+
+/// Amount of y paid out for `amount_in` of x added to reserves `x` and `y`,
+/// rounded down by one unit in the pool's favour.
+pub fn swap_out(amp: u64, x: u64, y: u64, amount_in: u64) -> Option<u64> {
+    let d = compute_d(amp, x, y)?;
+    let new_y = compute_y(amp, x.checked_add(amount_in)?, d)?;
+    let out = (y as u128).saturating_sub(new_y).saturating_sub(1);
+    u64::try_from(out).ok()
+}
+
+/// Amount of x required to take `amount_out` of y from reserves `x` and
+/// `y`, rounded up by one unit in the pool's favour.
+pub fn swap_in(amp: u64, x: u64, y: u64, amount_out: u64) -> Option<u64> {
+    let d = compute_d(amp, x, y)?;
+    let new_y = y.checked_sub(amount_out)?.checked_sub(1)?;
+    let new_x = compute_y(amp, new_y, d)?;
+    let amount_in = new_x.checked_sub(x as u128)?.checked_add(1)?;
+    u64::try_from(amount_in).ok()
+}
+
+/// Q64.64 marginal prices of x in y and of y in x.
+pub fn spot_prices(amp: u64, x: u64, y: u64) -> Option<(u128, u128)> {
+    let d = compute_d(amp, x, y)?;
+    let ann = amp as u128 * N_COINS;
+    let (x, y) = (x as u128, y as u128);
+    let d_p = d_product(d, x, y)?;
+
+    // -dy/dx = y * (ann * x + d_p) / (x * (ann * y + d_p))
+    let weight_x = ann.checked_mul(x)?.checked_add(d_p)?;
+    let weight_y = ann.checked_mul(y)?.checked_add(d_p)?;
+    Some((
+        mul_div(mul_div(y, 1 << 64, x)?, weight_x, weight_y)?,
+        mul_div(mul_div(x, 1 << 64, y)?, weight_y, weight_x)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compute_d_known_values() {
+        assert_eq!(compute_d(100, 0, 0), Some(0));
+        assert_eq!(compute_d(100, 0, 1_000), None);
+
+        // Balanced reserves sum to the invariant at any amplification.
+        for amp in [1, 100, 1_000_000] {
+            assert_eq!(compute_d(amp, 1_000_000, 1_000_000), Some(2_000_000));
+            assert_eq!(compute_d(amp, 1, 1), Some(2));
+            assert_eq!(compute_d(amp, u64::MAX, u64::MAX), Some(2 * u64::MAX as u128));
+        }
+
+        // ann * (x + y) + d = ann * d + d^3 / (4 * x * y) at amp 100 for
+        // 1_000_000 and 2_000_000 holds at d = 2_998_146.985...
+        let d = compute_d(100, 1_000_000, 2_000_000).unwrap();
+        assert!(d.abs_diff(2_998_147) <= 1, "d = {d}");
+
+        // Imbalance costs more of the invariant the lower the amplification.
+        let flat = compute_d(1_000_000, 1_000_000, 2_000_000).unwrap();
+        let curved = compute_d(1, 1_000_000, 2_000_000).unwrap();
+        assert!(curved < d && d < flat && flat <= 3_000_000);
+    }
+
+    #[test]
+    fn compute_y_inverts_compute_d() {
+        let cases: [(u64, u64, u64); 5] = [
+            (1, 1_000_000, 2_000_000),
+            (100, 1_000_000, 1_000_000),
+            (100, 1_000, 9_000_000),
+            (1_000_000, 5, 7),
+            (100, u64::MAX / 4, u64::MAX / 2),
+        ];
+        for (amp, x, y) in cases {
+            let d = compute_d(amp, x, y).unwrap();
+            let y_back = compute_y(amp, x, d).unwrap();
+            assert!(y_back.abs_diff(y as u128) <= 2, "amp {amp}, x {x}: {y_back} for {y}");
+        }
+        assert_eq!(compute_y(100, 0, 2_000), None);
+
+        // Past 2^64 the iteration's square overflows, which fails the swap.
+        let d = compute_d(100, u64::MAX, u64::MAX).unwrap();
+        assert_eq!(compute_y(100, u64::MAX, d), None);
+    }
+
+    #[test]
+    fn swaps_round_in_the_pools_favour() {
+        // Near the peg a high amplification trades one for one, give or take
+        // the units the pool rounds by in its favour.
+        assert_eq!(swap_out(1_000_000, 1_000_000, 1_000_000, 1_000), Some(999));
+        assert_eq!(swap_in(1_000_000, 1_000_000, 1_000_000, 1_000), Some(1_002));
+
+        for (amp, x, y, amount) in [(1, 1_000_000, 2_000_000, 50_000), (100, 1_000, 9_000_000, 1_000), (100, 1, 1, 1)] {
+            let d = compute_d(amp, x, y).unwrap();
+            let out = swap_out(amp, x, y, amount).unwrap();
+            // What is left keeps at least the invariant.
+            assert!(compute_d(amp, x + amount, y - out).unwrap() >= d);
+
+            if out == 0 {
+                continue;
+            }
+            assert!(swap_in(amp, x, y, out).unwrap() >= amount - 1);
+        }
+
+        assert_eq!(swap_in(100, 1_000, 1_000, 1_000), None);
+        assert_eq!(swap_out(100, u64::MAX, u64::MAX, 1), None);
+    }
+}
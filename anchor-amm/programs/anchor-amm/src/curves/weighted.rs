@@ -0,0 +1,387 @@
+//! Weighted product invariant: `x^w_x * y^w_y` with the weights in basis
+//! points summing to 10_000, so a 50/50 pool prices like the constant
+//! product. Multi-asset pools use its n-token form, `Π b_i^w_i`, and swap
+//! between any two of their tokens with the same two-token formulas.
+//!
+//! Powers go through base-2 logarithms and exponentials in fixed point.
+//! Their error is far below `POW_MARGIN`, which every power is rounded by
+//! in the pool's favour. Every function returns `None` when an
+//! intermediate overflows.
+
+use crate::math::{mul_div, mul_div_ceil};
+
+const ONE: u128 = 1 << 64;
+const TOTAL_WEIGHT: u128 = 10_000;
+
+/// Powers are moved by `1 / 2^POW_MARGIN` of themselves, plus one unit, in
+/// the pool's favour.
+const POW_MARGIN: u32 = 48;
+
+/// `2^(2^-i)` for `i` in `1..=64`, in Q1.63.
+const EXP2_TABLE: [u128; 64] = [
+    0xb504f333f9de6484,
+    0x9837f0518db8a96f,
+    0x8b95c1e3ea8bd6e6,
+    0x85aac367cc487b14,
+    0x82cd8698ac2ba1d7,
+    0x8164d1f3bc030773,
+    0x80b1ed4fd999ab6c,
+    0x8058d7d2d5e5f6b0,
+    0x802c6436d0e04f50,
+    0x8016302f17467628,
+    0x800b179c82028fd0,
+    0x80058baf7fee3b5d,
+    0x8002c5d00fdcfcb6,
+    0x800162e61bed4a48,
+    0x8000b17292f702a3,
+    0x800058b92abbae02,
+    0x80002c5c8dade4d7,
+    0x8000162e44eaf636,
+    0x80000b1721fa7c18,
+    0x8000058b90de7e4c,
+    0x800002c5c8678f36,
+    0x80000162e431db9f,
+    0x800000b1721872d0,
+    0x80000058b90c1aa8,
+    0x8000002c5c8605a4,
+    0x800000162e4300e6,
+    0x8000000b17217ff8,
+    0x800000058b90bfdd,
+    0x80000002c5c85fe6,
+    0x8000000162e42ff1,
+    0x80000000b17217f8,
+    0x8000000058b90bfc,
+    0x800000002c5c85fd,
+    0x80000000162e42fe,
+    0x800000000b17217f,
+    0x80000000058b90bf,
+    0x8000000002c5c85f,
+    0x800000000162e42f,
+    0x8000000000b17217,
+    0x800000000058b90b,
+    0x80000000002c5c85,
+    0x8000000000162e42,
+    0x80000000000b1721,
+    0x8000000000058b90,
+    0x800000000002c5c8,
+    0x80000000000162e4,
+    0x800000000000b172,
+    0x80000000000058b9,
+    0x8000000000002c5c,
+    0x800000000000162e,
+    0x8000000000000b17,
+    0x800000000000058b,
+    0x80000000000002c5,
+    0x8000000000000162,
+    0x80000000000000b1,
+    0x8000000000000058,
+    0x800000000000002c,
+    0x8000000000000016,
+    0x800000000000000b,
+    0x8000000000000005,
+    0x8000000000000002,
+    0x8000000000000001,
+    0x8000000000000000,
+    0x8000000000000000,
+];
+
+/// Q64.64 base-2 logarithm of `n`, rounded down.
+fn log2(n: u128) -> Option<u128> {
+    if n == 0 {
+        return None;
+    }
+
+    // Normalize to a Q1.63 mantissa in [1, 2), then square it once per
+    // fractional bit: each time it reaches 2 the bit is set.
+    let int = 127 - n.leading_zeros();
+    let mut mantissa = match int >= 63 {
+        true => n >> (int - 63),
+        false => n << (63 - int),
+    };
+    let mut log = (int as u128) << 64;
+    for bit in (0..64).rev() {
+        mantissa = (mantissa * mantissa) >> 63;
+        if mantissa >= 1 << 64 {
+            mantissa >>= 1;
+            log |= 1 << bit;
+        }
+    }
+    Some(log)
+}
+
+/// `2^x` of a Q64.64 `x`, in Q64.64, rounded down.
+fn exp2(x: u128) -> Option<u128> {
+    let int = x >> 64;
+    if int >= 64 {
+        return None;
+    }
+
+    let mut result = 1u128 << 63;
+    for (i, factor) in EXP2_TABLE.iter().enumerate() {
+        if x & (1 << (63 - i)) != 0 {
+            result = (result * factor) >> 63;
+        }
+    }
+    Some(result << (int + 1))
+}
+
+/// `2^-x` of a Q64.64 `x`, in Q64.64, rounded down.
+fn exp2_neg(x: u128) -> Option<u128> {
+    let int = x >> 64;
+    if int >= 64 {
+        return Some(0);
+    }
+    let frac = exp2(x & (ONE - 1))?;
+    Some((u128::MAX / frac) >> int)
+}
+
+/// `(num / den)^(exp_num / exp_den)` in Q64.64, rounded down.
+fn power(num: u128, den: u128, exp_num: u128, exp_den: u128) -> Option<u128> {
+    match num >= den {
+        true => exp2(mul_div(log2(num)?.saturating_sub(log2(den)?), exp_num, exp_den)?),
+        false => exp2_neg(mul_div(log2(den)?.saturating_sub(log2(num)?), exp_num, exp_den)?),
+    }
+}
+
+/// `power` moved up by the margin.
+fn round_up(power: u128) -> Option<u128> {
+    power.checked_add(power >> POW_MARGIN)?.checked_add(1)
+}
+
+/// `power` moved down by the margin.
+fn round_down(power: u128) -> u128 {
+    power.saturating_sub(power >> POW_MARGIN).saturating_sub(1)
+}
+
+/// Fee of `fee` basis points on the share of `amount` a single-token
+/// deposit or withdrawal in effect swaps against the pool's other tokens,
+/// `1 - weight`, rounded up.
+fn single_token_fee(weight: u16, amount: u128, fee: u16) -> Option<u128> {
+    let rate = fee as u128 * (TOTAL_WEIGHT - weight as u128);
+    mul_div_ceil(amount, rate, 10_000 * TOTAL_WEIGHT)
+}
+
+/// Invariant `x^w_x * y^w_y` of reserves `x` and `y` at `weight_x`,
+/// rounded down.
+pub fn compute_invariant(weight_x: u16, x: u64, y: u64) -> Option<u128> {
+    compute_multi_invariant(&[(weight_x, x), (TOTAL_WEIGHT as u16 - weight_x, y)])
+}
+
+/// Invariant `Π b_i^w_i` of a pool's `(weight, balance)` pairs, rounded
+/// down.
+pub fn compute_multi_invariant(tokens: &[(u16, u64)]) -> Option<u128> {
+    let mut log = 0u128;
+    for &(weight, balance) in tokens {
+        log = log.checked_add(log2(balance as u128)?.checked_mul(weight as u128)?)?;
+    }
+    Some(exp2(log / TOTAL_WEIGHT)? >> 64)
+}
+
+/// Amount paid out of `reserve_out` for `amount_in` added to `reserve_in`,
+/// rounded down in the pool's favour:
+/// `reserve_out * (1 - (reserve_in / (reserve_in + amount_in))^(weight_in / weight_out))`.
+pub fn swap_out(
+    weight_in: u16,
+    weight_out: u16,
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+) -> Option<u64> {
+    let reserve_in = reserve_in as u128;
+    let grown = reserve_in.checked_add(amount_in as u128)?;
+    let power = round_up(power(reserve_in, grown, weight_in as u128, weight_out as u128)?)?;
+
+    let out = mul_div(reserve_out as u128, ONE.saturating_sub(power), ONE)?;
+    u64::try_from(out.saturating_sub(1)).ok()
+}
+
+/// Amount added to `reserve_in` to take `amount_out` of `reserve_out`,
+/// rounded up in the pool's favour:
+/// `reserve_in * ((reserve_out / (reserve_out - amount_out))^(weight_out / weight_in) - 1)`.
+pub fn swap_in(
+    weight_in: u16,
+    weight_out: u16,
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_out: u64,
+) -> Option<u64> {
+    let reserve_out = reserve_out as u128;
+    let rest = reserve_out.checked_sub(amount_out as u128)?;
+    let power = round_up(power(reserve_out, rest, weight_out as u128, weight_in as u128)?)?;
+
+    let amount_in = mul_div_ceil(reserve_in as u128, power.checked_sub(ONE)?, ONE)?.checked_add(1)?;
+    u64::try_from(amount_in).ok()
+}
+
+/// LP minted for `amount_in` added to `balance` of a token at `weight`,
+/// against `supply` LP, rounded down in the pool's favour:
+/// `supply * (((balance + amount_in - fee) / balance)^weight - 1)`.
+pub fn deposit_single_lp(
+    weight: u16,
+    balance: u64,
+    supply: u64,
+    amount_in: u64,
+    fee: u16,
+) -> Option<u64> {
+    let balance = balance as u128;
+    let fee = single_token_fee(weight, amount_in as u128, fee)?;
+    let grown = balance.checked_add(amount_in as u128 - fee)?;
+    let power = round_down(power(grown, balance, weight as u128, TOTAL_WEIGHT)?);
+
+    let lp = mul_div(supply as u128, power.saturating_sub(ONE), ONE)?;
+    u64::try_from(lp.saturating_sub(1)).ok()
+}
+
+/// Amount of a token at `weight` paid out of `balance` for `lp` of
+/// `supply` LP burnt, rounded down in the pool's favour:
+/// `balance * (1 - ((supply - lp) / supply)^(1 / weight))`, less the fee.
+pub fn withdraw_single_out(
+    weight: u16,
+    balance: u64,
+    supply: u64,
+    lp: u64,
+    fee: u16,
+) -> Option<u64> {
+    let supply = supply as u128;
+    let rest = supply.checked_sub(lp as u128)?;
+    let power = round_up(power(rest, supply, TOTAL_WEIGHT, weight as u128)?)?;
+
+    let out = mul_div(balance as u128, ONE.saturating_sub(power), ONE)?;
+    let fee = single_token_fee(weight, out, fee)?;
+    u64::try_from((out - fee).saturating_sub(1)).ok()
+}
+
+/// Q64.64 marginal prices of x in y and of y in x:
+/// `(y / w_y) / (x / w_x)` and its inverse.
+pub fn spot_prices(weight_x: u16, x: u64, y: u64) -> Option<(u128, u128)> {
+    let (weight_x, weight_y) = (weight_x as u128, TOTAL_WEIGHT - weight_x as u128);
+    let (x, y) = (x as u128, y as u128);
+    Some((
+        mul_div(mul_div(y, ONE, x)?, weight_x, weight_y)?,
+        mul_div(mul_div(x, ONE, y)?, weight_y, weight_x)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Q64.64 of `value`, as close as an `f64` gets.
+    fn q64(value: f64) -> f64 {
+        value * ONE as f64
+    }
+
+    #[test]
+    fn log2_known_values() {
+        assert_eq!(log2(0), None);
+        assert_eq!(log2(1), Some(0));
+        assert_eq!(log2(2), Some(ONE));
+        assert_eq!(log2(1 << 100), Some(100 << 64));
+        assert!(log2(u128::MAX).unwrap() < 128 << 64);
+
+        // log2(3) to 64 fractional bits, rounded down.
+        let exact = 0x1_95c0_1a39_fbd6_879f;
+        let log = log2(3).unwrap();
+        assert!(log <= exact && exact - log < 64, "log2(3) = {log:#x}");
+    }
+
+    #[test]
+    fn exp2_known_values() {
+        assert_eq!(exp2(0), Some(ONE));
+        assert_eq!(exp2(ONE), Some(2 * ONE));
+        assert_eq!(exp2(63 << 64), Some(1 << 127));
+        assert_eq!(exp2(64 << 64), None);
+
+        // sqrt(2) to 64 fractional bits is 0x1_6a09_e667_f3bc_c908_b2..., so
+        // rounding down may only lose the bits the table does not hold.
+        let root = exp2(ONE / 2).unwrap();
+        assert!(root <= 0x1_6a09_e667_f3bc_c908 && 0x1_6a09_e667_f3bc_c908 - root < 4);
+    }
+
+    #[test]
+    fn exp2_neg_known_values() {
+        assert_eq!(exp2_neg(0), Some(ONE - 1));
+        assert_eq!(exp2_neg(ONE), Some(ONE / 2 - 1));
+        assert_eq!(exp2_neg(64 << 64), Some(0));
+        assert_eq!(exp2_neg(u128::MAX), Some(0));
+
+        // 2^x * 2^-x never exceeds one, and falls short by little more than
+        // 2^x times the unit 2^-x is rounded to.
+        for x in [1, ONE / 3, ONE / 2, 5 * ONE + 12_345, 40 * ONE + ONE / 7] {
+            let (up, down) = (exp2(x).unwrap(), exp2_neg(x).unwrap());
+            let product = mul_div(up, down, ONE).unwrap();
+            assert!(product <= ONE && ONE - product <= (up >> 64) + 1, "x = {x:#x}");
+        }
+    }
+
+    #[test]
+    fn power_error_stays_within_the_margin() {
+        let cases: [(u128, u128, u128, u128); 12] = [
+            (4, 1, 1, 2),
+            (1, 4, 1, 2),
+            (3, 2, 8_000, 2_000),
+            (2, 3, 2_000, 8_000),
+            (1_000_001, 1_000_000, 9_999, 1),
+            (1_000_000, 1_000_001, 9_999, 1),
+            (101, 100, 9_900, 100),
+            (100, 101, 9_900, 100),
+            (2, 1, 1, 9_999),
+            (1, 2, 1, 9_999),
+            (u64::MAX as u128, u64::MAX as u128 - 1, 5_000, 5_000),
+            (u64::MAX as u128, 1, 1, 2),
+        ];
+        for (num, den, exp_num, exp_den) in cases {
+            let ratio = match num >= den {
+                true => (num - den) as f64 / den as f64,
+                false => -((den - num) as f64 / den as f64),
+            };
+            let exact = q64((exp_num as f64 / exp_den as f64 * ratio.ln_1p()).exp());
+            let power = power(num, den, exp_num, exp_den).unwrap() as f64;
+            let margin = exact / (1u64 << POW_MARGIN) as f64 + 1.0;
+            assert!((power - exact).abs() <= margin, "({num} / {den})^({exp_num} / {exp_den})");
+        }
+
+        assert_eq!(power(4, 1, 1, 2), Some(2 * ONE));
+        assert_eq!(power(2, 1, 9_999, 1), None);
+    }
+
+    /// `reserve_out * (1 - (reserve_in / (reserve_in + amount_in))^(weight_in / weight_out))`
+    /// in floating point.
+    fn exact_out(weight_in: u16, weight_out: u16, reserve_in: u64, reserve_out: u64, amount_in: u64) -> f64 {
+        let exponent = weight_in as f64 / weight_out as f64;
+        -(-exponent * (amount_in as f64 / reserve_in as f64).ln_1p()).exp_m1() * reserve_out as f64
+    }
+
+    #[test]
+    fn swaps_round_in_the_pools_favour() {
+        let cases: [(u16, u16, u64, u64, u64); 8] = [
+            (5_000, 5_000, 1_000_000, 1_000_000, 1_000),
+            (8_000, 2_000, 1_000_000, 4_000_000, 250_000),
+            (2_000, 8_000, 4_000_000, 1_000_000, 3),
+            (1, 9_999, 1_000_000, 1_000_000, 1_000_000),
+            (9_999, 1, 1_000_000, 1_000_000, 1),
+            (5_000, 5_000, 1, 1, 1),
+            (5_000, 5_000, u64::MAX, u64::MAX, u64::MAX / 2),
+            (100, 9_900, u64::MAX / 3, u64::MAX, u64::MAX / 3),
+        ];
+        for (weight_in, weight_out, reserve_in, reserve_out, amount_in) in cases {
+            let exact = exact_out(weight_in, weight_out, reserve_in, reserve_out, amount_in);
+            let out = swap_out(weight_in, weight_out, reserve_in, reserve_out, amount_in).unwrap();
+            assert!((out as f64) <= exact, "{out} out for {amount_in}, exactly {exact}");
+            assert!(exact - out as f64 <= exact / (1u64 << 40) as f64 + 2.0, "{out} out, exactly {exact}");
+
+            if out == 0 {
+                continue;
+            }
+            let amount = swap_in(weight_in, weight_out, reserve_in, reserve_out, out).unwrap();
+            assert!(exact_out(weight_in, weight_out, reserve_in, reserve_out, amount) >= out as f64);
+        }
+    }
+
+    #[test]
+    fn swaps_fail_instead_of_overflowing() {
+        assert_eq!(swap_out(5_000, 5_000, 1, u64::MAX, u64::MAX), Some(u64::MAX - 2));
+        assert_eq!(swap_in(5_000, 5_000, u64::MAX, u64::MAX, u64::MAX - 1), None);
+        assert_eq!(swap_in(5_000, 5_000, 1_000, 1_000, 1_000), None);
+    }
+}
@@ -39,6 +39,66 @@ pub enum AmmError {
     InsufficientBalance,
     #[msg("Zero balance.")]
     ZeroBalance,
+    #[msg("Not enough oracle history for this window.")]
+    OracleHistoryTooShort,
+    #[msg("Not supported by this pool's curve.")]
+    InvalidCurve,
+    #[msg("Amplification coefficient out of range.")]
+    InvalidAmp,
+    #[msg("Invalid amplification ramp.")]
+    InvalidRamp,
+    #[msg("Invalid tick account.")]
+    InvalidTick,
+    #[msg("Invalid tick range.")]
+    InvalidTickRange,
+    #[msg("Price out of range.")]
+    InvalidPrice,
+    #[msg("Not enough liquidity to fill the swap.")]
+    InsufficientLiquidity,
+    #[msg("Position still holds liquidity or fees.")]
+    PositionNotEmpty,
+    #[msg("Mint has an unsupported Token-2022 extension.")]
+    UnsupportedMint,
+    #[msg("A flash loan is outstanding.")]
+    FlashLoanActive,
+    #[msg("Flash loan is not repaid later in the transaction.")]
+    FlashLoanNotRepaid,
+    #[msg("No flash loan to repay.")]
+    NoFlashLoan,
+    #[msg("Invalid swap route.")]
+    InvalidRoute,
+    #[msg("Fee is not one of the factory's tiers.")]
+    InvalidFeeTier,
+    #[msg("Pool mints must be sorted, mint_x before mint_y.")]
+    UnsortedMints,
+    #[msg("Swaps are paused.")]
+    SwapsPaused,
+    #[msg("Deposits are paused.")]
+    DepositsPaused,
+    #[msg("Withdrawals are paused.")]
+    WithdrawalsPaused,
+    #[msg("No authority transfer is pending.")]
+    NoPendingAuthority,
+    #[msg("Invalid pool weights.")]
+    InvalidWeight,
+    #[msg("A multi-asset pool holds 3 to 8 distinct tokens.")]
+    InvalidPoolTokens,
+    #[msg("Token accounts do not match the pool's tokens.")]
+    InvalidTokenAccounts,
+    #[msg("Emission schedule must end in the future.")]
+    InvalidEmissionSchedule,
+    #[msg("Reward vault cannot cover the emission schedule.")]
+    InsufficientRewards,
+    #[msg("Neither of the pool's tokens is wrapped SOL.")]
+    NotNativePool,
+    #[msg("Accounts do not match the order.")]
+    InvalidOrder,
+    #[msg("The pool price has not reached the order's limit.")]
+    OrderPriceNotReached,
+    #[msg("A farm cannot pay rewards in the LP token it stakes.")]
+    InvalidRewardMint,
+    #[msg("The config is already at the current version.")]
+    ConfigUpToDate,
 }
 
 impl From<CurveError> for AmmError {
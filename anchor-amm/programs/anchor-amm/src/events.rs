@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Config, CurveType};
+
+// Reserves in these events exclude accrued protocol fees, as in
+// `Config::reserves`, so consecutive events of a pool chain together.
+
+/// A pool was initialized, directly or through the factory.
+#[event]
+pub struct PoolCreated {
+    pub config: Pubkey,
+    pub mint_x: Pubkey,
+    pub mint_y: Pubkey,
+    pub fee: u16,
+    pub curve: CurveType,
+    pub authority: Option<Pubkey>,
+}
+
+impl PoolCreated {
+    pub fn new(key: Pubkey, config: &Config) -> Self {
+        Self {
+            config: key,
+            mint_x: config.mint_x,
+            mint_y: config.mint_y,
+            fee: config.fee,
+            curve: config.curve,
+            authority: config.authority,
+        }
+    }
+}
+
+/// A config was migrated to `version`. A `fee` below `legacy_fee` was
+/// capped at `MAX_FEE`.
+#[event]
+pub struct ConfigMigrated {
+    pub config: Pubkey,
+    pub version: u8,
+    pub legacy_fee: u16,
+    pub fee: u16,
+}
+
+/// Emitted by `deposit` and `deposit_single`. The amounts are what the user
+/// sent, transfer fees included, and `lp` what they were minted.
+#[event]
+pub struct LiquidityAdded {
+    pub config: Pubkey,
+    pub user: Pubkey,
+    pub amount_x: u64,
+    pub amount_y: u64,
+    pub lp: u64,
+    pub reserve_x_before: u64,
+    pub reserve_y_before: u64,
+    pub lp_supply_before: u64,
+    pub reserve_x_after: u64,
+    pub reserve_y_after: u64,
+    pub lp_supply_after: u64,
+}
+
+/// Emitted by `withdraw` and `withdraw_single`. The amounts are what left
+/// the vaults, before transfer fees, for `lp` burned.
+#[event]
+pub struct LiquidityRemoved {
+    pub config: Pubkey,
+    pub user: Pubkey,
+    pub amount_x: u64,
+    pub amount_y: u64,
+    pub lp: u64,
+    pub reserve_x_before: u64,
+    pub reserve_y_before: u64,
+    pub lp_supply_before: u64,
+    pub reserve_x_after: u64,
+    pub reserve_y_after: u64,
+    pub lp_supply_after: u64,
+}
+
+/// Emitted by every swap, once per hop of a route. `amount_in` of x
+/// (`is_x`) or y was sent by the user, `amount_out` of the other left the
+/// vault, and `fee` is the swap fee charged on the input at `fee_rate`
+/// basis points, which dynamic fee pools reprice as they go.
+#[event]
+pub struct Swapped {
+    pub config: Pubkey,
+    pub user: Pubkey,
+    pub is_x: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee: u64,
+    pub fee_rate: u16,
+    pub reserve_x_before: u64,
+    pub reserve_y_before: u64,
+    pub reserve_x_after: u64,
+    pub reserve_y_after: u64,
+}
+
+/// The swap fee, its mode or the protocol's share of it changed. Dynamic
+/// fee repricing on swaps is reported by `Swapped` instead.
+#[event]
+pub struct FeeUpdated {
+    pub config: Pubkey,
+    pub fee: u16,
+    pub dynamic_fee: bool,
+    pub min_fee: u16,
+    pub max_fee: u16,
+    pub protocol_fee: u16,
+}
+
+impl FeeUpdated {
+    pub fn new(key: Pubkey, config: &Config) -> Self {
+        Self {
+            config: key,
+            fee: config.fee,
+            dynamic_fee: config.dynamic_fee,
+            min_fee: config.min_fee,
+            max_fee: config.max_fee,
+            protocol_fee: config.protocol_fee,
+        }
+    }
+}
+
+/// The pool was locked, unlocked or its pause flags changed.
+#[event]
+pub struct LockUpdated {
+    pub config: Pubkey,
+    pub locked: bool,
+    pub swaps_paused: bool,
+    pub deposits_paused: bool,
+    pub withdrawals_paused: bool,
+}
+
+impl LockUpdated {
+    pub fn new(key: Pubkey, config: &Config) -> Self {
+        Self {
+            config: key,
+            locked: config.locked,
+            swaps_paused: config.swaps_paused,
+            deposits_paused: config.deposits_paused,
+            withdrawals_paused: config.withdrawals_paused,
+        }
+    }
+}
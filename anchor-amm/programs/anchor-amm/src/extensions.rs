@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    spl_token_2022::{
+        extension::{
+            transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType,
+            StateWithExtensions,
+        },
+        state::Mint,
+    },
+    Mint as MintAccount,
+};
+
+use crate::error::AmmError;
+
+/// Token-2022 mint extensions a pool accepts. The rest, such as transfer
+/// hooks, permanent delegates or non-transferable mints, could move or
+/// freeze vault funds outside the pool's accounting.
+const SUPPORTED_EXTENSIONS: [ExtensionType; 5] = [
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::InterestBearingConfig,
+    ExtensionType::MintCloseAuthority,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+];
+
+pub fn check_mint(mint: &InterfaceAccount<MintAccount>) -> Result<()> {
+    let info = mint.to_account_info();
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<Mint>::unpack(&data)?;
+    for extension in state.get_extension_types()? {
+        require!(
+            SUPPORTED_EXTENSIONS.contains(&extension),
+            AmmError::UnsupportedMint
+        );
+    }
+    Ok(())
+}
+
+/// Fee withheld from a transfer of `amount` of `mint` this epoch; zero for
+/// mints without the transfer fee extension.
+pub fn transfer_fee(mint: &InterfaceAccount<MintAccount>, amount: u64) -> Result<u64> {
+    let info = mint.to_account_info();
+    let data = info.try_borrow_data()?;
+    transfer_fee_at(&data, Clock::get()?.epoch, amount)
+}
+
+/// Amount of `mint` to send for `received` to arrive after the transfer fee.
+pub fn amount_before_fee(mint: &InterfaceAccount<MintAccount>, received: u64) -> Result<u64> {
+    let info = mint.to_account_info();
+    let data = info.try_borrow_data()?;
+    amount_before_fee_at(&data, Clock::get()?.epoch, received)
+}
+
+/// `transfer_fee` for the raw mint account `data` during `epoch`, so
+/// clients can compute it off-chain.
+pub fn transfer_fee_at(data: &[u8], epoch: u64, amount: u64) -> Result<u64> {
+    let state = StateWithExtensions::<Mint>::unpack(data)?;
+    let Ok(config) = state.get_extension::<TransferFeeConfig>() else {
+        return Ok(0);
+    };
+    Ok(config
+        .calculate_epoch_fee(epoch, amount)
+        .ok_or(AmmError::Overflow)?)
+}
+
+/// `amount_before_fee` for the raw mint account `data` during `epoch`.
+pub fn amount_before_fee_at(data: &[u8], epoch: u64, received: u64) -> Result<u64> {
+    let state = StateWithExtensions::<Mint>::unpack(data)?;
+    let Ok(config) = state.get_extension::<TransferFeeConfig>() else {
+        return Ok(received);
+    };
+    Ok(config
+        .get_epoch_fee(epoch)
+        .calculate_pre_fee_amount(received)
+        .ok_or(AmmError::Overflow)?)
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
+};
+
+use crate::{constants::ORDER_SEED, state::Order};
+
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        has_one = mint_in,
+        close = maker,
+        seeds = [ORDER_SEED, order.config.as_ref(), maker.key().as_ref(), Order::encode_id(order.id).as_ref()],
+        bump = order.bump,
+    )]
+    pub order: Box<Account<'info, Order>>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint_in: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_in,
+        associated_token::authority = order,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = maker,
+        associated_token::mint = mint_in,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_in: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CancelOrder<'info> {
+    /// Returns the escrow to the maker and closes the order and its escrow.
+    pub fn cancel(&mut self) -> Result<()> {
+        let id = Order::encode_id(self.order.id);
+        let seeds = self.order.signer_seeds(&id);
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_accounts = TransferChecked {
+            from: self.escrow.to_account_info(),
+            mint: self.mint_in.to_account_info(),
+            to: self.maker_in.to_account_info(),
+            authority: self.order.to_account_info(),
+        };
+        let ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        transfer_checked(ctx, self.escrow.amount, self.mint_in.decimals)?;
+
+        let cpi_accounts = CloseAccount {
+            account: self.escrow.to_account_info(),
+            destination: self.maker.to_account_info(),
+            authority: self.order.to_account_info(),
+        };
+        let ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+        close_account(ctx)
+    }
+}
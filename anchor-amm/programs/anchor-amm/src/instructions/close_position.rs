@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::AmmError, state::Position};
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        close = owner,
+    )]
+    pub position: Account<'info, Position>,
+}
+
+impl<'info> ClosePosition<'info> {
+    pub fn close(&self) -> Result<()> {
+        require!(
+            self.position.liquidity == 0
+                && self.position.fees_owed_x == 0
+                && self.position.fees_owed_y == 0,
+            AmmError::PositionNotEmpty
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{constants::CONFIG_SEED, state::Config};
+
+#[derive(Accounts)]
+pub struct CollectProtocolFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mint::token_program = token_program_x)]
+    pub mint_x: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_y)]
+    pub mint_y: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = mint_x,
+        has_one = mint_y,
+        seeds = [CONFIG_SEED, Config::encode_seed(config.seed).as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_x,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_x,
+    )]
+    pub vault_x: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_y,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_y,
+    )]
+    pub vault_y: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_x,
+        token::token_program = token_program_x,
+    )]
+    pub treasury_x: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_y,
+        token::token_program = token_program_y,
+    )]
+    pub treasury_y: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program_x: Interface<'info, TokenInterface>,
+
+    pub token_program_y: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> CollectProtocolFees<'info> {
+    pub fn collect(&mut self) -> Result<()> {
+        self.config.check_authority(&self.authority.key())?;
+
+        let (x, y) = (self.config.protocol_fees_x, self.config.protocol_fees_y);
+        self.config.protocol_fees_x = 0;
+        self.config.protocol_fees_y = 0;
+
+        self.withdraw_tokens(true, x)?;
+        self.withdraw_tokens(false, y)
+    }
+
+    pub fn withdraw_tokens(&self, is_x: bool, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let (from, to, mint, token_program) = match is_x {
+            true => (&self.vault_x, &self.treasury_x, &self.mint_x, &self.token_program_x),
+            false => (&self.vault_y, &self.treasury_y, &self.mint_y, &self.token_program_y),
+        };
+
+        let accounts = TransferChecked {
+            from: from.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: self.config.to_account_info(),
+        };
+
+        let seed = Config::encode_seed(self.config.seed);
+        let seeds = self.config.signer_seeds(&seed);
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            accounts,
+            signer_seeds,
+        );
+
+        transfer_checked(cpi_ctx, amount, mint.decimals)
+    }
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{FACTORY_SEED, POOL_KEY_SEED, REGISTRY_SEED},
+    instructions::initialize_concentrated::*,
+    state::{CurveType, Factory, PoolKey, RegistryPage},
+};
+
+/// `CreatePool` for concentrated liquidity pools.
+#[derive(Accounts)]
+#[instruction(seed: u64, fee: u16)]
+pub struct CreateConcentratedPool<'info> {
+    pub pool: InitializeConcentrated<'info>,
+
+    #[account(
+        mut,
+        seeds = [FACTORY_SEED],
+        bump = factory.bump,
+    )]
+    pub factory: Box<Account<'info, Factory>>,
+
+    #[account(
+        init,
+        payer = pool.initializer,
+        seeds = [
+            POOL_KEY_SEED,
+            pool.mint_x.key().as_ref(),
+            pool.mint_y.key().as_ref(),
+            &fee.to_le_bytes(),
+            &[CurveType::Concentrated as u8],
+        ],
+        bump,
+        space = 8 + PoolKey::INIT_SPACE,
+    )]
+    pub pool_key: Account<'info, PoolKey>,
+
+    #[account(
+        init_if_needed,
+        payer = pool.initializer,
+        seeds = [
+            REGISTRY_SEED,
+            RegistryPage::encode_index(RegistryPage::page_of(factory.pool_count)).as_ref(),
+        ],
+        bump,
+        space = 8 + RegistryPage::INIT_SPACE,
+    )]
+    pub registry_page: Box<Account<'info, RegistryPage>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateConcentratedPool<'info> {
+    /// Initializes the canonical concentrated liquidity pool of the sorted
+    /// mint pair at one of the factory's fee tiers, like `CreatePool::create`.
+    pub fn create(
+        &mut self,
+        seed: u64,
+        fee: u16,
+        tick_spacing: u16,
+        sqrt_price: u128,
+        bumps: CreateConcentratedPoolBumps,
+    ) -> Result<()> {
+        self.factory
+            .check_pool(&self.pool.mint_x.key(), &self.pool.mint_y.key(), fee)?;
+
+        let authority = Some(self.factory.authority);
+        self.pool
+            .init(seed, fee, authority, tick_spacing, sqrt_price, bumps.pool)?;
+
+        self.factory.register(
+            self.pool.config.key(),
+            &self.pool.config,
+            &mut self.pool_key,
+            bumps.pool_key,
+            &mut self.registry_page,
+            bumps.registry_page,
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::Token,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constants::{CONFIG_SEED, FARM_SEED, LP_SEED},
+    error::AmmError,
+    extensions,
+    state::{Config, Farm},
+};
+
+#[derive(Accounts)]
+pub struct CreateFarm<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED, Config::encode_seed(config.seed).as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [LP_SEED, config.key().as_ref()],
+        bump = config.lp_bump,
+        mint::token_program = token_program,
+    )]
+    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = reward_token_program)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [FARM_SEED, config.key().as_ref(), reward_mint.key().as_ref()],
+        bump,
+        space = 8 + Farm::INIT_SPACE,
+        // Checked here, before the vaults are created: an LP reward would
+        // share the staking vault.
+        constraint = reward_mint.key() != mint_lp.key() @ AmmError::InvalidRewardMint,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    /// Holds the staked LP.
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint_lp,
+        associated_token::authority = farm,
+        associated_token::token_program = token_program,
+    )]
+    pub lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = reward_mint,
+        associated_token::authority = farm,
+        associated_token::token_program = reward_token_program,
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Program of the LP mint.
+    pub token_program: Program<'info, Token>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateFarm<'info> {
+    /// Creates a farm paying `reward_mint` to the pool's LP stakers, with
+    /// no emissions until the pool's authority sets a schedule.
+    pub fn create(&mut self, bumps: CreateFarmBumps) -> Result<()> {
+        self.config.check_authority(&self.authority.key())?;
+        extensions::check_mint(&self.reward_mint)?;
+
+        let now = Clock::get()?.unix_timestamp;
+
+        self.farm.set_inner(Farm {
+            config: self.config.key(),
+            mint_lp: self.mint_lp.key(),
+            reward_mint: self.reward_mint.key(),
+            reward_rate: 0,
+            emission_end: now,
+            last_update: now,
+            reward_per_share: 0,
+            total_staked: 0,
+            rewards_unclaimed: 0,
+            bump: bumps.farm,
+        });
+
+        Ok(())
+    }
+}
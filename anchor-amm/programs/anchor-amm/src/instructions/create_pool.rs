@@ -0,0 +1,153 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{FACTORY_SEED, POOL_KEY_SEED, REGISTRY_SEED},
+    instructions::initialize::*,
+    state::{CurveType, Factory, PoolKey, RegistryPage},
+};
+
+#[derive(Accounts)]
+#[instruction(seed: u64, fee: u16, curve: CurveType)]
+pub struct CreatePool<'info> {
+    pub pool: Initialize<'info>,
+
+    #[account(
+        mut,
+        seeds = [FACTORY_SEED],
+        bump = factory.bump,
+    )]
+    pub factory: Box<Account<'info, Factory>>,
+
+    #[account(
+        init,
+        payer = pool.initializer,
+        seeds = [
+            POOL_KEY_SEED,
+            pool.mint_x.key().as_ref(),
+            pool.mint_y.key().as_ref(),
+            &fee.to_le_bytes(),
+            &[curve as u8],
+        ],
+        bump,
+        space = 8 + PoolKey::INIT_SPACE,
+    )]
+    pub pool_key: Account<'info, PoolKey>,
+
+    #[account(
+        init_if_needed,
+        payer = pool.initializer,
+        seeds = [
+            REGISTRY_SEED,
+            RegistryPage::encode_index(RegistryPage::page_of(factory.pool_count)).as_ref(),
+        ],
+        bump,
+        space = 8 + RegistryPage::INIT_SPACE,
+    )]
+    pub registry_page: Box<Account<'info, RegistryPage>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreatePool<'info> {
+    /// Initializes the canonical pool of the sorted mint pair at one of the
+    /// factory's fee tiers, with the factory authority as its authority, and
+    /// lists it in the registry. The config is still derived from `seed`,
+    /// which can be any unused one.
+    pub fn create(
+        &mut self,
+        seed: u64,
+        fee: u16,
+        curve: CurveType,
+        amp: u64,
+        bumps: CreatePoolBumps,
+    ) -> Result<()> {
+        self.factory
+            .check_pool(&self.pool.mint_x.key(), &self.pool.mint_y.key(), fee)?;
+
+        let authority = Some(self.factory.authority);
+        self.pool.init(seed, fee, authority, curve, amp, bumps.pool)?;
+
+        self.factory.register(
+            self.pool.config.key(),
+            &self.pool.config,
+            &mut self.pool_key,
+            bumps.pool_key,
+            &mut self.registry_page,
+            bumps.registry_page,
+        );
+
+        Ok(())
+    }
+}
+
+/// `CreatePool` for weighted pools, whose key is the weighted curve's.
+#[derive(Accounts)]
+#[instruction(seed: u64, fee: u16)]
+pub struct CreateWeightedPool<'info> {
+    pub pool: Initialize<'info>,
+
+    #[account(
+        mut,
+        seeds = [FACTORY_SEED],
+        bump = factory.bump,
+    )]
+    pub factory: Box<Account<'info, Factory>>,
+
+    #[account(
+        init,
+        payer = pool.initializer,
+        seeds = [
+            POOL_KEY_SEED,
+            pool.mint_x.key().as_ref(),
+            pool.mint_y.key().as_ref(),
+            &fee.to_le_bytes(),
+            &[CurveType::Weighted as u8],
+        ],
+        bump,
+        space = 8 + PoolKey::INIT_SPACE,
+    )]
+    pub pool_key: Account<'info, PoolKey>,
+
+    #[account(
+        init_if_needed,
+        payer = pool.initializer,
+        seeds = [
+            REGISTRY_SEED,
+            RegistryPage::encode_index(RegistryPage::page_of(factory.pool_count)).as_ref(),
+        ],
+        bump,
+        space = 8 + RegistryPage::INIT_SPACE,
+    )]
+    pub registry_page: Box<Account<'info, RegistryPage>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateWeightedPool<'info> {
+    /// `CreatePool::create` for a weighted pool holding `weight_x` basis
+    /// points of its value in x.
+    pub fn create(
+        &mut self,
+        seed: u64,
+        fee: u16,
+        weight_x: u16,
+        bumps: CreateWeightedPoolBumps,
+    ) -> Result<()> {
+        self.factory
+            .check_pool(&self.pool.mint_x.key(), &self.pool.mint_y.key(), fee)?;
+
+        let authority = Some(self.factory.authority);
+        self.pool.init_weighted(seed, fee, authority, weight_x, bumps.pool)?;
+
+        self.factory.register(
+            self.pool.config.key(),
+            &self.pool.config,
+            &mut self.pool_key,
+            bumps.pool_key,
+            &mut self.registry_page,
+            bumps.registry_page,
+        );
+
+        Ok(())
+    }
+}
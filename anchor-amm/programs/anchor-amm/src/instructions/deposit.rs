@@ -1,76 +1,123 @@
-use anchor_lang::{accounts::program, prelude::*};
+use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{transfer, mint_to, Mint, MintTo, Token, TokenAccount, Transfer}
+    token::Token,
+    token_interface::{
+        mint_to, transfer_checked, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+    },
 };
 use constant_product_curve::ConstantProduct;
 
-use crate::{state::Config,error::AmmError};
+use crate::{
+    constants::{CONFIG_SEED, DEAD_SEED, LP_SEED, MINIMUM_LIQUIDITY, ORACLE_SEED},
+    curves,
+    error::AmmError,
+    events::LiquidityAdded,
+    extensions, math,
+    state::{Config, Oracle},
+};
 
 #[derive(Accounts)]
 pub struct Deposit<'info>{
     #[account(mut)]
     pub user: Signer<'info>,
     
-    pub mint_x: Account<'info, Mint>,
+    #[account(mint::token_program = token_program_x)]
+    pub mint_x: Box<InterfaceAccount<'info, Mint>>,
     
-    pub mint_y: Account<'info,Mint>,
+    #[account(mint::token_program = token_program_y)]
+    pub mint_y: Box<InterfaceAccount<'info, Mint>>,
     
      #[account(
         mut,
-        seeds = [b"lp",config.key().as_ref()],
-        bump,
+        seeds = [LP_SEED, config.key().as_ref()],
+        bump = config.lp_bump,
         mint::decimals = 6,
         mint::authority = config,
+        mint::token_program = token_program,
     )]
-    pub mint_lp: Account<'info,Mint>,
+    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
     
     #[account(
+       mut,
        has_one = mint_x,
        has_one = mint_y,
-       seeds = [b"config",config.seed.to_le_bytes().as_ref()],
+       seeds = [CONFIG_SEED, Config::encode_seed(config.seed).as_ref()],
        bump = config.config_bump,
     )]
     pub config: Account<'info,Config>,
 
+    #[account(
+        mut,
+        has_one = config,
+        seeds = [ORACLE_SEED, config.key().as_ref()],
+        bump = oracle.bump,
+    )]
+    pub oracle: Box<Account<'info, Oracle>>,
+
     #[account(
         mut,
         associated_token::mint = mint_x,
-        associated_token::authority = config
+        associated_token::authority = config,
+        associated_token::token_program = token_program_x,
     )]
-    pub vault_x: Account<'info,TokenAccount>,
+    pub vault_x: Box<InterfaceAccount<'info, TokenAccount>>,
 
      #[account(
         mut,
         associated_token::mint = mint_y,
-        associated_token::authority = config
+        associated_token::authority = config,
+        associated_token::token_program = token_program_y,
     )]
-    pub vault_y: Account<'info,TokenAccount>,
+    pub vault_y: Box<InterfaceAccount<'info, TokenAccount>>,
 
 
     #[account(
         mut,
         associated_token::mint = mint_x,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program_x,
     )]
-    pub user_x: Account<'info,TokenAccount>,
+    pub user_x: Box<InterfaceAccount<'info, TokenAccount>>,
 
      #[account(
         mut,
         associated_token::mint = mint_y,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program_y,
     )]
-    pub user_y: Account<'info,TokenAccount>,
+    pub user_y: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
         payer = user,
         associated_token::mint = mint_lp,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_lp: Account<'info,TokenAccount>,
+    pub user_lp: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// CHECK: PDA that never signs, owner of the locked minimum liquidity.
+    #[account(
+        seeds = [DEAD_SEED, config.key().as_ref()],
+        bump,
+    )]
+    pub dead: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_lp,
+        associated_token::authority = dead,
+        associated_token::token_program = token_program,
+    )]
+    pub locked_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Program of the LP mint.
     pub token_program: Program<'info,Token>,
+
+    pub token_program_x: Interface<'info, TokenInterface>,
+
+    pub token_program_y: Interface<'info, TokenInterface>,
     
     pub associated_token_program: Program<'info,AssociatedToken>,
     
@@ -80,62 +127,189 @@ pub struct Deposit<'info>{
 
 impl<'info> Deposit<'info>{
 
-    pub fn deposit(&mut self,amount: u64,max_x:u64,max_y:u64)->Result<()>{
-        require!(self.config.locked==false, AmmError::PoolLocked);
+    /// Deposits `x`/`y` for `amount` LP. The first deposit instead sets the
+    /// price with exactly `max_x`/`max_y`, mints the curve's initial
+    /// liquidity (sqrt(x * y), or D for StableSwap) and locks
+    /// `MINIMUM_LIQUIDITY` of it; `amount` is then the least LP accepted.
+    /// All amounts are what the user sends, transfer fees included. Fails
+    /// once the clock is past `expiration`.
+    pub fn deposit(&mut self,amount: u64,max_x:u64,max_y:u64,expiration:i64)->Result<()>{
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(!self.config.flash_loan_active(), AmmError::FlashLoanActive);
+        require!(!self.config.deposits_paused, AmmError::DepositsPaused);
+        require!(
+            Clock::get()?.unix_timestamp <= expiration,
+            AmmError::OfferExpired
+        );
         require!(amount!=0,AmmError::InvalidAmount);
 
-        let (x,y) = match self.mint_lp.supply == 0 &&  self.vault_x.amount == 0 && self.vault_y.amount == 0 {
-            true => (max_x,max_y),
-            false => {
-                let amount = ConstantProduct::xy_deposit_amounts_from_l(
-                    self.vault_x.amount,
-                     self.vault_y.amount,
-                      self.mint_lp.supply,
-                       amount,
-                        6).unwrap();
-                        (amount.x,amount.y)
-            }
+        let (x, y) = self.config.reserves(self.vault_x.amount, self.vault_y.amount)?;
+        self.oracle.update(&mut self.config, x, y)?;
+
+        // Tokens donated to the vaults before the first deposit accrue to
+        // it rather than blocking the pool.
+        if self.mint_lp.supply == 0 {
+            // Price the pool on what the vaults actually receive.
+            let received_x = max_x - extensions::transfer_fee(&self.mint_x, max_x)?;
+            let received_y = max_y - extensions::transfer_fee(&self.mint_y, max_y)?;
+
+            let now = Clock::get()?.unix_timestamp;
+            let liquidity = curves::initial_liquidity(&self.config, received_x, received_y, now)?;
+            require!(liquidity > MINIMUM_LIQUIDITY, AmmError::LiquidityLessThanMinimum);
+
+            let lp = liquidity - MINIMUM_LIQUIDITY;
+            require!(lp >= amount, AmmError::SlippageExceeded);
+
+            self.deposit_tokens(true, max_x)?;
+            self.deposit_tokens(false, max_y)?;
+
+            self.mint_lp_token(self.locked_lp.to_account_info(), MINIMUM_LIQUIDITY)?;
+            self.mint_lp_token(self.user_lp.to_account_info(), lp)?;
+            return self.emit_added(max_x, max_y, lp, (x, y, 0));
+        }
+
+        // Both curves are homogeneous, so a proportional deposit leaves
+        // the price unchanged whichever one the pool uses.
+        let amounts = ConstantProduct::xy_deposit_amounts_from_l(
+            x,
+            y,
+            self.mint_lp.supply,
+            amount,
+            6,
+        )
+        .map_err(AmmError::from)?;
+        let amount_x = extensions::amount_before_fee(&self.mint_x, amounts.x)?;
+        let amount_y = extensions::amount_before_fee(&self.mint_y, amounts.y)?;
+        require!(amount_x<=max_x && amount_y<=max_y,AmmError::SlippageExceeded);
+
+        let supply = self.mint_lp.supply;
+        self.deposit_tokens(true, amount_x)?;
+        self.deposit_tokens(false, amount_y)?;
+
+        self.mint_lp_token(self.user_lp.to_account_info(), amount)?;
+        self.emit_added(amount_x, amount_y, amount, (x, y, supply))
+    }
+
+    /// Deposits `amount_in` of x (`is_x`) or y alone: the pool swaps part of
+    /// it through its own curve and adds the rest along with the swap's
+    /// output as liquidity, minting at least `min_lp`. No tokens of the
+    /// other side move.
+    pub fn deposit_single(
+        &mut self,
+        is_x: bool,
+        amount_in: u64,
+        min_lp: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(!self.config.flash_loan_active(), AmmError::FlashLoanActive);
+        require!(!self.config.deposits_paused, AmmError::DepositsPaused);
+        require!(!self.config.swaps_paused, AmmError::SwapsPaused);
+        require!(
+            Clock::get()?.unix_timestamp <= expiration,
+            AmmError::OfferExpired
+        );
+        require!(amount_in != 0, AmmError::InvalidAmount);
+        require!(self.mint_lp.supply != 0, AmmError::NoLiquidityInPool);
+
+        let (x, y) = self.config.reserves(self.vault_x.amount, self.vault_y.amount)?;
+        self.oracle.update(&mut self.config, x, y)?;
+
+        let mint_in = match is_x {
+            true => &self.mint_x,
+            false => &self.mint_y,
+        };
+        let received = amount_in - extensions::transfer_fee(mint_in, amount_in)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let swapped = curves::zap_swap_amount(&self.config, x, y, is_x, received, now)?;
+        let res = curves::swap(&self.config, x, y, is_x, swapped, now)?;
+        let share = self.config.accrue_protocol_fee(is_x, res.fee)?;
+
+        let (reserve_in, reserve_out) = match is_x {
+            true => (x, y),
+            false => (y, x),
         };
-        require!(x<=max_x && y<=max_y,AmmError::SlippageExceeded);
+        let reserve_in = reserve_in
+            .checked_add(swapped)
+            .and_then(|reserve| reserve.checked_sub(share))
+            .ok_or(AmmError::Overflow)? as u128;
+        let reserve_out = reserve_out.checked_sub(res.withdraw).ok_or(AmmError::Overflow)? as u128;
 
-        self.deposit_tokens(true, x);
-        self.deposit_tokens(false, y);
+        // Whatever does not match the ratio stays with the pool.
+        let supply = self.mint_lp.supply as u128;
+        let lp = math::mul_div((received - swapped) as u128, supply, reserve_in)
+            .zip(math::mul_div(res.withdraw as u128, supply, reserve_out))
+            .map(|(a, b)| a.min(b))
+            .and_then(|lp| u64::try_from(lp).ok())
+            .ok_or(AmmError::Overflow)?;
+        require!(lp != 0, AmmError::InvalidAmount);
+        require!(lp >= min_lp, AmmError::SlippageExceeded);
 
-        self.mint_lp_token(amount)
+        self.deposit_tokens(is_x, amount_in)?;
+        self.mint_lp_token(self.user_lp.to_account_info(), lp)?;
+
+        let (amount_x, amount_y) = match is_x {
+            true => (amount_in, 0),
+            false => (0, amount_in),
+        };
+        self.emit_added(amount_x, amount_y, lp, (x, y, supply as u64))
+    }
+
+    /// Emits `LiquidityAdded` for the reserves and LP supply `before` the
+    /// deposit and as they are now.
+    fn emit_added(&mut self, amount_x: u64, amount_y: u64, lp: u64, before: (u64, u64, u64)) -> Result<()> {
+        self.vault_x.reload()?;
+        self.vault_y.reload()?;
+        self.mint_lp.reload()?;
+        let (x, y) = self.config.reserves(self.vault_x.amount, self.vault_y.amount)?;
+
+        emit!(LiquidityAdded {
+            config: self.config.key(),
+            user: self.user.key(),
+            amount_x,
+            amount_y,
+            lp,
+            reserve_x_before: before.0,
+            reserve_y_before: before.1,
+            lp_supply_before: before.2,
+            reserve_x_after: x,
+            reserve_y_after: y,
+            lp_supply_after: self.mint_lp.supply,
+        });
+
+        Ok(())
     }
 
     pub fn deposit_tokens(&self,is_x:bool,amount:u64)->Result<()>{
-        let (from,to) = match is_x{
-            true => (self.user_x.to_account_info(),self.vault_x.to_account_info()),
-            false => (self.user_y.to_account_info(),self.vault_y.to_account_info()),
+        let (from, to, mint, cpi_program) = match is_x {
+            true => (&self.user_x, &self.vault_x, &self.mint_x, &self.token_program_x),
+            false => (&self.user_y, &self.vault_y, &self.mint_y, &self.token_program_y),
         };
 
-        let cpi_program = self.token_program.to_account_info();
-
-        let cpi_accounts = Transfer{
-            from,
-            to,
+        let cpi_accounts = TransferChecked {
+            from: from.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
             authority: self.user.to_account_info(),
         };
 
-        let ctx = CpiContext::new(cpi_program, cpi_accounts);
-        transfer(ctx, amount)
+        let ctx = CpiContext::new(cpi_program.to_account_info(), cpi_accounts);
+
+        transfer_checked(ctx, amount, mint.decimals)
     }
 
-    pub fn mint_lp_token(&self, amount: u64) -> Result<()> {
+    pub fn mint_lp_token(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
         let cpi_program  = self.token_program.to_account_info();
         
         let cpi_accounts = MintTo{
             mint:self.mint_lp.to_account_info(),
-            to:self.user_lp.to_account_info(),
+            to,
             authority: self.config.to_account_info()
         };
         
-        let seeds: &[&[u8];3] = &[
-            &b"config"[..],
-            &self.config.seed.to_le_bytes(),
-            &[self.config.config_bump],
-        ];
+        let seed = Config::encode_seed(self.config.seed);
+        let seeds = self.config.signer_seeds(&seed);
 
         let signer_seeds = &[&seeds[..]];
 
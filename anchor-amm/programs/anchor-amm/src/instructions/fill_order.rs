@@ -0,0 +1,158 @@
+use std::collections::BTreeSet;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
+};
+
+use crate::{
+    constants::{ORDER_BOUNTY, ORDER_SEED},
+    error::AmmError,
+    extensions,
+    instructions::swap::*,
+    math,
+    state::Order,
+};
+
+#[derive(Accounts)]
+pub struct FillOrder<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    /// CHECK: The order's maker, receiving the order's rent.
+    #[account(mut)]
+    pub maker: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        has_one = mint_in,
+        close = maker,
+        seeds = [ORDER_SEED, order.config.as_ref(), maker.key().as_ref(), Order::encode_id(order.id).as_ref()],
+        bump = order.bump,
+    )]
+    pub order: Box<Account<'info, Order>>,
+
+    #[account(mint::token_program = token_program_in)]
+    pub mint_in: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_out)]
+    pub mint_out: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_in,
+        associated_token::authority = order,
+        associated_token::token_program = token_program_in,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        associated_token::mint = mint_out,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program_out,
+    )]
+    pub maker_out: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program_in: Interface<'info, TokenInterface>,
+
+    pub token_program_out: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> FillOrder<'info> {
+    /// Fills the whole order: the escrow moves to the keeper, who swaps it
+    /// through the pool, keeps `ORDER_BOUNTY` of the output and passes the
+    /// rest to the maker. Fails unless the maker receives at least the
+    /// order's `min_out`. `remaining` holds the pool's `Swap` accounts for
+    /// the keeper, followed by the ticks the swap may cross on a
+    /// concentrated liquidity pool.
+    pub fn fill(&mut self, remaining: &'info [AccountInfo<'info>]) -> Result<()> {
+        let mut accounts = remaining;
+        let mut swap = Swap::try_accounts(
+            &crate::ID,
+            &mut accounts,
+            &[],
+            &mut SwapBumps::default(),
+            &mut BTreeSet::new(),
+        )?;
+        require_keys_eq!(swap.user.key(), self.keeper.key(), AmmError::InvalidOrder);
+        require_keys_eq!(swap.config.key(), self.order.config, AmmError::InvalidOrder);
+
+        let is_x = swap.mint_x.key() == self.order.mint_in;
+        let (keeper_in, keeper_out, mint_out) = match is_x {
+            true => (&swap.user_x, &swap.user_y, swap.mint_y.key()),
+            false => (&swap.user_y, &swap.user_x, swap.mint_x.key()),
+        };
+        require_keys_eq!(self.mint_out.key(), mint_out, AmmError::InvalidOrder);
+        let (keeper_in, keeper_out) = (keeper_in.to_account_info(), keeper_out.to_account_info());
+
+        // Anything sent to the escrow since the order was placed is sold too.
+        let amount = self.escrow.amount;
+        self.release(keeper_in, amount)?;
+        let received = amount - extensions::transfer_fee(&self.mint_in, amount)?;
+
+        let out = swap.swap_hop(is_x, received, accounts)?;
+        swap.exit(&crate::ID)?;
+
+        let bounty = math::mul_div(out as u128, ORDER_BOUNTY as u128, 10_000)
+            .and_then(|bounty| u64::try_from(bounty).ok())
+            .ok_or(AmmError::Overflow)?;
+        let paid = out - bounty;
+        let received = paid - extensions::transfer_fee(&self.mint_out, paid)?;
+        require!(received >= self.order.min_out, AmmError::OrderPriceNotReached);
+
+        let cpi_accounts = TransferChecked {
+            from: keeper_out,
+            mint: self.mint_out.to_account_info(),
+            to: self.maker_out.to_account_info(),
+            authority: self.keeper.to_account_info(),
+        };
+        let ctx = CpiContext::new(self.token_program_out.to_account_info(), cpi_accounts);
+        transfer_checked(ctx, paid, self.mint_out.decimals)?;
+
+        self.close_escrow()
+    }
+
+    /// Sends `amount` from the escrow, signed by the order.
+    fn release(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.escrow.to_account_info(),
+            mint: self.mint_in.to_account_info(),
+            to,
+            authority: self.order.to_account_info(),
+        };
+
+        let id = Order::encode_id(self.order.id);
+        let seeds = self.order.signer_seeds(&id);
+        let signer_seeds = &[&seeds[..]];
+
+        let ctx = CpiContext::new_with_signer(self.token_program_in.to_account_info(), cpi_accounts, signer_seeds);
+
+        transfer_checked(ctx, amount, self.mint_in.decimals)
+    }
+
+    fn close_escrow(&self) -> Result<()> {
+        let cpi_accounts = CloseAccount {
+            account: self.escrow.to_account_info(),
+            destination: self.maker.to_account_info(),
+            authority: self.order.to_account_info(),
+        };
+
+        let id = Order::encode_id(self.order.id);
+        let seeds = self.order.signer_seeds(&id);
+        let signer_seeds = &[&seeds[..]];
+
+        let ctx = CpiContext::new_with_signer(self.token_program_in.to_account_info(), cpi_accounts, signer_seeds);
+
+        close_account(ctx)
+    }
+}
@@ -0,0 +1,175 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+    Discriminator,
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{CONFIG_SEED, ORACLE_SEED},
+    error::AmmError,
+    instruction::FlashRepay,
+    state::{Config, Oracle},
+};
+
+/// Position of `config` in the accounts of `flash_repay`.
+const REPAY_CONFIG_INDEX: usize = 7;
+
+#[derive(Accounts)]
+pub struct FlashBorrow<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mint::token_program = token_program_x)]
+    pub mint_x: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_y)]
+    pub mint_y: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_x,
+        associated_token::authority = user,
+        associated_token::token_program = token_program_x,
+    )]
+    pub user_x: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_y,
+        associated_token::authority = user,
+        associated_token::token_program = token_program_y,
+    )]
+    pub user_y: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_x,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_x,
+    )]
+    pub vault_x: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_y,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_y,
+    )]
+    pub vault_y: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = mint_x,
+        has_one = mint_y,
+        seeds = [CONFIG_SEED, Config::encode_seed(config.seed).as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        has_one = config,
+        seeds = [ORACLE_SEED, config.key().as_ref()],
+        bump = oracle.bump,
+    )]
+    pub oracle: Box<Account<'info, Oracle>>,
+
+    /// CHECK: the instructions sysvar, checked by address.
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub token_program_x: Interface<'info, TokenInterface>,
+
+    pub token_program_y: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> FlashBorrow<'info> {
+    /// Lends `amount_x` and `amount_y` from the vaults. A `flash_repay` for
+    /// this pool must follow later in the same transaction and return them
+    /// plus `FLASH_LOAN_FEE`, which stays with the LPs. Until then every
+    /// instruction touching the reserves fails with `FlashLoanActive`.
+    pub fn flash_borrow(&mut self, amount_x: u64, amount_y: u64) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(!self.config.flash_loan_active(), AmmError::FlashLoanActive);
+        require!(!self.config.swaps_paused, AmmError::SwapsPaused);
+        require!(amount_x != 0 || amount_y != 0, AmmError::InvalidAmount);
+
+        let (x, y) = self.config.reserves(self.vault_x.amount, self.vault_y.amount)?;
+        require!(
+            amount_x <= x && amount_y <= y,
+            AmmError::InsufficientLiquidity
+        );
+        self.check_repay()?;
+
+        // The repaid fee moves the price, so the oracle records the one the
+        // loan started at.
+        self.oracle.update(&mut self.config, x, y)?;
+        self.config.flash_loan_x = amount_x;
+        self.config.flash_loan_y = amount_y;
+
+        self.withdraw_tokens(true, amount_x)?;
+        self.withdraw_tokens(false, amount_y)
+    }
+
+    /// Finds a `flash_repay` for this pool among the top-level instructions
+    /// after the current one. Only its `config` account counts: this config
+    /// passed in any other position does not make it this pool's repayment.
+    fn check_repay(&self) -> Result<()> {
+        let instructions = self.instructions.to_account_info();
+        let current = load_current_index_checked(&instructions)? as usize;
+        let config = self.config.key();
+
+        let mut index = current + 1;
+        while let Ok(ix) = load_instruction_at_checked(index, &instructions) {
+            if ix.program_id == crate::ID
+                && ix.data.starts_with(FlashRepay::DISCRIMINATOR)
+                && ix.accounts.get(REPAY_CONFIG_INDEX).is_some_and(|meta| meta.pubkey == config)
+            {
+                return Ok(());
+            }
+            index += 1;
+        }
+
+        err!(AmmError::FlashLoanNotRepaid)
+    }
+
+    pub fn withdraw_tokens(&self, is_x: bool, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let (from, to, mint, token_program) = match is_x {
+            true => (&self.vault_x, &self.user_x, &self.mint_x, &self.token_program_x),
+            false => (&self.vault_y, &self.user_y, &self.mint_y, &self.token_program_y),
+        };
+
+        let accounts = TransferChecked {
+            from: from.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: self.config.to_account_info(),
+        };
+
+        let seed = Config::encode_seed(self.config.seed);
+        let seeds = self.config.signer_seeds(&seed);
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            accounts,
+            signer_seeds,
+        );
+
+        transfer_checked(cpi_ctx, amount, mint.decimals)
+    }
+}
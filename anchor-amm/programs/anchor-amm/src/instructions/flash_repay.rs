@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{
+    constants::CONFIG_SEED,
+    error::AmmError,
+    extensions, math,
+    state::{Config, CurveType},
+};
+
+#[derive(Accounts)]
+pub struct FlashRepay<'info> {
+    pub user: Signer<'info>,
+
+    #[account(mint::token_program = token_program_x)]
+    pub mint_x: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_y)]
+    pub mint_y: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_x,
+        associated_token::authority = user,
+        associated_token::token_program = token_program_x,
+    )]
+    pub user_x: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_y,
+        associated_token::authority = user,
+        associated_token::token_program = token_program_y,
+    )]
+    pub user_y: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_x,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_x,
+    )]
+    pub vault_x: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_y,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_y,
+    )]
+    pub vault_y: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = mint_x,
+        has_one = mint_y,
+        seeds = [CONFIG_SEED, Config::encode_seed(config.seed).as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub token_program_x: Interface<'info, TokenInterface>,
+
+    pub token_program_y: Interface<'info, TokenInterface>,
+}
+
+impl<'info> FlashRepay<'info> {
+    /// Returns the outstanding flash loan plus its fee from the user's
+    /// accounts, transfer fees included, and closes it. The fee is split
+    /// with the protocol like a swap fee.
+    pub fn flash_repay(&mut self) -> Result<()> {
+        require!(self.config.flash_loan_active(), AmmError::NoFlashLoan);
+
+        let (x, y) = (self.config.flash_loan_x, self.config.flash_loan_y);
+        self.config.flash_loan_x = 0;
+        self.config.flash_loan_y = 0;
+
+        self.repay(true, x)?;
+        self.repay(false, y)
+    }
+
+    fn repay(&mut self, is_x: bool, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let fee = Config::flash_loan_fee(amount)?;
+        let owed = amount.checked_add(fee).ok_or(AmmError::Overflow)?;
+        let share = self.config.accrue_protocol_fee(is_x, fee)?;
+
+        // Concentrated positions earn the fee through the global fee growth,
+        // like swap fees.
+        if self.config.curve == CurveType::Concentrated && self.config.liquidity > 0 {
+            let growth = math::mul_div((fee - share) as u128, 1 << 64, self.config.liquidity as u128)
+                .ok_or(AmmError::Overflow)?;
+            let fee_growth = match is_x {
+                true => &mut self.config.fee_growth_global_x,
+                false => &mut self.config.fee_growth_global_y,
+            };
+            *fee_growth = fee_growth.wrapping_add(growth);
+        }
+
+        let (from, to, mint, token_program) = match is_x {
+            true => (&self.user_x, &self.vault_x, &self.mint_x, &self.token_program_x),
+            false => (&self.user_y, &self.vault_y, &self.mint_y, &self.token_program_y),
+        };
+        let amount = extensions::amount_before_fee(mint, owed)?;
+
+        let accounts = TransferChecked {
+            from: from.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(token_program.to_account_info(), accounts);
+
+        transfer_checked(cpi_ctx, amount, mint.decimals)
+    }
+}
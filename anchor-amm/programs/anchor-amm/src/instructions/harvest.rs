@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{FARM_SEED, FARM_STAKE_SEED},
+    error::AmmError,
+    state::{Farm, FarmStake},
+};
+
+#[derive(Accounts)]
+pub struct Harvest<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = reward_mint,
+        seeds = [FARM_SEED, farm.config.as_ref(), reward_mint.key().as_ref()],
+        bump = farm.bump,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(
+        mut,
+        has_one = farm,
+        has_one = owner,
+        seeds = [FARM_STAKE_SEED, farm.key().as_ref(), owner.key().as_ref()],
+        bump = stake.bump,
+    )]
+    pub stake: Box<Account<'info, FarmStake>>,
+
+    #[account(mint::token_program = reward_token_program)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = farm,
+        associated_token::token_program = reward_token_program,
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = reward_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = reward_token_program,
+    )]
+    pub owner_rewards: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Harvest<'info> {
+    /// Pays out every reward the stake has earned so far. Transfer fees of
+    /// the reward mint come out of the payout.
+    pub fn harvest(&mut self) -> Result<()> {
+        self.farm.accrue(Clock::get()?.unix_timestamp)?;
+        self.stake.update_rewards(&self.farm)?;
+
+        let amount = self.stake.rewards_owed;
+        require!(amount != 0, AmmError::InvalidAmount);
+        self.stake.rewards_owed = 0;
+        self.farm.rewards_unclaimed = self.farm.rewards_unclaimed.saturating_sub(amount);
+
+        let cpi_accounts = TransferChecked {
+            from: self.reward_vault.to_account_info(),
+            mint: self.reward_mint.to_account_info(),
+            to: self.owner_rewards.to_account_info(),
+            authority: self.farm.to_account_info(),
+        };
+
+        let seeds = self.farm.signer_seeds();
+        let signer_seeds = &[&seeds[..]];
+
+        let ctx = CpiContext::new_with_signer(self.reward_token_program.to_account_info(), cpi_accounts, signer_seeds);
+
+        transfer_checked(ctx, amount, self.reward_mint.decimals)
+    }
+}
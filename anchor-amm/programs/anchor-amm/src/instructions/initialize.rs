@@ -1,10 +1,20 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint,Token,TokenAccount}
+    token::Token,
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-use crate::state::Config;
+use crate::{
+    constants::{
+        CONFIG_SEED, CONFIG_VERSION, DEAD_SEED, LP_SEED, MAX_AMP, MAX_FEE, MIN_AMP, MIN_WEIGHT, ORACLE_SEED,
+        TOTAL_WEIGHT,
+    },
+    error::AmmError,
+    events::PoolCreated,
+    extensions,
+    state::{Config, CurveType, Observation, Oracle},
+};
 
 #[derive(Accounts)]
 #[instruction(seed:u64)]
@@ -12,46 +22,81 @@ pub struct Initialize<'info> {
     #[account(mut)]
     pub initializer: Signer<'info>,
     
-    pub mint_x: Account<'info, Mint>,
+    #[account(mint::token_program = token_program_x)]
+    pub mint_x: Box<InterfaceAccount<'info, Mint>>,
     
-    pub mint_y: Account<'info,Mint>,
+    #[account(mint::token_program = token_program_y)]
+    pub mint_y: Box<InterfaceAccount<'info, Mint>>,
     
     #[account(
         init,
         payer = initializer,
-        seeds = [b"lp",config.key().as_ref()],
+        seeds = [LP_SEED, config.key().as_ref()],
         bump,
         mint::decimals = 6,
         mint::authority = config,
+        mint::token_program = token_program,
     )]
-    pub mint_lp: Account<'info,Mint>,
+    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
     
     #[account(
         init,
         payer = initializer,
-        seeds = [b"config",seed.to_le_bytes().as_ref()],
+        seeds = [CONFIG_SEED, Config::encode_seed(seed).as_ref()],
         bump,
         space = 8 + Config::INIT_SPACE,
     )]
     pub config: Account<'info,Config>,
 
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [ORACLE_SEED, config.key().as_ref()],
+        bump,
+        space = 8 + Oracle::INIT_SPACE,
+    )]
+    pub oracle: Box<Account<'info, Oracle>>,
+
     #[account(
         init,
         payer = initializer,
         associated_token::mint = mint_x,
-        associated_token::authority = config
+        associated_token::authority = config,
+        associated_token::token_program = token_program_x,
     )]
-    pub vault_x: Account<'info,TokenAccount>,
+    pub vault_x: Box<InterfaceAccount<'info, TokenAccount>>,
 
      #[account(
         init,
         payer = initializer,
         associated_token::mint = mint_y,
-        associated_token::authority = config
+        associated_token::authority = config,
+        associated_token::token_program = token_program_y,
     )]
-    pub vault_y: Account<'info,TokenAccount>,
+    pub vault_y: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// CHECK: PDA that never signs, owner of the locked minimum liquidity.
+    #[account(
+        seeds = [DEAD_SEED, config.key().as_ref()],
+        bump,
+    )]
+    pub dead: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = initializer,
+        associated_token::mint = mint_lp,
+        associated_token::authority = dead,
+        associated_token::token_program = token_program,
+    )]
+    pub locked_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Program of the LP mint.
     pub token_program: Program<'info,Token>,
+
+    pub token_program_x: Interface<'info, TokenInterface>,
+
+    pub token_program_y: Interface<'info, TokenInterface>,
     
     pub associated_token_program: Program<'info,AssociatedToken>,
     
@@ -60,7 +105,65 @@ pub struct Initialize<'info> {
 }
 
 impl<'info>Initialize<'info> {
-    pub fn init(&mut self,seed: u64,fee: u16, authority:Option<Pubkey>,bumps:InitializeBumps)-> Result<()>{
+    pub fn init(
+        &mut self,
+        seed: u64,
+        fee: u16,
+        authority: Option<Pubkey>,
+        curve: CurveType,
+        amp: u64,
+        bumps: InitializeBumps,
+    ) -> Result<()> {
+        match curve {
+            CurveType::ConstantProduct => require!(amp == 0, AmmError::InvalidAmp),
+            CurveType::StableSwap => {
+                require!((MIN_AMP..=MAX_AMP).contains(&amp), AmmError::InvalidAmp)
+            }
+            CurveType::Concentrated | CurveType::Weighted => return err!(AmmError::InvalidCurve),
+        }
+        self.create(seed, fee, authority, curve, amp, bumps)?;
+
+        emit!(PoolCreated::new(self.config.key(), &self.config));
+        Ok(())
+    }
+
+    /// Initializes a weighted pool holding `weight_x` basis points of its
+    /// value in x and the rest in y, each side at least `MIN_WEIGHT`.
+    pub fn init_weighted(
+        &mut self,
+        seed: u64,
+        fee: u16,
+        authority: Option<Pubkey>,
+        weight_x: u16,
+        bumps: InitializeBumps,
+    ) -> Result<()> {
+        require!(
+            (MIN_WEIGHT..=TOTAL_WEIGHT - MIN_WEIGHT).contains(&weight_x),
+            AmmError::InvalidWeight
+        );
+        self.create(seed, fee, authority, CurveType::Weighted, 0, bumps)?;
+        self.config.weight_x = weight_x;
+        self.config.weight_y = TOTAL_WEIGHT - weight_x;
+
+        emit!(PoolCreated::new(self.config.key(), &self.config));
+        Ok(())
+    }
+
+    fn create(
+        &mut self,
+        seed: u64,
+        fee: u16,
+        authority: Option<Pubkey>,
+        curve: CurveType,
+        amp: u64,
+        bumps: InitializeBumps,
+    ) -> Result<()> {
+        require!(fee <= MAX_FEE, AmmError::InvalidFee);
+        extensions::check_mint(&self.mint_x)?;
+        extensions::check_mint(&self.mint_y)?;
+
+        let now = Clock::get()?.unix_timestamp;
+
         self.config.set_inner(
             Config { seed ,
                  authority ,
@@ -69,10 +172,51 @@ impl<'info>Initialize<'info> {
                  fee ,
                  locked: false,
                  config_bump: bumps.config,
-                 lp_bum: bumps.mint_lp,
+                 lp_bump: bumps.mint_lp,
+                 version: CONFIG_VERSION,
+                 pending_authority: None,
+                 dynamic_fee: false,
+                 min_fee: 0,
+                 max_fee: 0,
+                 curve,
+                 initial_amp: amp,
+                 target_amp: amp,
+                 ramp_start_timestamp: now,
+                 ramp_stop_timestamp: now,
+                 weight_x: 0,
+                 weight_y: 0,
+                 tick_spacing: 0,
+                 sqrt_price: 0,
+                 tick_current: 0,
+                 tick_below: 0,
+                 tick_above: 0,
+                 liquidity: 0,
+                 fee_growth_global_x: 0,
+                 fee_growth_global_y: 0,
+                 protocol_fee: 0,
+                 protocol_fees_x: 0,
+                 protocol_fees_y: 0,
+                 price_x_cumulative: 0,
+                 price_y_cumulative: 0,
+                 last_update_timestamp: now,
+                 reference_price: 0,
+                 volatility: 0,
+                 flash_loan_x: 0,
+                 flash_loan_y: 0,
+                 swaps_paused: false,
+                 deposits_paused: false,
+                 withdrawals_paused: false,
+                 reference_slot: 0,
                 });
 
-                Ok(())
+        self.oracle.set_inner(Oracle {
+            config: self.config.key(),
+            index: 0,
+            observations: vec![Observation::from(&*self.config)],
+            bump: bumps.oracle,
+        });
+
+        Ok(())
     }
 }
 
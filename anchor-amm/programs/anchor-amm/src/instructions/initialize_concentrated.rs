@@ -0,0 +1,192 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constants::{CONFIG_SEED, CONFIG_VERSION, MAX_FEE, MAX_TICK_SPACING, ORACLE_SEED, TICK_SEED},
+    curves::concentrated,
+    error::AmmError,
+    events::PoolCreated,
+    extensions,
+    state::{Config, CurveType, Observation, Oracle, Tick},
+};
+
+#[derive(Accounts)]
+#[instruction(seed: u64, fee: u16, tick_spacing: u16)]
+pub struct InitializeConcentrated<'info> {
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+
+    #[account(mint::token_program = token_program_x)]
+    pub mint_x: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_y)]
+    pub mint_y: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [CONFIG_SEED, Config::encode_seed(seed).as_ref()],
+        bump,
+        space = 8 + Config::INIT_SPACE,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [ORACLE_SEED, config.key().as_ref()],
+        bump,
+        space = 8 + Oracle::INIT_SPACE,
+    )]
+    pub oracle: Box<Account<'info, Oracle>>,
+
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [TICK_SEED, config.key().as_ref(), &Tick::bounds(tick_spacing).0.to_le_bytes()],
+        bump,
+        space = 8 + Tick::INIT_SPACE,
+    )]
+    pub min_tick: Box<Account<'info, Tick>>,
+
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [TICK_SEED, config.key().as_ref(), &Tick::bounds(tick_spacing).1.to_le_bytes()],
+        bump,
+        space = 8 + Tick::INIT_SPACE,
+    )]
+    pub max_tick: Box<Account<'info, Tick>>,
+
+    #[account(
+        init,
+        payer = initializer,
+        associated_token::mint = mint_x,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_x,
+    )]
+    pub vault_x: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = initializer,
+        associated_token::mint = mint_y,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_y,
+    )]
+    pub vault_y: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program_x: Interface<'info, TokenInterface>,
+
+    pub token_program_y: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeConcentrated<'info> {
+    /// Creates a concentrated liquidity pool at Q64.64 square root price
+    /// `sqrt_price`, with sentinel ticks at the ends of its usable range.
+    pub fn init(
+        &mut self,
+        seed: u64,
+        fee: u16,
+        authority: Option<Pubkey>,
+        tick_spacing: u16,
+        sqrt_price: u128,
+        bumps: InitializeConcentratedBumps,
+    ) -> Result<()> {
+        require!(fee <= MAX_FEE, AmmError::InvalidFee);
+        require!(
+            (1..=MAX_TICK_SPACING).contains(&tick_spacing),
+            AmmError::InvalidTickRange
+        );
+
+        let (min_index, max_index) = Tick::bounds(tick_spacing);
+        require!(
+            sqrt_price > concentrated::sqrt_price_at_tick(min_index)
+                && sqrt_price < concentrated::sqrt_price_at_tick(max_index),
+            AmmError::InvalidPrice
+        );
+        extensions::check_mint(&self.mint_x)?;
+        extensions::check_mint(&self.mint_y)?;
+
+        let now = Clock::get()?.unix_timestamp;
+
+        self.config.set_inner(Config {
+            seed,
+            authority,
+            mint_x: self.mint_x.key(),
+            mint_y: self.mint_y.key(),
+            fee,
+            locked: false,
+            config_bump: bumps.config,
+            lp_bump: 0,
+            version: CONFIG_VERSION,
+            pending_authority: None,
+            dynamic_fee: false,
+            min_fee: 0,
+            max_fee: 0,
+            curve: CurveType::Concentrated,
+            initial_amp: 0,
+            target_amp: 0,
+            ramp_start_timestamp: now,
+            ramp_stop_timestamp: now,
+            weight_x: 0,
+            weight_y: 0,
+            tick_spacing,
+            sqrt_price,
+            tick_current: concentrated::tick_at_sqrt_price(sqrt_price),
+            tick_below: min_index,
+            tick_above: max_index,
+            liquidity: 0,
+            fee_growth_global_x: 0,
+            fee_growth_global_y: 0,
+            protocol_fee: 0,
+            protocol_fees_x: 0,
+            protocol_fees_y: 0,
+            price_x_cumulative: 0,
+            price_y_cumulative: 0,
+            last_update_timestamp: now,
+            reference_price: 0,
+            volatility: 0,
+            flash_loan_x: 0,
+            flash_loan_y: 0,
+            swaps_paused: false,
+            deposits_paused: false,
+            withdrawals_paused: false,
+            reference_slot: 0,
+        });
+
+        self.oracle.set_inner(Oracle {
+            config: self.config.key(),
+            index: 0,
+            observations: vec![Observation::from(&**self.config)],
+            bump: bumps.oracle,
+        });
+
+        for (tick, index, bump) in [
+            (&mut self.min_tick, min_index, bumps.min_tick),
+            (&mut self.max_tick, max_index, bumps.max_tick),
+        ] {
+            tick.set_inner(Tick {
+                config: self.config.key(),
+                index,
+                liquidity_net: 0,
+                fee_growth_outside_x: 0,
+                fee_growth_outside_y: 0,
+                prev: min_index,
+                next: max_index,
+                bump,
+            });
+        }
+
+        emit!(PoolCreated::new(self.config.key(), &self.config));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{FACTORY_SEED, MAX_FEE, MAX_FEE_TIERS},
+    error::AmmError,
+    state::Factory,
+};
+
+#[derive(Accounts)]
+pub struct InitializeFactory<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [FACTORY_SEED],
+        bump,
+        space = 8 + Factory::INIT_SPACE,
+    )]
+    pub factory: Account<'info, Factory>,
+
+    /// The factory authority administers every pool, so only the program's
+    /// upgrade authority may create it.
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = ProgramData::owner(),
+        constraint = program_data.upgrade_authority_address == Some(authority.key()) @ AmmError::InvalidAuthority,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeFactory<'info> {
+    pub fn init(&mut self, fee_tiers: Vec<u16>, bumps: InitializeFactoryBumps) -> Result<()> {
+        require!(fee_tiers.len() <= MAX_FEE_TIERS, AmmError::InvalidFeeTier);
+        for (i, fee) in fee_tiers.iter().enumerate() {
+            require!(*fee <= MAX_FEE, AmmError::InvalidFee);
+            require!(!fee_tiers[..i].contains(fee), AmmError::InvalidFeeTier);
+        }
+
+        self.factory.set_inner(Factory {
+            authority: self.authority.key(),
+            fee_tiers,
+            pool_count: 0,
+            bump: bumps.factory,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address_with_program_id, AssociatedToken},
+    token::Token,
+    token_2022::Token2022,
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::{
+    constants::{
+        DEAD_SEED, LP_SEED, MAX_FEE, MAX_MULTI_POOL_TOKENS, MIN_MULTI_POOL_TOKENS, MIN_WEIGHT,
+        MULTI_POOL_SEED, TOTAL_WEIGHT,
+    },
+    error::AmmError,
+    extensions,
+    state::{MultiPool, PoolToken},
+};
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct InitializeMultiPool<'info> {
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [MULTI_POOL_SEED, MultiPool::encode_seed(seed).as_ref()],
+        bump,
+        space = 8 + MultiPool::INIT_SPACE,
+    )]
+    pub pool: Box<Account<'info, MultiPool>>,
+
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [LP_SEED, pool.key().as_ref()],
+        bump,
+        mint::decimals = 6,
+        mint::authority = pool,
+        mint::token_program = token_program,
+    )]
+    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: PDA that never signs, owner of the locked minimum liquidity.
+    #[account(
+        seeds = [DEAD_SEED, pool.key().as_ref()],
+        bump,
+    )]
+    pub dead: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = initializer,
+        associated_token::mint = mint_lp,
+        associated_token::authority = dead,
+        associated_token::token_program = token_program,
+    )]
+    pub locked_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Program of the LP mint and of classic token mints.
+    pub token_program: Program<'info, Token>,
+
+    pub token_program_2022: Program<'info, Token2022>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeMultiPool<'info> {
+    /// Initializes a pool of one token per entry of `weights`, each at least
+    /// `MIN_WEIGHT` and together `TOTAL_WEIGHT`. `remaining` holds each
+    /// token's mint followed by the pool's associated token account for it,
+    /// which is created here.
+    ///
+    /// Multi-asset pools are outside the factory: a basket has no
+    /// canonical pair to key it by, so its creator picks its fee and
+    /// authority and it is not listed in the registry.
+    pub fn init(
+        &mut self,
+        seed: u64,
+        fee: u16,
+        authority: Option<Pubkey>,
+        weights: &[u16],
+        bumps: InitializeMultiPoolBumps,
+        remaining: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(fee <= MAX_FEE, AmmError::InvalidFee);
+        require!(
+            (MIN_MULTI_POOL_TOKENS..=MAX_MULTI_POOL_TOKENS).contains(&weights.len()),
+            AmmError::InvalidPoolTokens
+        );
+        require!(
+            remaining.len() == 2 * weights.len(),
+            AmmError::InvalidTokenAccounts
+        );
+        require!(
+            weights.iter().all(|weight| *weight >= MIN_WEIGHT)
+                && weights.iter().map(|weight| *weight as u32).sum::<u32>() == TOTAL_WEIGHT as u32,
+            AmmError::InvalidWeight
+        );
+
+        let mut tokens = Vec::with_capacity(weights.len());
+        for (accounts, weight) in remaining.chunks(2).zip(weights) {
+            let (mint, vault) = (&accounts[0], &accounts[1]);
+            extensions::check_mint(&InterfaceAccount::<Mint>::try_from(mint)?)?;
+            require!(
+                tokens.iter().all(|token: &PoolToken| token.mint != mint.key()),
+                AmmError::InvalidPoolTokens
+            );
+            self.create_vault(mint, vault)?;
+            tokens.push(PoolToken {
+                mint: mint.key(),
+                weight: *weight,
+            });
+        }
+
+        self.pool.set_inner(MultiPool {
+            seed,
+            authority,
+            fee,
+            tokens,
+            locked: false,
+            bump: bumps.pool,
+            lp_bump: bumps.mint_lp,
+        });
+
+        Ok(())
+    }
+
+    fn create_vault(&self, mint: &AccountInfo<'info>, vault: &AccountInfo<'info>) -> Result<()> {
+        let token_program = match *mint.owner == Token2022::id() {
+            true => self.token_program_2022.to_account_info(),
+            false => self.token_program.to_account_info(),
+        };
+        require_keys_eq!(
+            vault.key(),
+            get_associated_token_address_with_program_id(&self.pool.key(), mint.key, token_program.key),
+            AmmError::InvalidTokenAccounts
+        );
+
+        let cpi_accounts = associated_token::Create {
+            payer: self.initializer.to_account_info(),
+            associated_token: vault.clone(),
+            authority: self.pool.to_account_info(),
+            mint: mint.clone(),
+            system_program: self.system_program.to_account_info(),
+            token_program,
+        };
+
+        let ctx = CpiContext::new(self.associated_token_program.to_account_info(), cpi_accounts);
+
+        associated_token::create(ctx)
+    }
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{CONFIG_SEED, TICK_SEED},
+    error::AmmError,
+    state::{Config, CurveType, Tick},
+};
+
+#[derive(Accounts)]
+#[instruction(index: i32)]
+pub struct InitializeTick<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED, Config::encode_seed(config.seed).as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [TICK_SEED, config.key().as_ref(), &index.to_le_bytes()],
+        bump,
+        space = 8 + Tick::INIT_SPACE,
+    )]
+    pub tick: Box<Account<'info, Tick>>,
+
+    /// Initialized ticks directly below and above `index`.
+    #[account(mut, has_one = config)]
+    pub prev: Box<Account<'info, Tick>>,
+
+    #[account(mut, has_one = config)]
+    pub next: Box<Account<'info, Tick>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeTick<'info> {
+    /// Links a new tick into the pool's list between `prev` and `next`.
+    pub fn init(&mut self, index: i32, bumps: InitializeTickBumps) -> Result<()> {
+        require!(self.config.curve == CurveType::Concentrated, AmmError::InvalidCurve);
+        require!(
+            index % self.config.tick_spacing as i32 == 0,
+            AmmError::InvalidTickRange
+        );
+        require!(
+            self.prev.next == self.next.index
+                && self.prev.index < index
+                && index < self.next.index,
+            AmmError::InvalidTick
+        );
+
+        // Fee growth so far is attributed to below the current price.
+        let (outside_x, outside_y) = match index <= self.config.tick_current {
+            true => (self.config.fee_growth_global_x, self.config.fee_growth_global_y),
+            false => (0, 0),
+        };
+
+        self.tick.set_inner(Tick {
+            config: self.config.key(),
+            index,
+            liquidity_net: 0,
+            fee_growth_outside_x: outside_x,
+            fee_growth_outside_y: outside_y,
+            prev: self.prev.index,
+            next: self.next.index,
+            bump: bumps.tick,
+        });
+        self.prev.next = index;
+        self.next.prev = index;
+
+        if self.prev.index == self.config.tick_below && self.next.index == self.config.tick_above {
+            match index <= self.config.tick_current {
+                true => self.config.tick_below = index,
+                false => self.config.tick_above = index,
+            }
+        }
+
+        Ok(())
+    }
+}
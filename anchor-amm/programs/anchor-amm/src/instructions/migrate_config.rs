@@ -0,0 +1,206 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+    Discriminator,
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::Token,
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::{
+    constants::{CONFIG_VERSION, DEAD_SEED, LP_SEED, MAX_FEE, ORACLE_SEED},
+    error::AmmError,
+    events::ConfigMigrated,
+    state::{Config, CurveType, Observation, Oracle},
+};
+
+/// A config as the first release laid it out, still the prefix of `Config`.
+#[derive(AnchorDeserialize)]
+struct ConfigV0 {
+    seed: u64,
+    authority: Option<Pubkey>,
+    mint_x: Pubkey,
+    mint_y: Pubkey,
+    fee: u16,
+    locked: bool,
+    config_bump: u8,
+    lp_bump: u8,
+}
+
+impl ConfigV0 {
+    /// The config `initialize` would have created for this constant
+    /// product pool, with its fee capped at `MAX_FEE`.
+    fn upgrade(self, now: i64) -> Config {
+        Config {
+            seed: self.seed,
+            authority: self.authority,
+            mint_x: self.mint_x,
+            mint_y: self.mint_y,
+            fee: self.fee.min(MAX_FEE),
+            locked: self.locked,
+            config_bump: self.config_bump,
+            lp_bump: self.lp_bump,
+            version: CONFIG_VERSION,
+            pending_authority: None,
+            dynamic_fee: false,
+            min_fee: 0,
+            max_fee: 0,
+            curve: CurveType::ConstantProduct,
+            initial_amp: 0,
+            target_amp: 0,
+            ramp_start_timestamp: now,
+            ramp_stop_timestamp: now,
+            weight_x: 0,
+            weight_y: 0,
+            tick_spacing: 0,
+            sqrt_price: 0,
+            tick_current: 0,
+            tick_below: 0,
+            tick_above: 0,
+            liquidity: 0,
+            fee_growth_global_x: 0,
+            fee_growth_global_y: 0,
+            protocol_fee: 0,
+            protocol_fees_x: 0,
+            protocol_fees_y: 0,
+            price_x_cumulative: 0,
+            price_y_cumulative: 0,
+            last_update_timestamp: now,
+            reference_price: 0,
+            volatility: 0,
+            flash_loan_x: 0,
+            flash_loan_y: 0,
+            swaps_paused: false,
+            deposits_paused: false,
+            withdrawals_paused: false,
+            reference_slot: 0,
+        }
+    }
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Migration rewrites any pool's config, so only the program's upgrade
+    /// authority may run it.
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = ProgramData::owner(),
+        constraint = program_data.upgrade_authority_address == Some(authority.key()) @ AmmError::InvalidAuthority,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// CHECK: an older layout does not deserialize as `Config`, so its
+    /// discriminator and address are checked by hand.
+    #[account(mut, owner = crate::ID)]
+    pub config: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [LP_SEED, config.key().as_ref()],
+        bump,
+        mint::authority = config,
+        mint::token_program = token_program,
+    )]
+    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Pools of the first release were created without one.
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [ORACLE_SEED, config.key().as_ref()],
+        bump,
+        space = 8 + Oracle::INIT_SPACE,
+    )]
+    pub oracle: Box<Account<'info, Oracle>>,
+
+    /// CHECK: PDA that never signs, owner of the locked minimum liquidity.
+    #[account(
+        seeds = [DEAD_SEED, config.key().as_ref()],
+        bump,
+    )]
+    pub dead: UncheckedAccount<'info>,
+
+    /// Pools of the first release were created without one either.
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint_lp,
+        associated_token::authority = dead,
+        associated_token::token_program = token_program,
+    )]
+    pub locked_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Program of the LP mint.
+    pub token_program: Program<'info, Token>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateConfig<'info> {
+    /// Rewrites a config left at an older `Config::version` in the current
+    /// layout, growing the account to fit, and creates the pool accounts
+    /// that version lacked. The authority covers the rent.
+    pub fn migrate(&mut self, bumps: MigrateConfigBumps) -> Result<()> {
+        let (config, legacy_fee) = {
+            let data = self.config.try_borrow_data()?;
+            require!(
+                data.starts_with(Config::DISCRIMINATOR),
+                ErrorCode::AccountDiscriminatorMismatch
+            );
+            let mut fields = &data[Config::DISCRIMINATOR.len()..];
+            let v0 = ConfigV0::deserialize(&mut fields)?;
+            require_keys_eq!(
+                self.config.key(),
+                Config::find_address(v0.seed).0,
+                ErrorCode::ConstraintSeeds
+            );
+
+            // Configs of the first release end, or are zero padded, where
+            // the version now sits. Every later layout change adds a step.
+            match fields.first().copied().unwrap_or(0) {
+                0 => {
+                    let legacy_fee = v0.fee;
+                    let config = v0.upgrade(Clock::get()?.unix_timestamp);
+                    self.oracle.set_inner(Oracle {
+                        config: self.config.key(),
+                        index: 0,
+                        observations: vec![Observation::from(&config)],
+                        bump: bumps.oracle,
+                    });
+                    (config, legacy_fee)
+                }
+                _ => return err!(AmmError::ConfigUpToDate),
+            }
+        };
+
+        let space = 8 + Config::INIT_SPACE;
+        let rent = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(self.config.lamports());
+        if rent > 0 {
+            let cpi_accounts = Transfer {
+                from: self.authority.to_account_info(),
+                to: self.config.to_account_info(),
+            };
+            transfer(CpiContext::new(self.system_program.to_account_info(), cpi_accounts), rent)?;
+        }
+        self.config.resize(space)?;
+
+        config.try_serialize(&mut &mut self.config.try_borrow_mut_data()?[..])?;
+
+        emit!(ConfigMigrated {
+            config: self.config.key(),
+            version: config.version,
+            legacy_fee,
+            fee: config.fee,
+        });
+        Ok(())
+    }
+}
@@ -1,7 +1,73 @@
 pub mod initialize;
-mod deposit;
-mod swap;
-mod withdraw;
-mod update_locked;
+pub mod deposit;
+pub mod swap;
+pub mod withdraw;
+pub mod update_locked;
+pub mod collect_protocol_fees;
+pub mod observe;
+pub mod initialize_concentrated;
+pub mod initialize_tick;
+pub mod open_position;
+pub mod modify_position;
+pub mod close_position;
+pub mod flash_borrow;
+pub mod flash_repay;
+pub mod route_swap;
+pub mod initialize_factory;
+pub mod update_factory;
+pub mod create_pool;
+pub mod create_concentrated_pool;
+pub mod quote;
+pub mod initialize_multi_pool;
+pub mod multi_pool_tokens;
+pub mod multi_pool_liquidity;
+pub mod swap_multi;
+pub mod update_multi_pool;
+pub mod create_farm;
+pub mod update_farm;
+pub mod stake;
+pub mod harvest;
+pub mod swap_sol;
+pub mod deposit_sol;
+pub mod withdraw_sol;
+pub mod place_order;
+pub mod fill_order;
+pub mod cancel_order;
+pub mod migrate_config;
 
 pub use initialize::*;
+pub use deposit::*;
+pub use swap::*;
+pub use withdraw::*;
+pub use update_locked::*;
+pub use collect_protocol_fees::*;
+pub use observe::*;
+pub use initialize_concentrated::*;
+pub use initialize_tick::*;
+pub use open_position::*;
+pub use modify_position::*;
+pub use close_position::*;
+pub use flash_borrow::*;
+pub use flash_repay::*;
+pub use route_swap::*;
+pub use initialize_factory::*;
+pub use update_factory::*;
+pub use create_pool::*;
+pub use create_concentrated_pool::*;
+pub use quote::*;
+pub use initialize_multi_pool::*;
+pub use multi_pool_tokens::*;
+pub use multi_pool_liquidity::*;
+pub use swap_multi::*;
+pub use update_multi_pool::*;
+pub use create_farm::*;
+pub use update_farm::*;
+pub use stake::*;
+pub use harvest::*;
+pub use swap_sol::*;
+pub use deposit_sol::*;
+pub use withdraw_sol::*;
+pub use place_order::*;
+pub use fill_order::*;
+pub use cancel_order::*;
+pub use migrate_config::*;
@@ -0,0 +1,279 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{CONFIG_SEED, ORACLE_SEED, POSITION_SEED, TICK_SEED},
+    curves::concentrated,
+    error::AmmError,
+    extensions,
+    state::{Config, Oracle, Position, Tick},
+};
+
+#[derive(Accounts)]
+pub struct ModifyPosition<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mint::token_program = token_program_x)]
+    pub mint_x: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_y)]
+    pub mint_y: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = mint_x,
+        has_one = mint_y,
+        seeds = [CONFIG_SEED, Config::encode_seed(config.seed).as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        has_one = config,
+        seeds = [ORACLE_SEED, config.key().as_ref()],
+        bump = oracle.bump,
+    )]
+    pub oracle: Box<Account<'info, Oracle>>,
+
+    #[account(
+        mut,
+        seeds = [
+            POSITION_SEED,
+            config.key().as_ref(),
+            owner.key().as_ref(),
+            &position.tick_lower.to_le_bytes(),
+            &position.tick_upper.to_le_bytes(),
+        ],
+        bump = position.bump,
+    )]
+    pub position: Box<Account<'info, Position>>,
+
+    #[account(
+        mut,
+        seeds = [TICK_SEED, config.key().as_ref(), &position.tick_lower.to_le_bytes()],
+        bump = tick_lower.bump,
+    )]
+    pub tick_lower: Box<Account<'info, Tick>>,
+
+    #[account(
+        mut,
+        seeds = [TICK_SEED, config.key().as_ref(), &position.tick_upper.to_le_bytes()],
+        bump = tick_upper.bump,
+    )]
+    pub tick_upper: Box<Account<'info, Tick>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_x,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_x,
+    )]
+    pub vault_x: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_y,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_y,
+    )]
+    pub vault_y: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint_x,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program_x,
+    )]
+    pub user_x: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint_y,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program_y,
+    )]
+    pub user_y: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program_x: Interface<'info, TokenInterface>,
+
+    pub token_program_y: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ModifyPosition<'info> {
+    /// Adds `liquidity` to the position for at most `max_x`/`max_y` sent,
+    /// transfer fees included, unless the clock is past `expiration`.
+    pub fn add_liquidity(
+        &mut self,
+        liquidity: u64,
+        max_x: u64,
+        max_y: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(!self.config.flash_loan_active(), AmmError::FlashLoanActive);
+        require!(!self.config.deposits_paused, AmmError::DepositsPaused);
+        require!(
+            Clock::get()?.unix_timestamp <= expiration,
+            AmmError::OfferExpired
+        );
+        require!(liquidity != 0, AmmError::InvalidAmount);
+
+        let (x, y) = self.modify(liquidity as i128, true)?;
+        let x = extensions::amount_before_fee(&self.mint_x, x)?;
+        let y = extensions::amount_before_fee(&self.mint_y, y)?;
+        require!(x <= max_x && y <= max_y, AmmError::SlippageExceeded);
+
+        self.deposit_tokens(true, x)?;
+        self.deposit_tokens(false, y)
+    }
+
+    /// Removes `liquidity` from the position for at least `min_x`/`min_y`
+    /// received after transfer fees, unless the clock is past `expiration`.
+    /// Earned fees stay in the position until collected.
+    pub fn remove_liquidity(
+        &mut self,
+        liquidity: u64,
+        min_x: u64,
+        min_y: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(!self.config.flash_loan_active(), AmmError::FlashLoanActive);
+        require!(!self.config.withdrawals_paused, AmmError::WithdrawalsPaused);
+        require!(
+            Clock::get()?.unix_timestamp <= expiration,
+            AmmError::OfferExpired
+        );
+        require!(liquidity != 0, AmmError::InvalidAmount);
+        require!(
+            liquidity <= self.position.liquidity,
+            AmmError::InsufficientBalance
+        );
+
+        let (x, y) = self.modify(-(liquidity as i128), false)?;
+        require!(
+            x - extensions::transfer_fee(&self.mint_x, x)? >= min_x
+                && y - extensions::transfer_fee(&self.mint_y, y)? >= min_y,
+            AmmError::SlippageExceeded
+        );
+
+        self.withdraw_tokens(true, x)?;
+        self.withdraw_tokens(false, y)
+    }
+
+    /// Pays out the fees the position has earned.
+    pub fn collect_fees(&mut self) -> Result<()> {
+        require!(!self.config.flash_loan_active(), AmmError::FlashLoanActive);
+
+        self.position
+            .update_fees(&self.config, &self.tick_lower, &self.tick_upper)?;
+
+        let (x, y) = (self.position.fees_owed_x, self.position.fees_owed_y);
+        self.position.fees_owed_x = 0;
+        self.position.fees_owed_y = 0;
+
+        self.withdraw_tokens(true, x)?;
+        self.withdraw_tokens(false, y)
+    }
+
+    /// Applies `delta` to the position, its ticks and, when the range holds
+    /// the current price, the pool's liquidity. Returns the token amounts
+    /// backing `delta`, rounded up when added and down when removed.
+    fn modify(&mut self, delta: i128, round_up: bool) -> Result<(u64, u64)> {
+        let (x, y) = self.config.reserves(self.vault_x.amount, self.vault_y.amount)?;
+        self.oracle.update(&mut self.config, x, y)?;
+
+        self.position
+            .update_fees(&self.config, &self.tick_lower, &self.tick_upper)?;
+
+        self.position.liquidity = u64::try_from(self.position.liquidity as i128 + delta)
+            .map_err(|_| AmmError::Overflow)?;
+        self.tick_lower.liquidity_net = self
+            .tick_lower
+            .liquidity_net
+            .checked_add(delta)
+            .ok_or(AmmError::Overflow)?;
+        self.tick_upper.liquidity_net = self
+            .tick_upper
+            .liquidity_net
+            .checked_sub(delta)
+            .ok_or(AmmError::Overflow)?;
+
+        let current = self.config.tick_current;
+        if self.tick_lower.index <= current && current < self.tick_upper.index {
+            self.config.liquidity = u64::try_from(self.config.liquidity as i128 + delta)
+                .map_err(|_| AmmError::Overflow)?;
+        }
+
+        concentrated::amounts_for_liquidity(
+            self.config.sqrt_price,
+            concentrated::sqrt_price_at_tick(self.tick_lower.index),
+            concentrated::sqrt_price_at_tick(self.tick_upper.index),
+            delta.unsigned_abs() as u64,
+            round_up,
+        )
+        .ok_or(AmmError::Overflow.into())
+    }
+
+    pub fn deposit_tokens(&self, is_x: bool, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let (from, to, mint, token_program) = match is_x {
+            true => (&self.user_x, &self.vault_x, &self.mint_x, &self.token_program_x),
+            false => (&self.user_y, &self.vault_y, &self.mint_y, &self.token_program_y),
+        };
+
+        let accounts = TransferChecked {
+            from: from.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: self.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(token_program.to_account_info(), accounts);
+
+        transfer_checked(cpi_ctx, amount, mint.decimals)
+    }
+
+    pub fn withdraw_tokens(&self, is_x: bool, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let (from, to, mint, token_program) = match is_x {
+            true => (&self.vault_x, &self.user_x, &self.mint_x, &self.token_program_x),
+            false => (&self.vault_y, &self.user_y, &self.mint_y, &self.token_program_y),
+        };
+
+        let accounts = TransferChecked {
+            from: from.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: self.config.to_account_info(),
+        };
+
+        let seed = Config::encode_seed(self.config.seed);
+        let seeds = self.config.signer_seeds(&seed);
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            accounts,
+            signer_seeds,
+        );
+
+        transfer_checked(cpi_ctx, amount, mint.decimals)
+    }
+}
@@ -0,0 +1,282 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::Token,
+    token_2022::Token2022,
+    token_interface::{burn, mint_to, Burn, Mint, MintTo, TokenAccount},
+};
+
+use crate::{
+    constants::{DEAD_SEED, LP_SEED, MINIMUM_LIQUIDITY, MULTI_POOL_SEED},
+    curves::weighted,
+    error::AmmError,
+    extensions,
+    instructions::PoolTokenAccounts,
+    math,
+    state::MultiPool,
+};
+
+#[derive(Accounts)]
+pub struct MultiPoolLiquidity<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [MULTI_POOL_SEED, MultiPool::encode_seed(pool.seed).as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, MultiPool>>,
+
+    #[account(
+        mut,
+        seeds = [LP_SEED, pool.key().as_ref()],
+        bump = pool.lp_bump,
+        mint::decimals = 6,
+        mint::authority = pool,
+        mint::token_program = token_program,
+    )]
+    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_lp,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: PDA that never signs, owner of the locked minimum liquidity.
+    #[account(
+        seeds = [DEAD_SEED, pool.key().as_ref()],
+        bump,
+    )]
+    pub dead: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_lp,
+        associated_token::authority = dead,
+        associated_token::token_program = token_program,
+    )]
+    pub locked_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Program of the LP mint and of classic token mints.
+    pub token_program: Program<'info, Token>,
+
+    pub token_program_2022: Program<'info, Token2022>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MultiPoolLiquidity<'info> {
+    /// Deposits every token in proportion to the pool for `lp` LP, sending
+    /// at most `max_amounts` in pool order. The first deposit instead sets
+    /// the prices with exactly `max_amounts`, mints the weighted invariant
+    /// and locks `MINIMUM_LIQUIDITY` of it; `lp` is then the least LP
+    /// accepted. `remaining` holds every token's accounts in pool order.
+    pub fn deposit(
+        &mut self,
+        lp: u64,
+        max_amounts: &[u64],
+        expiration: i64,
+        remaining: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        self.check(expiration)?;
+        require!(lp != 0, AmmError::InvalidAmount);
+        let tokens = self.load_all(remaining)?;
+        require!(
+            max_amounts.len() == tokens.len(),
+            AmmError::InvalidTokenAccounts
+        );
+
+        if self.mint_lp.supply == 0 {
+            // Price the pool on what the vaults actually receive.
+            let mut balances = Vec::with_capacity(tokens.len());
+            for (token, max) in tokens.iter().zip(max_amounts) {
+                let received = max - extensions::transfer_fee(&token.mint, *max)?;
+                let balance = token.vault.amount.checked_add(received).ok_or(AmmError::Overflow)?;
+                balances.push((token.weight, balance));
+            }
+            let liquidity = weighted::compute_multi_invariant(&balances)
+                .and_then(|liquidity| u64::try_from(liquidity).ok())
+                .ok_or(AmmError::Overflow)?;
+            require!(liquidity > MINIMUM_LIQUIDITY, AmmError::LiquidityLessThanMinimum);
+
+            let minted = liquidity - MINIMUM_LIQUIDITY;
+            require!(minted >= lp, AmmError::SlippageExceeded);
+
+            for (token, max) in tokens.iter().zip(max_amounts) {
+                token.deposit(&self.user, *max)?;
+            }
+            self.mint_lp_token(self.locked_lp.to_account_info(), MINIMUM_LIQUIDITY)?;
+            return self.mint_lp_token(self.user_lp.to_account_info(), minted);
+        }
+
+        let supply = self.mint_lp.supply as u128;
+        for (token, max) in tokens.iter().zip(max_amounts) {
+            let amount = math::mul_div_ceil(token.vault.amount as u128, lp as u128, supply)
+                .and_then(|amount| u64::try_from(amount).ok())
+                .ok_or(AmmError::Overflow)?;
+            let amount = extensions::amount_before_fee(&token.mint, amount)?;
+            require!(amount <= *max, AmmError::SlippageExceeded);
+            token.deposit(&self.user, amount)?;
+        }
+        self.mint_lp_token(self.user_lp.to_account_info(), lp)
+    }
+
+    /// Deposits `amount_in` of token `index` alone for at least `min_lp`
+    /// LP. The part of it the pool in effect swaps into its other tokens
+    /// pays the swap fee. `remaining` holds that token's accounts.
+    pub fn deposit_single(
+        &mut self,
+        index: u8,
+        amount_in: u64,
+        min_lp: u64,
+        expiration: i64,
+        remaining: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        self.check(expiration)?;
+        require!(amount_in != 0, AmmError::InvalidAmount);
+        require!(self.mint_lp.supply != 0, AmmError::NoLiquidityInPool);
+        let token = self.load(index, remaining)?;
+
+        let received = amount_in - extensions::transfer_fee(&token.mint, amount_in)?;
+        let lp = weighted::deposit_single_lp(
+            token.weight,
+            token.vault.amount,
+            self.mint_lp.supply,
+            received,
+            self.pool.fee,
+        )
+        .ok_or(AmmError::Overflow)?;
+        require!(lp != 0, AmmError::InvalidAmount);
+        require!(lp >= min_lp, AmmError::SlippageExceeded);
+
+        token.deposit(&self.user, amount_in)?;
+        self.mint_lp_token(self.user_lp.to_account_info(), lp)
+    }
+
+    /// Burns `lp` LP for every token in proportion to the pool, receiving
+    /// at least `min_amounts` in pool order after transfer fees.
+    /// `remaining` holds every token's accounts in pool order.
+    pub fn withdraw(
+        &mut self,
+        lp: u64,
+        min_amounts: &[u64],
+        expiration: i64,
+        remaining: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        self.check(expiration)?;
+        require!(lp != 0, AmmError::InvalidAmount);
+        let tokens = self.load_all(remaining)?;
+        require!(
+            min_amounts.len() == tokens.len(),
+            AmmError::InvalidTokenAccounts
+        );
+
+        let supply = self.mint_lp.supply as u128;
+        for (token, min) in tokens.iter().zip(min_amounts) {
+            let amount = math::mul_div(token.vault.amount as u128, lp as u128, supply)
+                .and_then(|amount| u64::try_from(amount).ok())
+                .ok_or(AmmError::Overflow)?;
+            let received = amount - extensions::transfer_fee(&token.mint, amount)?;
+            require!(received >= *min, AmmError::SlippageExceeded);
+            token.withdraw(&self.pool, amount)?;
+        }
+        self.burn_lp_token(lp)
+    }
+
+    /// Burns `lp` LP for token `index` alone, receiving at least `min_out`
+    /// after transfer fees. The part of the payout the pool in effect swaps
+    /// out of its other tokens pays the swap fee. `remaining` holds that
+    /// token's accounts.
+    pub fn withdraw_single(
+        &mut self,
+        index: u8,
+        lp: u64,
+        min_out: u64,
+        expiration: i64,
+        remaining: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        self.check(expiration)?;
+        require!(lp != 0, AmmError::InvalidAmount);
+        require!(lp < self.mint_lp.supply, AmmError::InsufficientLiquidity);
+        let token = self.load(index, remaining)?;
+
+        let amount = weighted::withdraw_single_out(
+            token.weight,
+            token.vault.amount,
+            self.mint_lp.supply,
+            lp,
+            self.pool.fee,
+        )
+        .ok_or(AmmError::Overflow)?;
+        let received = amount - extensions::transfer_fee(&token.mint, amount)?;
+        require!(received != 0, AmmError::InvalidAmount);
+        require!(received >= min_out, AmmError::SlippageExceeded);
+
+        token.withdraw(&self.pool, amount)?;
+        self.burn_lp_token(lp)
+    }
+
+    fn check(&self, expiration: i64) -> Result<()> {
+        require!(!self.pool.locked, AmmError::PoolLocked);
+        require!(
+            Clock::get()?.unix_timestamp <= expiration,
+            AmmError::OfferExpired
+        );
+        Ok(())
+    }
+
+    fn token_programs(&self) -> [AccountInfo<'info>; 2] {
+        [
+            self.token_program.to_account_info(),
+            self.token_program_2022.to_account_info(),
+        ]
+    }
+
+    fn load(&self, index: u8, remaining: &'info [AccountInfo<'info>]) -> Result<PoolTokenAccounts<'info>> {
+        PoolTokenAccounts::load(
+            &self.pool,
+            self.user.key,
+            index as usize,
+            remaining,
+            &self.token_programs(),
+        )
+    }
+
+    fn load_all(&self, remaining: &'info [AccountInfo<'info>]) -> Result<Vec<PoolTokenAccounts<'info>>> {
+        PoolTokenAccounts::load_all(&self.pool, self.user.key, remaining, &self.token_programs())
+    }
+
+    fn mint_lp_token(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        let cpi_accounts = MintTo {
+            mint: self.mint_lp.to_account_info(),
+            to,
+            authority: self.pool.to_account_info(),
+        };
+
+        let seed = MultiPool::encode_seed(self.pool.seed);
+        let seeds = self.pool.signer_seeds(&seed);
+        let signer_seeds = &[&seeds[..]];
+
+        let ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+
+        mint_to(ctx, amount)
+    }
+
+    fn burn_lp_token(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = Burn {
+            mint: self.mint_lp.to_account_info(),
+            from: self.user_lp.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+
+        let ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+        burn(ctx, amount)
+    }
+}
@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{transfer_checked, Mint, TokenAccount, TransferChecked},
+};
+
+use crate::{error::AmmError, state::MultiPool};
+
+/// Accounts of one of a `MultiPool`'s tokens, passed as remaining accounts
+/// in the order `[mint, vault, user token account]`.
+pub struct PoolTokenAccounts<'info> {
+    pub index: usize,
+    pub weight: u16,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub user: InterfaceAccount<'info, TokenAccount>,
+    /// Whichever of `token_programs` owns the mint.
+    pub token_program: AccountInfo<'info>,
+}
+
+impl<'info> PoolTokenAccounts<'info> {
+    /// Loads token `index` of `pool` from `accounts`, checking the vault is
+    /// the pool's and the token account holds the mint for `owner`.
+    pub fn load(
+        pool: &Account<'info, MultiPool>,
+        owner: &Pubkey,
+        index: usize,
+        accounts: &'info [AccountInfo<'info>],
+        token_programs: &[AccountInfo<'info>],
+    ) -> Result<Self> {
+        let [mint, vault, user] = accounts else {
+            return err!(AmmError::InvalidTokenAccounts);
+        };
+        let token = pool.tokens.get(index).ok_or(AmmError::InvalidTokenAccounts)?;
+        require_keys_eq!(mint.key(), token.mint, AmmError::InvalidTokenAccounts);
+        require_keys_eq!(
+            vault.key(),
+            get_associated_token_address_with_program_id(&pool.key(), mint.key, mint.owner),
+            AmmError::InvalidTokenAccounts
+        );
+
+        let user = InterfaceAccount::<TokenAccount>::try_from(user)?;
+        require!(
+            user.mint == token.mint && user.owner == *owner,
+            AmmError::InvalidTokenAccounts
+        );
+        let token_program = token_programs
+            .iter()
+            .find(|program| program.key == mint.owner)
+            .ok_or(AmmError::InvalidTokenAccounts)?;
+
+        Ok(Self {
+            index,
+            weight: token.weight,
+            mint: InterfaceAccount::try_from(mint)?,
+            vault: InterfaceAccount::try_from(vault)?,
+            user,
+            token_program: token_program.clone(),
+        })
+    }
+
+    /// Loads every token of `pool`, each from its triple of `accounts` in
+    /// pool order.
+    pub fn load_all(
+        pool: &Account<'info, MultiPool>,
+        owner: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+        token_programs: &[AccountInfo<'info>],
+    ) -> Result<Vec<Self>> {
+        require!(
+            accounts.len() == 3 * pool.tokens.len(),
+            AmmError::InvalidTokenAccounts
+        );
+        accounts
+            .chunks(3)
+            .enumerate()
+            .map(|(index, accounts)| Self::load(pool, owner, index, accounts, token_programs))
+            .collect()
+    }
+
+    /// Moves `amount` from the user's account into the vault.
+    pub fn deposit(&self, user: &Signer<'info>, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.user.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: user.to_account_info(),
+        };
+
+        let ctx = CpiContext::new(self.token_program.clone(), cpi_accounts);
+
+        transfer_checked(ctx, amount, self.mint.decimals)
+    }
+
+    /// Pays `amount` out of the vault to the user's account.
+    pub fn withdraw(&self, pool: &Account<'info, MultiPool>, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.user.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+
+        let seed = MultiPool::encode_seed(pool.seed);
+        let seeds = pool.signer_seeds(&seed);
+        let signer_seeds = &[&seeds[..]];
+
+        let ctx = CpiContext::new_with_signer(self.token_program.clone(), cpi_accounts, signer_seeds);
+
+        transfer_checked(ctx, amount, self.mint.decimals)
+    }
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    constants::{CONFIG_SEED, ORACLE_SEED},
+    curves,
+    error::AmmError,
+    state::{Config, Oracle},
+};
+
+/// Q64.64 time-weighted average prices of x in y and of y in x.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Twap {
+    pub price_x: u128,
+    pub price_y: u128,
+}
+
+#[derive(Accounts)]
+pub struct Observe<'info> {
+    #[account(mint::token_program = token_program_x)]
+    pub mint_x: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_y)]
+    pub mint_y: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        has_one = mint_x,
+        has_one = mint_y,
+        seeds = [CONFIG_SEED, Config::encode_seed(config.seed).as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        has_one = config,
+        seeds = [ORACLE_SEED, config.key().as_ref()],
+        bump = oracle.bump,
+    )]
+    pub oracle: Box<Account<'info, Oracle>>,
+
+    #[account(
+        associated_token::mint = mint_x,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_x,
+    )]
+    pub vault_x: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        associated_token::mint = mint_y,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_y,
+    )]
+    pub vault_y: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program_x: Interface<'info, TokenInterface>,
+
+    pub token_program_y: Interface<'info, TokenInterface>,
+}
+
+impl<'info> Observe<'info> {
+    /// TWAP over the last `window` seconds.
+    pub fn observe(&self, window: u32) -> Result<Twap> {
+        require!(window > 0, AmmError::InvalidAmount);
+        require!(!self.config.flash_loan_active(), AmmError::FlashLoanActive);
+
+        let (x, y) = self.config.reserves(self.vault_x.amount, self.vault_y.amount)?;
+        let now = Clock::get()?.unix_timestamp;
+        let spot = curves::spot_prices(&self.config, x, y, now);
+
+        let (x_now, y_now) = self.oracle.cumulative_at(&self.config, spot, now)?;
+        let (x_then, y_then) =
+            self.oracle
+                .cumulative_at(&self.config, spot, now - window as i64)?;
+
+        Ok(Twap {
+            price_x: x_now.wrapping_sub(x_then) / window as u128,
+            price_y: y_now.wrapping_sub(y_then) / window as u128,
+        })
+    }
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{CONFIG_SEED, POSITION_SEED},
+    error::AmmError,
+    state::{Config, CurveType, Position},
+};
+
+#[derive(Accounts)]
+#[instruction(tick_lower: i32, tick_upper: i32)]
+pub struct OpenPosition<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED, Config::encode_seed(config.seed).as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            POSITION_SEED,
+            config.key().as_ref(),
+            owner.key().as_ref(),
+            &tick_lower.to_le_bytes(),
+            &tick_upper.to_le_bytes(),
+        ],
+        bump,
+        space = 8 + Position::INIT_SPACE,
+    )]
+    pub position: Account<'info, Position>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> OpenPosition<'info> {
+    /// Opens an empty position over `[tick_lower, tick_upper)`. Both ticks
+    /// must be initialized before liquidity is added.
+    pub fn open(&mut self, tick_lower: i32, tick_upper: i32, bumps: OpenPositionBumps) -> Result<()> {
+        require!(self.config.curve == CurveType::Concentrated, AmmError::InvalidCurve);
+
+        let spacing = self.config.tick_spacing as i32;
+        require!(
+            tick_lower < tick_upper && tick_lower % spacing == 0 && tick_upper % spacing == 0,
+            AmmError::InvalidTickRange
+        );
+
+        self.position.set_inner(Position {
+            config: self.config.key(),
+            owner: self.owner.key(),
+            tick_lower,
+            tick_upper,
+            liquidity: 0,
+            fee_growth_inside_x_last: 0,
+            fee_growth_inside_y_last: 0,
+            fees_owed_x: 0,
+            fees_owed_y: 0,
+            bump: bumps.position,
+        });
+
+        Ok(())
+    }
+}
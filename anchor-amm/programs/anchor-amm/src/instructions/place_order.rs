@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{CONFIG_SEED, ORDER_SEED},
+    error::AmmError,
+    state::{Config, Order},
+};
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct PlaceOrder<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED, Config::encode_seed(config.seed).as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint_in: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + Order::INIT_SPACE,
+        seeds = [ORDER_SEED, config.key().as_ref(), maker.key().as_ref(), Order::encode_id(id).as_ref()],
+        bump,
+    )]
+    pub order: Box<Account<'info, Order>>,
+
+    #[account(
+        mut,
+        token::mint = mint_in,
+        token::authority = maker,
+        token::token_program = token_program,
+    )]
+    pub maker_in: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = mint_in,
+        associated_token::authority = order,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PlaceOrder<'info> {
+    /// Escrows `amount` of the pool's x (`is_x`) or y to sell for at least
+    /// `min_out` of the other token. The order holds what reaches the
+    /// escrow, transfer fees taken.
+    pub fn place(&mut self, id: u64, is_x: bool, amount: u64, min_out: u64, bumps: PlaceOrderBumps) -> Result<()> {
+        require!(amount != 0 && min_out != 0, AmmError::InvalidAmount);
+        let mint_in = match is_x {
+            true => self.config.mint_x,
+            false => self.config.mint_y,
+        };
+        require_keys_eq!(self.mint_in.key(), mint_in, AmmError::InvalidOrder);
+
+        let cpi_accounts = TransferChecked {
+            from: self.maker_in.to_account_info(),
+            mint: self.mint_in.to_account_info(),
+            to: self.escrow.to_account_info(),
+            authority: self.maker.to_account_info(),
+        };
+        let ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+        transfer_checked(ctx, amount, self.mint_in.decimals)?;
+
+        self.escrow.reload()?;
+        require!(self.escrow.amount != 0, AmmError::InvalidAmount);
+
+        self.order.set_inner(Order {
+            config: self.config.key(),
+            maker: self.maker.key(),
+            id,
+            mint_in,
+            amount: self.escrow.amount,
+            min_out,
+            bump: bumps.order,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use constant_product_curve::{ConstantProduct, SwapResult};
+
+use crate::{
+    constants::{CONFIG_SEED, LP_SEED},
+    curves,
+    error::AmmError,
+    extensions, math,
+    state::Config,
+};
+
+/// Result of `quote_swap`. Amounts are what the user sends and receives,
+/// transfer fees included.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// Swap fee, in the input token.
+    pub fee: u64,
+    /// Shortfall of the output against the spot price, fees aside, in basis
+    /// points.
+    pub price_impact: u16,
+}
+
+/// Result of `quote_deposit` and `quote_withdraw`: the x and y the user
+/// sends or receives for `lp`, transfer fees included.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LiquidityQuote {
+    pub amount_x: u64,
+    pub amount_y: u64,
+    pub lp: u64,
+}
+
+/// Quotes price the pool as it is, on the same curve paths as the
+/// instructions they preview, but ignore locks, pauses and deadlines.
+/// Concentrated liquidity pools have no LP mint and cannot be quoted.
+#[derive(Accounts)]
+pub struct Quote<'info> {
+    #[account(mint::token_program = token_program_x)]
+    pub mint_x: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_y)]
+    pub mint_y: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        has_one = mint_x,
+        has_one = mint_y,
+        seeds = [CONFIG_SEED, Config::encode_seed(config.seed).as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [LP_SEED, config.key().as_ref()],
+        bump = config.lp_bump,
+    )]
+    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        associated_token::mint = mint_x,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_x,
+    )]
+    pub vault_x: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        associated_token::mint = mint_y,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_y,
+    )]
+    pub vault_y: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program_x: Interface<'info, TokenInterface>,
+
+    pub token_program_y: Interface<'info, TokenInterface>,
+}
+
+impl<'info> Quote<'info> {
+    /// What `swap` (`exact_in`) of `amount` or `swap_exact_out` for `amount`
+    /// of x (`is_x`) or y would send and receive.
+    pub fn quote_swap(&self, is_x: bool, amount: u64, exact_in: bool) -> Result<SwapQuote> {
+        self.with_view(|pool| pool.quote_swap(is_x, amount, exact_in))
+    }
+
+    /// What `deposit` of `lp` would take. The first deposit sets the price
+    /// itself and has nothing to quote.
+    pub fn quote_deposit(&self, lp: u64) -> Result<LiquidityQuote> {
+        self.with_view(|pool| pool.quote_deposit(lp))
+    }
+
+    /// What `withdraw` of `lp` would pay out.
+    pub fn quote_withdraw(&self, lp: u64) -> Result<LiquidityQuote> {
+        self.with_view(|pool| pool.quote_withdraw(lp))
+    }
+
+    fn with_view<T>(&self, quote: impl FnOnce(&PoolView) -> Result<T>) -> Result<T> {
+        let (mint_x, mint_y) = (self.mint_x.to_account_info(), self.mint_y.to_account_info());
+        let (data_x, data_y) = (mint_x.try_borrow_data()?, mint_y.try_borrow_data()?);
+        let clock = Clock::get()?;
+
+        quote(&PoolView {
+            config: &self.config,
+            vault_x: self.vault_x.amount,
+            vault_y: self.vault_y.amount,
+            lp_supply: self.mint_lp.supply,
+            mint_x: &data_x,
+            mint_y: &data_y,
+            epoch: clock.epoch,
+            now: clock.unix_timestamp,
+            slot: clock.slot,
+        })
+    }
+}
+
+/// A pool as quotes read it, from its accounts on-chain or from their
+/// fetched data off-chain, so both price it with the same code.
+pub struct PoolView<'a> {
+    pub config: &'a Config,
+    /// Vault balances, accrued protocol fees included.
+    pub vault_x: u64,
+    pub vault_y: u64,
+    pub lp_supply: u64,
+    /// Raw mint account data, for their transfer fees.
+    pub mint_x: &'a [u8],
+    pub mint_y: &'a [u8],
+    pub epoch: u64,
+    pub now: i64,
+    pub slot: u64,
+}
+
+impl PoolView<'_> {
+    pub fn quote_swap(&self, is_x: bool, amount: u64, exact_in: bool) -> Result<SwapQuote> {
+        require!(amount > 0, AmmError::InvalidAmount);
+        let (x, y) = self.reserves()?;
+
+        // Swaps accrue prices first, which reprices a dynamic fee.
+        let mut config = self.config.clone();
+        config.accumulate_prices(x, y, self.now, self.slot);
+
+        let (mint_in, mint_out) = match is_x {
+            true => (self.mint_x, self.mint_y),
+            false => (self.mint_y, self.mint_x),
+        };
+
+        let (res, amount_in, amount_out) = match exact_in {
+            true => {
+                let received = amount - extensions::transfer_fee_at(mint_in, self.epoch, amount)?;
+                require!(received > 0, AmmError::InvalidAmount);
+
+                let res = curves::swap(&config, x, y, is_x, received, self.now)?;
+                require!(res.withdraw != 0, AmmError::InvalidAmount);
+                let out = res.withdraw
+                    - extensions::transfer_fee_at(mint_out, self.epoch, res.withdraw)?;
+                (res, amount, out)
+            }
+            false => {
+                let withdraw = extensions::amount_before_fee_at(mint_out, self.epoch, amount)?;
+                let res = curves::swap_exact_out(&config, x, y, is_x, withdraw, self.now)?;
+                let amount_in = extensions::amount_before_fee_at(mint_in, self.epoch, res.deposit)?;
+                require!(amount_in != 0, AmmError::InvalidAmount);
+                (res, amount_in, amount)
+            }
+        };
+
+        Ok(SwapQuote {
+            amount_in,
+            amount_out,
+            fee: res.fee,
+            price_impact: self.price_impact(is_x, &res, x, y)?,
+        })
+    }
+
+    pub fn quote_deposit(&self, lp: u64) -> Result<LiquidityQuote> {
+        require!(lp != 0, AmmError::InvalidAmount);
+        require!(self.lp_supply != 0, AmmError::NoLiquidityInPool);
+        let (x, y) = self.reserves()?;
+
+        let amounts = ConstantProduct::xy_deposit_amounts_from_l(x, y, self.lp_supply, lp, 6)
+            .map_err(AmmError::from)?;
+
+        Ok(LiquidityQuote {
+            amount_x: extensions::amount_before_fee_at(self.mint_x, self.epoch, amounts.x)?,
+            amount_y: extensions::amount_before_fee_at(self.mint_y, self.epoch, amounts.y)?,
+            lp,
+        })
+    }
+
+    pub fn quote_withdraw(&self, lp: u64) -> Result<LiquidityQuote> {
+        require!(lp != 0, AmmError::InvalidAmount);
+        let (x, y) = self.reserves()?;
+
+        let amounts = ConstantProduct::xy_withdraw_amounts_from_l(x, y, self.lp_supply, lp, 6)
+            .map_err(AmmError::from)?;
+
+        Ok(LiquidityQuote {
+            amount_x: amounts.x - extensions::transfer_fee_at(self.mint_x, self.epoch, amounts.x)?,
+            amount_y: amounts.y - extensions::transfer_fee_at(self.mint_y, self.epoch, amounts.y)?,
+            lp,
+        })
+    }
+
+    fn reserves(&self) -> Result<(u64, u64)> {
+        require!(!self.config.flash_loan_active(), AmmError::FlashLoanActive);
+        self.config.reserves(self.vault_x, self.vault_y)
+    }
+
+    /// How far the output of `res` falls short of its input after the fee
+    /// at the spot price of reserves `x`/`y`, in basis points.
+    fn price_impact(&self, is_x: bool, res: &SwapResult, x: u64, y: u64) -> Result<u16> {
+        let (price_x, price_y) = curves::spot_prices(self.config, x, y, self.now)
+            .ok_or(AmmError::NoLiquidityInPool)?;
+        let price = match is_x {
+            true => price_x,
+            false => price_y,
+        };
+
+        let ideal = math::mul_div((res.deposit - res.fee) as u128, price, 1 << 64)
+            .ok_or(AmmError::Overflow)?;
+        let shortfall = ideal.saturating_sub(res.withdraw as u128);
+        let impact = match ideal {
+            0 => 0,
+            _ => shortfall * 10_000 / ideal,
+        };
+
+        Ok(impact as u16)
+    }
+}
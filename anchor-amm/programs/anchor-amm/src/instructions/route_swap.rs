@@ -0,0 +1,81 @@
+use std::collections::BTreeSet;
+
+use anchor_lang::prelude::*;
+
+use crate::{error::AmmError, instructions::swap::*};
+
+/// One pool of a `route_swap`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hop {
+    /// Whether the hop sends the pool's x for its y.
+    pub is_x: bool,
+    /// Initialized ticks the hop may cross on a concentrated liquidity pool,
+    /// passed right after its `Swap` accounts.
+    pub ticks: u8,
+}
+
+#[derive(Accounts)]
+pub struct RouteSwap<'info> {
+    pub user: Signer<'info>,
+}
+
+impl<'info> RouteSwap<'info> {
+    /// Sends `amount` through the pools of `hops` in order, each hop sending
+    /// all the previous one received, for at least `min` out of the last.
+    /// `remaining` holds every hop's `Swap` accounts followed by its ticks,
+    /// and each hop must send the token the previous one paid out.
+    pub fn route_swap(
+        &self,
+        hops: &[Hop],
+        amount: u64,
+        min: u64,
+        expiration: i64,
+        remaining: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp <= expiration,
+            AmmError::OfferExpired
+        );
+        require!(!hops.is_empty(), AmmError::InvalidRoute);
+
+        let mut accounts = remaining;
+        let mut amount = amount;
+        let mut mint_in = None;
+
+        for hop in hops {
+            let mut swap = Swap::try_accounts(
+                &crate::ID,
+                &mut accounts,
+                &[],
+                &mut SwapBumps::default(),
+                &mut BTreeSet::new(),
+            )?;
+            require_keys_eq!(swap.user.key(), self.user.key(), AmmError::InvalidRoute);
+
+            let (hop_in, hop_out) = match hop.is_x {
+                true => (swap.mint_x.key(), swap.mint_y.key()),
+                false => (swap.mint_y.key(), swap.mint_x.key()),
+            };
+            if let Some(mint_in) = mint_in {
+                require_keys_eq!(hop_in, mint_in, AmmError::InvalidRoute);
+            }
+
+            require!(
+                accounts.len() >= hop.ticks as usize,
+                ErrorCode::AccountNotEnoughKeys
+            );
+            let (ticks, rest) = accounts.split_at(hop.ticks as usize);
+
+            amount = swap.swap_hop(hop.is_x, amount, ticks)?;
+            // Persist the pool before a later hop loads it again.
+            swap.exit(&crate::ID)?;
+
+            accounts = rest;
+            mint_in = Some(hop_out);
+        }
+
+        require!(amount >= min, AmmError::SlippageExceeded);
+
+        Ok(())
+    }
+}
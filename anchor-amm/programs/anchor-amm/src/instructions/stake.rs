@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_interface::{transfer_checked, Mint, TokenAccount, TransferChecked},
+};
+
+use crate::{
+    constants::{FARM_SEED, FARM_STAKE_SEED},
+    error::AmmError,
+    state::{Farm, FarmStake},
+};
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = mint_lp,
+        seeds = [FARM_SEED, farm.config.as_ref(), farm.reward_mint.as_ref()],
+        bump = farm.bump,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [FARM_STAKE_SEED, farm.key().as_ref(), owner.key().as_ref()],
+        bump,
+        space = 8 + FarmStake::INIT_SPACE,
+    )]
+    pub stake: Box<Account<'info, FarmStake>>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_lp,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_lp,
+        associated_token::authority = farm,
+        associated_token::token_program = token_program,
+    )]
+    pub lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Program of the LP mint.
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Stake<'info> {
+    /// Locks `amount` LP in the farm. Rewards earned so far are kept for
+    /// `harvest`.
+    pub fn stake(&mut self, amount: u64, bumps: StakeBumps) -> Result<()> {
+        require!(amount != 0, AmmError::InvalidAmount);
+
+        // A new stake starts at the farm's current reward per share.
+        if self.stake.owner == Pubkey::default() {
+            self.stake.set_inner(FarmStake {
+                farm: self.farm.key(),
+                owner: self.owner.key(),
+                amount: 0,
+                reward_per_share_last: self.farm.reward_per_share,
+                rewards_owed: 0,
+                bump: bumps.stake,
+            });
+        }
+
+        self.update(amount as i128)?;
+
+        let cpi_accounts = TransferChecked {
+            from: self.owner_lp.to_account_info(),
+            mint: self.mint_lp.to_account_info(),
+            to: self.lp_vault.to_account_info(),
+            authority: self.owner.to_account_info(),
+        };
+
+        let ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+        transfer_checked(ctx, amount, self.mint_lp.decimals)
+    }
+
+    /// Returns `amount` staked LP to the owner. Rewards earned so far are
+    /// kept for `harvest`.
+    pub fn unstake(&mut self, amount: u64) -> Result<()> {
+        require!(amount != 0, AmmError::InvalidAmount);
+        require!(amount <= self.stake.amount, AmmError::InsufficientBalance);
+
+        self.update(-(amount as i128))?;
+
+        let cpi_accounts = TransferChecked {
+            from: self.lp_vault.to_account_info(),
+            mint: self.mint_lp.to_account_info(),
+            to: self.owner_lp.to_account_info(),
+            authority: self.farm.to_account_info(),
+        };
+
+        let seeds = self.farm.signer_seeds();
+        let signer_seeds = &[&seeds[..]];
+
+        let ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+
+        transfer_checked(ctx, amount, self.mint_lp.decimals)
+    }
+
+    /// Credits the stake's rewards up to now, then moves its amount and the
+    /// farm's total by `delta`.
+    fn update(&mut self, delta: i128) -> Result<()> {
+        self.farm.accrue(Clock::get()?.unix_timestamp)?;
+        self.stake.update_rewards(&self.farm)?;
+
+        let apply = |amount: u64| {
+            u64::try_from(amount as i128 + delta).map_err(|_| AmmError::Overflow)
+        };
+        self.stake.amount = apply(self.stake.amount)?;
+        self.farm.total_staked = apply(self.farm.total_staked)?;
+        Ok(())
+    }
+}
@@ -1,14 +1,19 @@
-use std::iter::Map;
-
-use anchor_lang::{accounts, prelude::*};
+use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token:: AssociatedToken,
-    token::{transfer,Mint,Transfer,TokenAccount,Token},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
-use constant_product_curve::{ConstantProduct, CurveError, LiquidityPair};
+use constant_product_curve::SwapResult;
 
-use crate::{error::AmmError, state::Config};
+use crate::{
+    constants::{CONFIG_SEED, ORACLE_SEED},
+    curves::{self, concentrated},
+    error::AmmError,
+    events::Swapped,
+    extensions, math,
+    state::{Config, CurveType, Oracle, Tick},
+};
 
 #[derive(Accounts)]
 pub struct Swap<'info>{
@@ -16,49 +21,66 @@ pub struct Swap<'info>{
     #[account(mut)]
     pub user: Signer<'info>,
     
-    pub mint_x:Account<'info,Mint>,
+    #[account(mint::token_program = token_program_x)]
+    pub mint_x: Box<InterfaceAccount<'info, Mint>>,
     
-    pub mint_y:Account<'info,Mint>,
+    #[account(mint::token_program = token_program_y)]
+    pub mint_y: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         init_if_needed,
         payer = user,
         associated_token::mint = mint_x,
         associated_token::authority = user,
+        associated_token::token_program = token_program_x,
     )]
-    pub user_x:Account<'info,TokenAccount>,
+    pub user_x: Box<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(
         init_if_needed,
         payer = user,
         associated_token::mint = mint_y,
         associated_token::authority = user,
+        associated_token::token_program = token_program_y,
     )]
-    pub user_y:Account<'info,TokenAccount>,
+    pub user_y: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = mint_x,
         associated_token::authority = config,
+        associated_token::token_program = token_program_x,
     )]
-    pub vault_x:Account<'info,TokenAccount>,
+    pub vault_x: Box<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(
         mut,
         associated_token::mint = mint_y,
         associated_token::authority = config,
+        associated_token::token_program = token_program_y,
     )]
-    pub vault_y:Account<'info,TokenAccount>,
+    pub vault_y: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
+        mut,
         has_one = mint_x,
         has_one = mint_y,
-        seeds = [b"config",config.seed.to_le_bytes().as_ref()],
+        seeds = [CONFIG_SEED, Config::encode_seed(config.seed).as_ref()],
         bump = config.config_bump,
     )]
     pub config:Account<'info,Config>,
 
-    pub token_program:Program<'info,Token>,
+    #[account(
+        mut,
+        has_one = config,
+        seeds = [ORACLE_SEED, config.key().as_ref()],
+        bump = oracle.bump,
+    )]
+    pub oracle: Box<Account<'info, Oracle>>,
+
+    pub token_program_x: Interface<'info, TokenInterface>,
+
+    pub token_program_y: Interface<'info, TokenInterface>,
     
     pub associated_token_program: Program<'info, AssociatedToken>,
     
@@ -66,93 +88,325 @@ pub struct Swap<'info>{
 }
 
 impl<'info> Swap<'info> {
-    pub fn swap(&mut self, is_x: bool, amount: u64, min: u64) -> Result<()> {
-        require!(self.config.locked == false, AmmError::PoolLocked);
+    /// Sends `amount` of x (`is_x`) or y for at least `min` of the other
+    /// token received. Transfer fees on either side are paid by the user:
+    /// the curve prices what reaches the vault, and `min` applies to what
+    /// reaches the user.
+    ///
+    /// Both swaps fail once the clock is past `expiration`. On concentrated
+    /// liquidity pools `ticks` are the initialized ticks the swap may cross,
+    /// in the order it crosses them.
+    pub fn swap(
+        &mut self,
+        is_x: bool,
+        amount: u64,
+        min: u64,
+        expiration: i64,
+        ticks: &[AccountInfo],
+    ) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(!self.config.flash_loan_active(), AmmError::FlashLoanActive);
+        require!(!self.config.swaps_paused, AmmError::SwapsPaused);
+        require!(
+            Clock::get()?.unix_timestamp <= expiration,
+            AmmError::OfferExpired
+        );
+
+        let before = self.config.reserves(self.vault_x.amount, self.vault_y.amount)?;
+        let (res, out) = self.price_exact_in(is_x, amount, ticks)?;
+        require!(out >= min, AmmError::SlippageExceeded);
+
+        self.deposit_tokens(is_x,amount)?;
+        self.withdraw_tokens(is_x,res.withdraw)?;
+
+        self.emit_swapped(is_x, amount, &res, before)
+    }
+
+    /// One hop of a `route_swap`: sends `amount` of x (`is_x`) or y with no
+    /// minimum and returns what reaches the user, the route checking its
+    /// own minimum on the last hop.
+    pub fn swap_hop(&mut self, is_x: bool, amount: u64, ticks: &[AccountInfo]) -> Result<u64> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(!self.config.flash_loan_active(), AmmError::FlashLoanActive);
+        require!(!self.config.swaps_paused, AmmError::SwapsPaused);
+
+        let before = self.config.reserves(self.vault_x.amount, self.vault_y.amount)?;
+        let (res, out) = self.price_exact_in(is_x, amount, ticks)?;
+
+        self.deposit_tokens(is_x, amount)?;
+        self.withdraw_tokens(is_x, res.withdraw)?;
+
+        self.emit_swapped(is_x, amount, &res, before)?;
+        Ok(out)
+    }
+
+    /// Sends at most `max_in` of x (`is_x`) or y for exactly `amount_out` of
+    /// the other token received, transfer fees on either side included. The
+    /// required input is rounded up.
+    pub fn swap_exact_out(
+        &mut self,
+        is_x: bool,
+        amount_out: u64,
+        max_in: u64,
+        expiration: i64,
+        ticks: &[AccountInfo],
+    ) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(!self.config.flash_loan_active(), AmmError::FlashLoanActive);
+        require!(!self.config.swaps_paused, AmmError::SwapsPaused);
+        require!(
+            Clock::get()?.unix_timestamp <= expiration,
+            AmmError::OfferExpired
+        );
+        require!(amount_out > 0, AmmError::InvalidAmount);
+
+        let mint_out = match is_x {
+            true => &self.mint_y,
+            false => &self.mint_x,
+        };
+        let withdraw = extensions::amount_before_fee(mint_out, amount_out)?;
+
+        let before = self.config.reserves(self.vault_x.amount, self.vault_y.amount)?;
+        let res = self.execute(is_x, withdraw, false, ticks)?;
+        require!(res.withdraw == withdraw, AmmError::InsufficientLiquidity);
+
+        let mint_in = match is_x {
+            true => &self.mint_x,
+            false => &self.mint_y,
+        };
+        let amount = extensions::amount_before_fee(mint_in, res.deposit)?;
+        require!(amount != 0, AmmError::InvalidAmount);
+        require!(amount <= max_in, AmmError::SlippageExceeded);
+
+        self.deposit_tokens(is_x, amount)?;
+        self.withdraw_tokens(is_x, res.withdraw)?;
+
+        self.emit_swapped(is_x, amount, &res, before)
+    }
+
+    /// Prices sending `amount` of x (`is_x`) or y, transfer fee included,
+    /// and updates the pool for it. Returns the curve's result and the part
+    /// of its output reaching the user.
+    fn price_exact_in(
+        &mut self,
+        is_x: bool,
+        amount: u64,
+        ticks: &[AccountInfo],
+    ) -> Result<(SwapResult, u64)> {
         require!(amount > 0, AmmError::InvalidAmount);
 
-        let mut curve = ConstantProduct::init(
-            self.vault_x.amount,
-            self.vault_y.amount,
-            self.vault_x.amount,
-            self.config.fee,
-            None,
-        )
-        .map_err(AmmError::from)?;
-
-        let p = match is_x {
-            true => LiquidityPair::X,
-            false => LiquidityPair::Y,
+        let mint_in = match is_x {
+            true => &self.mint_x,
+            false => &self.mint_y,
         };
-        
-        let res = curve.swap(p, amount, min).map_err(AmmError::from)?;
+        let received = amount - extensions::transfer_fee(mint_in, amount)?;
+        require!(received > 0, AmmError::InvalidAmount);
 
-        require!(res.deposit!=0,AmmError::InvalidAmount);
+        let res = self.execute(is_x, received, true, ticks)?;
         require!(res.withdraw!=0,AmmError::InvalidAmount);
 
-        self.deposit_tokens(is_x,res.deposit)?;
-        self.withdraw_tokens(is_x,res.withdraw)?;
+        let mint_out = match is_x {
+            true => &self.mint_y,
+            false => &self.mint_x,
+        };
+        let out = res.withdraw - extensions::transfer_fee(mint_out, res.withdraw)?;
+
+        Ok((res, out))
+    }
+
+    /// Emits `Swapped` for `amount_in` sent, against the reserves `before`
+    /// the swap and as they are now.
+    fn emit_swapped(&mut self, is_x: bool, amount_in: u64, res: &SwapResult, before: (u64, u64)) -> Result<()> {
+        self.vault_x.reload()?;
+        self.vault_y.reload()?;
+        let (x, y) = self.config.reserves(self.vault_x.amount, self.vault_y.amount)?;
+
+        emit!(Swapped {
+            config: self.config.key(),
+            user: self.user.key(),
+            is_x,
+            amount_in,
+            amount_out: res.withdraw,
+            fee: res.fee,
+            fee_rate: self.config.fee,
+            reserve_x_before: before.0,
+            reserve_y_before: before.1,
+            reserve_x_after: x,
+            reserve_y_after: y,
+        });
 
         Ok(())
     }
 
+    /// Prices a swap of `amount` in (`exact_in`) or out on the pool's curve
+    /// and updates the pool for it. The amounts are those reaching the vault
+    /// and leaving it.
+    fn execute(
+        &mut self,
+        is_x: bool,
+        amount: u64,
+        exact_in: bool,
+        ticks: &[AccountInfo],
+    ) -> Result<SwapResult> {
+        let (x, y) = self.config.reserves(self.vault_x.amount, self.vault_y.amount)?;
+        self.oracle.update(&mut self.config, x, y)?;
+
+        if self.config.curve == CurveType::Concentrated {
+            return self.swap_concentrated(is_x, amount, exact_in, ticks);
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let res = match exact_in {
+            true => curves::swap(&self.config, x, y, is_x, amount, now)?,
+            false => curves::swap_exact_out(&self.config, x, y, is_x, amount, now)?,
+        };
+        self.config.accrue_protocol_fee(is_x, res.fee)?;
+
+        Ok(res)
+    }
+
+    /// Swaps through the ranges between initialized ticks, moving the price
+    /// and crediting fees to the liquidity in range of each step.
+    fn swap_concentrated(
+        &mut self,
+        is_x: bool,
+        amount: u64,
+        exact_in: bool,
+        ticks: &[AccountInfo],
+    ) -> Result<SwapResult> {
+        let config_key = self.config.key();
+        let config = &mut self.config;
+        let mut ticks = ticks.iter();
+        let mut remaining = amount;
+        let mut deposit = 0u64;
+        let mut withdraw = 0u64;
+        let mut fee = 0u64;
+
+        while remaining > 0 {
+            let next_tick = match is_x {
+                true => config.tick_below,
+                false => config.tick_above,
+            };
+            let target = concentrated::sqrt_price_at_tick(next_tick);
+            let step = match exact_in {
+                true => concentrated::compute_swap_step(
+                    config.sqrt_price,
+                    target,
+                    config.liquidity,
+                    remaining,
+                    config.fee,
+                    is_x,
+                ),
+                false => concentrated::compute_swap_step_exact_out(
+                    config.sqrt_price,
+                    target,
+                    config.liquidity,
+                    remaining,
+                    config.fee,
+                    is_x,
+                ),
+            }
+            .ok_or(AmmError::Overflow)?;
+
+            let step_in = step.amount_in.checked_add(step.fee).ok_or(AmmError::Overflow)?;
+            remaining = remaining
+                .checked_sub(match exact_in {
+                    true => step_in,
+                    false => step.amount_out,
+                })
+                .ok_or(AmmError::Underflow)?;
+            deposit = deposit.checked_add(step_in).ok_or(AmmError::Overflow)?;
+            withdraw = withdraw.checked_add(step.amount_out).ok_or(AmmError::Overflow)?;
+            fee += step.fee;
+
+            let share = config.accrue_protocol_fee(is_x, step.fee)?;
+            if config.liquidity > 0 {
+                let growth = math::mul_div((step.fee - share) as u128, 1 << 64, config.liquidity as u128)
+                    .ok_or(AmmError::Overflow)?;
+                match is_x {
+                    true => config.fee_growth_global_x = config.fee_growth_global_x.wrapping_add(growth),
+                    false => config.fee_growth_global_y = config.fee_growth_global_y.wrapping_add(growth),
+                }
+            }
+            config.sqrt_price = step.sqrt_price;
+
+            if step.sqrt_price != target {
+                config.tick_current = concentrated::tick_at_sqrt_price(step.sqrt_price);
+                continue;
+            }
+
+            let info = ticks.next().ok_or(AmmError::InvalidTick)?;
+            let mut tick = Tick::load(info, &config_key, next_tick)?;
+            require!(!tick.is_sentinel(), AmmError::InsufficientLiquidity);
+
+            tick.cross(config);
+            let liquidity = match is_x {
+                true => {
+                    config.tick_current = tick.index - 1;
+                    config.tick_below = tick.prev;
+                    config.tick_above = tick.index;
+                    config.liquidity as i128 - tick.liquidity_net
+                }
+                false => {
+                    config.tick_current = tick.index;
+                    config.tick_below = tick.index;
+                    config.tick_above = tick.next;
+                    config.liquidity as i128 + tick.liquidity_net
+                }
+            };
+            config.liquidity = u64::try_from(liquidity).map_err(|_| AmmError::Overflow)?;
+            tick.store(info)?;
+        }
+
+        Ok(SwapResult {
+            deposit,
+            withdraw,
+            fee,
+        })
+    }
+
     pub fn deposit_tokens(&mut self,is_x: bool,amount: u64) -> Result<()> {
-         let (from,to) = match is_x {
-             true => (
-              self.user_x.to_account_info(),
-              self.vault_x.to_account_info(),
-             ),
-             false => (
-                self.user_y.to_account_info(),
-                self.vault_y.to_account_info(),
-             )
+         let (from, to, mint, cpi_program) = match is_x {
+             true => (&self.user_x, &self.vault_x, &self.mint_x, &self.token_program_x),
+             false => (&self.user_y, &self.vault_y, &self.mint_y, &self.token_program_y),
          };
 
-         let cpi_program = self.token_program.to_account_info();
-
-         let accounts = Transfer{
+         let accounts = TransferChecked{
             from: from.to_account_info(),
+            mint: mint.to_account_info(),
             to:to.to_account_info(),
             authority:self.user.to_account_info(),
          };
 
-         let cpi_ctx = CpiContext::new(cpi_program, accounts);
+         let cpi_ctx = CpiContext::new(cpi_program.to_account_info(), accounts);
 
-         transfer(cpi_ctx, amount)?;
+         transfer_checked(cpi_ctx, amount, mint.decimals)?;
 
          Ok(())
     }
 
     pub fn withdraw_tokens(&mut self,is_x: bool,amount: u64) -> Result<()>{
-        let (from,to) = match is_x {
-            true => (
-                self.vault_y.to_account_info(),
-                self.user_y.to_account_info(),
-            ),
-            false => (
-                self.vault_x.to_account_info(),
-                self.user_x.to_account_info(),
-            )
+        let (from, to, mint, cpi_program) = match is_x {
+            true => (&self.vault_y, &self.user_y, &self.mint_y, &self.token_program_y),
+            false => (&self.vault_x, &self.user_x, &self.mint_x, &self.token_program_x),
         };
 
-        let cpi_program = self.token_program.to_account_info();
-
-        let accounts = Transfer{
+        let accounts = TransferChecked{
             from:from.to_account_info(),
+            mint:mint.to_account_info(),
             to:to.to_account_info(),
             authority:self.config.to_account_info()
         };
 
-        let seeds = &[
-            &b"config"[..],
-            &self.config.seed.to_be_bytes(),
-            &[self.config.config_bump],
-        ];
+        let seed = Config::encode_seed(self.config.seed);
+        let seeds = self.config.signer_seeds(&seed);
 
         let signer_seeds = &[&seeds[..]];
 
-        let cpi_ctx = CpiContext::new_with_signer(cpi_program, accounts, signer_seeds);
+        let cpi_ctx =
+            CpiContext::new_with_signer(cpi_program.to_account_info(), accounts, signer_seeds);
 
-        transfer(cpi_ctx, amount)?;
+        transfer_checked(cpi_ctx, amount, mint.decimals)?;
 
         Ok(())
     }
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token::Token, token_2022::Token2022};
+
+use crate::{
+    constants::MULTI_POOL_SEED,
+    curves::weighted,
+    error::AmmError,
+    extensions,
+    instructions::PoolTokenAccounts,
+    math,
+    state::MultiPool,
+};
+
+#[derive(Accounts)]
+pub struct SwapMulti<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [MULTI_POOL_SEED, MultiPool::encode_seed(pool.seed).as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, MultiPool>>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+impl<'info> SwapMulti<'info> {
+    /// Sends `amount_in` of token `index_in` for at least `min_out` of
+    /// token `index_out`, received after transfer fees. `remaining` holds
+    /// the accounts of the input token followed by those of the output.
+    pub fn swap(
+        &self,
+        index_in: u8,
+        index_out: u8,
+        amount_in: u64,
+        min_out: u64,
+        expiration: i64,
+        remaining: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(!self.pool.locked, AmmError::PoolLocked);
+        require!(
+            Clock::get()?.unix_timestamp <= expiration,
+            AmmError::OfferExpired
+        );
+        require!(amount_in != 0, AmmError::InvalidAmount);
+        require!(index_in != index_out, AmmError::InvalidTokenAccounts);
+        require!(remaining.len() == 6, AmmError::InvalidTokenAccounts);
+
+        let token_programs = [
+            self.token_program.to_account_info(),
+            self.token_program_2022.to_account_info(),
+        ];
+        let (accounts_in, accounts_out) = remaining.split_at(3);
+        let token_in = PoolTokenAccounts::load(
+            &self.pool,
+            self.user.key,
+            index_in as usize,
+            accounts_in,
+            &token_programs,
+        )?;
+        let token_out = PoolTokenAccounts::load(
+            &self.pool,
+            self.user.key,
+            index_out as usize,
+            accounts_out,
+            &token_programs,
+        )?;
+
+        let received = amount_in - extensions::transfer_fee(&token_in.mint, amount_in)?;
+        let fee = math::mul_div_ceil(received as u128, self.pool.fee as u128, 10_000)
+            .ok_or(AmmError::Overflow)? as u64;
+        let amount_out = weighted::swap_out(
+            token_in.weight,
+            token_out.weight,
+            token_in.vault.amount,
+            token_out.vault.amount,
+            received - fee,
+        )
+        .ok_or(AmmError::Overflow)?;
+        require!(amount_out != 0, AmmError::InvalidAmount);
+
+        let out = amount_out - extensions::transfer_fee(&token_out.mint, amount_out)?;
+        require!(out >= min_out, AmmError::SlippageExceeded);
+
+        token_in.deposit(&self.user, amount_in)?;
+        token_out.withdraw(&self.pool, amount_out)
+    }
+}
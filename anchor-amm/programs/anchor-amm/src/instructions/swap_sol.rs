@@ -0,0 +1,158 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constants::{CONFIG_SEED, ORACLE_SEED, WSOL_SEED},
+    error::AmmError,
+    instructions::swap::Swap,
+    native,
+    state::{Config, Oracle},
+};
+
+/// `Swap`'s accounts, with a temporary wrapped SOL account standing in for
+/// the user's own on the pool's wrapped SOL side, which is left out.
+#[derive(Accounts)]
+pub struct SwapSol<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mint::token_program = token_program_x)]
+    pub mint_x: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_y)]
+    pub mint_y: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_x,
+        associated_token::authority = user,
+        associated_token::token_program = token_program_x,
+    )]
+    pub user_x: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_y,
+        associated_token::authority = user,
+        associated_token::token_program = token_program_y,
+    )]
+    pub user_y: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_x,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_x,
+    )]
+    pub vault_x: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_y,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_y,
+    )]
+    pub vault_y: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = mint_x,
+        has_one = mint_y,
+        seeds = [CONFIG_SEED, Config::encode_seed(config.seed).as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        has_one = config,
+        seeds = [ORACLE_SEED, config.key().as_ref()],
+        bump = oracle.bump,
+    )]
+    pub oracle: Box<Account<'info, Oracle>>,
+
+    /// The pool's wrapped SOL mint.
+    #[account(
+        constraint = native::is_native(&native_mint.key()) @ AmmError::NotNativePool,
+        mint::token_program = native_token_program,
+    )]
+    pub native_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Opened for the user at the start of the instruction and closed at
+    /// the end.
+    #[account(
+        init,
+        payer = user,
+        seeds = [WSOL_SEED, user.key().as_ref()],
+        bump,
+        token::mint = native_mint,
+        token::authority = user,
+        token::token_program = native_token_program,
+    )]
+    pub wsol: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program_x: Interface<'info, TokenInterface>,
+
+    pub token_program_y: Interface<'info, TokenInterface>,
+
+    /// Program of the wrapped SOL mint.
+    pub native_token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SwapSol<'info> {
+    /// `swap` with native SOL on the pool's wrapped SOL side: lamports sent
+    /// are wrapped into the temporary account first, which is closed at the
+    /// end, unwrapping what it holds back to the user.
+    pub fn swap_sol(
+        &mut self,
+        is_x: bool,
+        amount: u64,
+        min: u64,
+        expiration: i64,
+        ticks: &[AccountInfo],
+    ) -> Result<()> {
+        let native_x = native::native_side(&self.mint_x.key(), &self.mint_y.key())?;
+        let native_mint = match native_x {
+            true => self.mint_x.key(),
+            false => self.mint_y.key(),
+        };
+        require_keys_eq!(self.native_mint.key(), native_mint, AmmError::InvalidTokenAccounts);
+        let (user_x, user_y) =
+            native::user_accounts(native_x, &self.wsol, self.user_x.as_deref(), self.user_y.as_deref())?;
+
+        if is_x == native_x {
+            native::wrap(&self.user, &self.wsol, &self.native_token_program, &self.system_program, amount)?;
+        }
+
+        let mut swap = Swap {
+            user: self.user.clone(),
+            mint_x: self.mint_x.clone(),
+            mint_y: self.mint_y.clone(),
+            user_x,
+            user_y,
+            vault_x: self.vault_x.clone(),
+            vault_y: self.vault_y.clone(),
+            config: self.config.clone(),
+            oracle: self.oracle.clone(),
+            token_program_x: self.token_program_x.clone(),
+            token_program_y: self.token_program_y.clone(),
+            associated_token_program: self.associated_token_program.clone(),
+            system_program: self.system_program.clone(),
+        };
+        swap.swap(is_x, amount, min, expiration, ticks)?;
+        // Written back on exit.
+        self.config = swap.config;
+        self.oracle = swap.oracle;
+
+        native::unwrap(&self.user, &self.wsol, &self.native_token_program)
+    }
+}
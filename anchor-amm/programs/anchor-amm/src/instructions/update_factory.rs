@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{FACTORY_SEED, MAX_FEE, MAX_FEE_TIERS},
+    error::AmmError,
+    state::Factory,
+};
+
+#[derive(Accounts)]
+pub struct UpdateFactory<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidAuthority,
+        seeds = [FACTORY_SEED],
+        bump = factory.bump,
+    )]
+    pub factory: Account<'info, Factory>,
+}
+
+impl<'info> UpdateFactory<'info> {
+    /// Lets pools be created with `fee`. Existing tiers are never removed,
+    /// so the pools created with them stay canonical.
+    pub fn add_fee_tier(&mut self, fee: u16) -> Result<()> {
+        require!(fee <= MAX_FEE, AmmError::InvalidFee);
+        require!(
+            !self.factory.fee_tiers.contains(&fee)
+                && self.factory.fee_tiers.len() < MAX_FEE_TIERS,
+            AmmError::InvalidFeeTier
+        );
+
+        self.factory.fee_tiers.push(fee);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{
+    constants::{CONFIG_SEED, FARM_SEED},
+    error::AmmError,
+    state::{Config, Farm},
+};
+
+#[derive(Accounts)]
+pub struct UpdateFarm<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG_SEED, Config::encode_seed(config.seed).as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        has_one = config,
+        has_one = reward_mint,
+        seeds = [FARM_SEED, config.key().as_ref(), reward_mint.key().as_ref()],
+        bump = farm.bump,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(mint::token_program = reward_token_program)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        associated_token::mint = reward_mint,
+        associated_token::authority = farm,
+        associated_token::token_program = reward_token_program,
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> UpdateFarm<'info> {
+    /// Emits `reward_rate` per second from now until `emission_end`,
+    /// replacing the current schedule after accruing it. The reward vault
+    /// must already hold every reward the new schedule and the stakers'
+    /// unharvested rewards add up to; see `fund_farm`.
+    pub fn set_emission(&mut self, reward_rate: u64, emission_end: i64) -> Result<()> {
+        self.config.check_authority(&self.authority.key())?;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(emission_end >= now, AmmError::InvalidEmissionSchedule);
+
+        self.farm.accrue(now)?;
+        self.farm.last_update = now;
+        self.farm.reward_rate = reward_rate;
+        self.farm.emission_end = emission_end;
+
+        let required = self
+            .farm
+            .remaining_emissions(now)
+            .and_then(|remaining| remaining.checked_add(self.farm.rewards_unclaimed))
+            .ok_or(AmmError::Overflow)?;
+        require!(self.reward_vault.amount >= required, AmmError::InsufficientRewards);
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct FundFarm<'info> {
+    pub funder: Signer<'info>,
+
+    #[account(
+        has_one = reward_mint,
+        seeds = [FARM_SEED, farm.config.as_ref(), reward_mint.key().as_ref()],
+        bump = farm.bump,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(mint::token_program = reward_token_program)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = farm,
+        associated_token::token_program = reward_token_program,
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = funder,
+        token::token_program = reward_token_program,
+    )]
+    pub funder_rewards: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> FundFarm<'info> {
+    /// Adds `amount` to the farm's reward vault. Anyone may fund a farm.
+    pub fn fund(&mut self, amount: u64) -> Result<()> {
+        require!(amount != 0, AmmError::InvalidAmount);
+
+        let cpi_accounts = TransferChecked {
+            from: self.funder_rewards.to_account_info(),
+            mint: self.reward_mint.to_account_info(),
+            to: self.reward_vault.to_account_info(),
+            authority: self.funder.to_account_info(),
+        };
+
+        let ctx = CpiContext::new(self.reward_token_program.to_account_info(), cpi_accounts);
+
+        transfer_checked(ctx, amount, self.reward_mint.decimals)
+    }
+}
@@ -1,5 +1,7 @@
 use crate::error::AmmError;
-use crate::state::Config;
+use crate::events::{FeeUpdated, LockUpdated};
+use crate::constants::{CONFIG_SEED, MAX_AMP, MAX_FEE, MAX_AMP_CHANGE, MIN_AMP, MIN_RAMP_DURATION};
+use crate::state::{Config, CurveType};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -10,7 +12,7 @@ pub struct Update<'info> {
     
     #[account(
         mut,
-        seeds = [b"config",config.seed.to_be_bytes().as_ref()],
+        seeds = [CONFIG_SEED, Config::encode_seed(config.seed).as_ref()],
         bump = config.config_bump,
     )]
     pub config: Account<'info,Config>,
@@ -18,21 +20,128 @@ pub struct Update<'info> {
 
 impl<'info>Update<'info>{
     pub fn lock(&mut self) -> Result<()>{
-        require!(
-            self.user.key() == self.config.authority.unwrap(),
-            AmmError::InvalidAmount
-        );
+        self.config.check_authority(&self.user.key())?;
         self.config.locked = true;
+        emit!(LockUpdated::new(self.config.key(), &self.config));
         Ok(())
     }
 
     pub fn unlock(&mut self) -> Result<()>{
+        self.config.check_authority(&self.user.key())?;
+        self.config.locked = false;
+        emit!(LockUpdated::new(self.config.key(), &self.config));
+        Ok(())
+    }
+
+    /// Pauses or resumes swaps, deposits and withdrawals independently.
+    /// Single-sided deposits and withdrawals also swap, so they stop with
+    /// either flag.
+    pub fn set_paused(&mut self, swaps: bool, deposits: bool, withdrawals: bool) -> Result<()> {
+        self.config.check_authority(&self.user.key())?;
+        self.config.swaps_paused = swaps;
+        self.config.deposits_paused = deposits;
+        self.config.withdrawals_paused = withdrawals;
+        emit!(LockUpdated::new(self.config.key(), &self.config));
+        Ok(())
+    }
+
+    /// Proposes `new_authority`, which takes over once it calls
+    /// `accept_authority`. `None` cancels a pending transfer.
+    pub fn transfer_authority(&mut self, new_authority: Option<Pubkey>) -> Result<()> {
+        self.config.check_authority(&self.user.key())?;
+        self.config.pending_authority = new_authority;
+        Ok(())
+    }
+
+    pub fn accept_authority(&mut self) -> Result<()> {
+        let pending = self
+            .config
+            .pending_authority
+            .ok_or(AmmError::NoPendingAuthority)?;
+        require_keys_eq!(self.user.key(), pending, AmmError::InvalidAuthority);
+        self.config.authority = Some(pending);
+        self.config.pending_authority = None;
+        Ok(())
+    }
+
+    /// Gives up the authority for good, leaving the pool's settings and
+    /// pause flags as they are.
+    pub fn renounce_authority(&mut self) -> Result<()> {
+        self.config.check_authority(&self.user.key())?;
+        self.config.authority = None;
+        self.config.pending_authority = None;
+        Ok(())
+    }
+
+    /// Sets a fixed swap fee, at most `MAX_FEE`, leaving dynamic fee mode.
+    pub fn set_fee(&mut self, fee: u16) -> Result<()> {
+        self.config.check_authority(&self.user.key())?;
+        require!(fee <= MAX_FEE, AmmError::InvalidFee);
+        self.config.fee = fee;
+        self.config.dynamic_fee = false;
+        emit!(FeeUpdated::new(self.config.key(), &self.config));
+        Ok(())
+    }
+
+    /// Switches to dynamic fee mode, where the fee follows the pool's
+    /// recent volatility between `min_fee` and `max_fee`, at most `MAX_FEE`.
+    /// The fee is repriced straight away from the volatility tracked so far.
+    pub fn set_dynamic_fee(&mut self, min_fee: u16, max_fee: u16) -> Result<()> {
+        self.config.check_authority(&self.user.key())?;
+        require!(min_fee <= max_fee && max_fee <= MAX_FEE, AmmError::InvalidFee);
+        self.config.dynamic_fee = true;
+        self.config.min_fee = min_fee;
+        self.config.max_fee = max_fee;
+        self.config.fee = self.config.volatility_fee();
+        emit!(FeeUpdated::new(self.config.key(), &self.config));
+        Ok(())
+    }
+
+    pub fn set_protocol_fee(&mut self, protocol_fee: u16) -> Result<()> {
+        self.config.check_authority(&self.user.key())?;
+        require!(protocol_fee <= 10_000, AmmError::InvalidFee);
+        self.config.protocol_fee = protocol_fee;
+        emit!(FeeUpdated::new(self.config.key(), &self.config));
+        Ok(())
+    }
+
+    /// Moves a StableSwap pool's amplification linearly from its current
+    /// value to `target_amp`, reached at `stop_timestamp`.
+    pub fn ramp_amp(&mut self, target_amp: u64, stop_timestamp: i64) -> Result<()> {
+        self.config.check_authority(&self.user.key())?;
+        require!(self.config.curve == CurveType::StableSwap, AmmError::InvalidCurve);
+        require!((MIN_AMP..=MAX_AMP).contains(&target_amp), AmmError::InvalidAmp);
+
+        let now = Clock::get()?.unix_timestamp;
         require!(
-            self.user.key() == self.config.authority.unwrap(),
-            AmmError::InvalidAmount,
+            stop_timestamp >= now.saturating_add(MIN_RAMP_DURATION),
+            AmmError::InvalidRamp
         );
-        self.config.locked = false;
+
+        let current = self.config.amp(now);
+        require!(
+            target_amp <= current * MAX_AMP_CHANGE && target_amp * MAX_AMP_CHANGE >= current,
+            AmmError::InvalidRamp
+        );
+
+        self.config.initial_amp = current;
+        self.config.target_amp = target_amp;
+        self.config.ramp_start_timestamp = now;
+        self.config.ramp_stop_timestamp = stop_timestamp;
+        Ok(())
+    }
+
+    /// Freezes the amplification at its current value.
+    pub fn stop_ramp_amp(&mut self) -> Result<()> {
+        self.config.check_authority(&self.user.key())?;
+        require!(self.config.curve == CurveType::StableSwap, AmmError::InvalidCurve);
+
+        let now = Clock::get()?.unix_timestamp;
+        let current = self.config.amp(now);
+        self.config.initial_amp = current;
+        self.config.target_amp = current;
+        self.config.ramp_start_timestamp = now;
+        self.config.ramp_stop_timestamp = now;
         Ok(())
-        
     }
 }
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::MULTI_POOL_SEED, state::MultiPool};
+
+#[derive(Accounts)]
+pub struct UpdateMultiPool<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MULTI_POOL_SEED, MultiPool::encode_seed(pool.seed).as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, MultiPool>,
+}
+
+impl<'info> UpdateMultiPool<'info> {
+    /// Stops or resumes every swap, deposit and withdrawal of the pool.
+    pub fn set_locked(&mut self, locked: bool) -> Result<()> {
+        self.pool.check_authority(&self.user.key())?;
+        self.pool.locked = locked;
+        Ok(())
+    }
+}
@@ -1,11 +1,21 @@
-use anchor_lang::{accounts::program, prelude::*};
+use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{burn, mint_to, transfer, Burn, Mint, MintTo, Token, TokenAccount, Transfer}
+    token::Token,
+    token_interface::{
+        burn, transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
 };
 use constant_product_curve::ConstantProduct;
 
-use crate::{state::Config,error::AmmError};
+use crate::{
+    constants::{CONFIG_SEED, LP_SEED, ORACLE_SEED},
+    curves,
+    error::AmmError,
+    events::LiquidityRemoved,
+    extensions,
+    state::{Config, Oracle},
+};
 
 #[derive(Accounts)]
 pub struct Withdraw<'info>{
@@ -13,64 +23,86 @@ pub struct Withdraw<'info>{
     #[account(mut)]
     pub user: Signer<'info>,
     
-    pub mint_x: Account<'info, Mint>,
+    #[account(mint::token_program = token_program_x)]
+    pub mint_x: Box<InterfaceAccount<'info, Mint>>,
     
-    pub mint_y: Account<'info,Mint>,
+    #[account(mint::token_program = token_program_y)]
+    pub mint_y: Box<InterfaceAccount<'info, Mint>>,
     
      #[account(
         mut,
-        seeds = [b"lp",config.key().as_ref()],
-        bump = config.config_bump,
+        seeds = [LP_SEED, config.key().as_ref()],
+        bump = config.lp_bump,
+        mint::token_program = token_program,
     )]
-    pub mint_lp: Account<'info,Mint>,
+    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
     
     #[account(
+       mut,
        has_one = mint_x,
        has_one = mint_y,
-       seeds = [b"config",config.seed.to_le_bytes().as_ref()],
+       seeds = [CONFIG_SEED, Config::encode_seed(config.seed).as_ref()],
        bump = config.config_bump,
     )]
     pub config: Account<'info,Config>,
 
+    #[account(
+        mut,
+        has_one = config,
+        seeds = [ORACLE_SEED, config.key().as_ref()],
+        bump = oracle.bump,
+    )]
+    pub oracle: Box<Account<'info, Oracle>>,
+
     #[account(
         mut,
         associated_token::mint = mint_x,
-        associated_token::authority = config
+        associated_token::authority = config,
+        associated_token::token_program = token_program_x,
     )]
-    pub vault_x: Account<'info,TokenAccount>,
+    pub vault_x: Box<InterfaceAccount<'info, TokenAccount>>,
 
      #[account(
         mut,
         associated_token::mint = mint_y,
-        associated_token::authority = config
+        associated_token::authority = config,
+        associated_token::token_program = token_program_y,
     )]
-    pub vault_y: Account<'info,TokenAccount>,
+    pub vault_y: Box<InterfaceAccount<'info, TokenAccount>>,
 
 
     #[account(
         init_if_needed,
         payer = user,
         associated_token::mint = mint_x,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program_x,
     )]
-    pub user_x: Account<'info,TokenAccount>,
+    pub user_x: Box<InterfaceAccount<'info, TokenAccount>>,
 
      #[account(
         init_if_needed,
         payer = user,
         associated_token::mint = mint_y,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program_y,
     )]
-    pub user_y: Account<'info,TokenAccount>,
+    pub user_y: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = mint_lp,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_lp: Account<'info,TokenAccount>,
+    pub user_lp: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// Program of the LP mint.
     pub token_program: Program<'info,Token>,
+
+    pub token_program_x: Interface<'info, TokenInterface>,
+
+    pub token_program_y: Interface<'info, TokenInterface>,
     
     pub associated_token_program: Program<'info,AssociatedToken>,
     
@@ -80,64 +112,155 @@ pub struct Withdraw<'info>{
 
 impl<'info> Withdraw<'info>{
 
-    pub fn withdraw(&mut self,amount: u64,min_x:u64,min_y:u64)->Result<()>{
-        require!(self.config.locked==false, AmmError::PoolLocked);
+    pub fn withdraw(&mut self,amount: u64,min_x:u64,min_y:u64,expiration:i64)->Result<()>{
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(!self.config.flash_loan_active(), AmmError::FlashLoanActive);
+        require!(!self.config.withdrawals_paused, AmmError::WithdrawalsPaused);
+        require!(
+            Clock::get()?.unix_timestamp <= expiration,
+            AmmError::OfferExpired
+        );
         require!(amount!=0,AmmError::InvalidAmount);
         require!(min_x != 0 || min_y != 0 ,AmmError::InvalidAmount);
 
+        let (x, y) = self.config.reserves(self.vault_x.amount, self.vault_y.amount)?;
+        self.oracle.update(&mut self.config, x, y)?;
+
         let amounts = ConstantProduct::xy_withdraw_amounts_from_l(
-            self.vault_x.amount,
-            self.vault_y.amount,
+            x,
+            y,
             self.mint_lp.supply,
             amount,
             6,
             )
             .map_err(AmmError::from)?;
 
+        // The minimums apply to what the user receives after transfer fees.
+        let received_x = amounts.x - extensions::transfer_fee(&self.mint_x, amounts.x)?;
+        let received_y = amounts.y - extensions::transfer_fee(&self.mint_y, amounts.y)?;
         require!(
-            min_x <= amounts.x && min_y <= amounts.y,
+            min_x <= received_x && min_y <= received_y,
             AmmError::SlippageExceeded
         );
 
 
+        let supply = self.mint_lp.supply;
         self.withdraw_tokens(true, amounts.x)?;
         self.withdraw_tokens(false, amounts.y)?;
         self.burn_lp_token(amount)?;
 
+        self.emit_removed(amounts.x, amounts.y, amount, (x, y, supply))
+    }
+
+    /// Burns `amount` LP for x (`is_x`) or y alone: the pool swaps the
+    /// other side of the withdrawal through its own curve, after the
+    /// withdrawal, and pays out at least `min_out` received in total.
+    pub fn withdraw_single(
+        &mut self,
+        is_x: bool,
+        amount: u64,
+        min_out: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(!self.config.flash_loan_active(), AmmError::FlashLoanActive);
+        require!(!self.config.withdrawals_paused, AmmError::WithdrawalsPaused);
+        require!(!self.config.swaps_paused, AmmError::SwapsPaused);
+        require!(
+            Clock::get()?.unix_timestamp <= expiration,
+            AmmError::OfferExpired
+        );
+        require!(amount != 0, AmmError::InvalidAmount);
+
+        let (x, y) = self.config.reserves(self.vault_x.amount, self.vault_y.amount)?;
+        self.oracle.update(&mut self.config, x, y)?;
+
+        let amounts =
+            ConstantProduct::xy_withdraw_amounts_from_l(x, y, self.mint_lp.supply, amount, 6)
+                .map_err(AmmError::from)?;
+        let (kept, swapped) = match is_x {
+            true => (amounts.x, amounts.y),
+            false => (amounts.y, amounts.x),
+        };
+
+        let swapped_out = match swapped {
+            0 => 0,
+            _ => {
+                let now = Clock::get()?.unix_timestamp;
+                let (x, y) = (x - amounts.x, y - amounts.y);
+                let res = curves::swap(&self.config, x, y, !is_x, swapped, now)?;
+                self.config.accrue_protocol_fee(!is_x, res.fee)?;
+                res.withdraw
+            }
+        };
+        let total = kept.checked_add(swapped_out).ok_or(AmmError::Overflow)?;
+        require!(total != 0, AmmError::InvalidAmount);
+
+        let mint_out = match is_x {
+            true => &self.mint_x,
+            false => &self.mint_y,
+        };
+        let received = total - extensions::transfer_fee(mint_out, total)?;
+        require!(received >= min_out, AmmError::SlippageExceeded);
+
+        let supply = self.mint_lp.supply;
+        self.withdraw_tokens(is_x, total)?;
+        self.burn_lp_token(amount)?;
+
+        let (amount_x, amount_y) = match is_x {
+            true => (total, 0),
+            false => (0, total),
+        };
+        self.emit_removed(amount_x, amount_y, amount, (x, y, supply))
+    }
+
+    /// Emits `LiquidityRemoved` for the reserves and LP supply `before` the
+    /// withdrawal and as they are now.
+    fn emit_removed(&mut self, amount_x: u64, amount_y: u64, lp: u64, before: (u64, u64, u64)) -> Result<()> {
+        self.vault_x.reload()?;
+        self.vault_y.reload()?;
+        self.mint_lp.reload()?;
+        let (x, y) = self.config.reserves(self.vault_x.amount, self.vault_y.amount)?;
+
+        emit!(LiquidityRemoved {
+            config: self.config.key(),
+            user: self.user.key(),
+            amount_x,
+            amount_y,
+            lp,
+            reserve_x_before: before.0,
+            reserve_y_before: before.1,
+            lp_supply_before: before.2,
+            reserve_x_after: x,
+            reserve_y_after: y,
+            lp_supply_after: self.mint_lp.supply,
+        });
+
         Ok(())
     }
 
  pub fn withdraw_tokens(&mut self,is_x: bool,amount: u64) -> Result<()>{
-        let (from,to) = match is_x {
-            true => (
-                self.vault_y.to_account_info(),
-                self.user_y.to_account_info(),
-            ),
-            false => (
-                self.vault_x.to_account_info(),
-                self.user_x.to_account_info(),
-            )
+        let (from, to, mint, cpi_program) = match is_x {
+            true => (&self.vault_x, &self.user_x, &self.mint_x, &self.token_program_x),
+            false => (&self.vault_y, &self.user_y, &self.mint_y, &self.token_program_y),
         };
 
-        let cpi_program = self.token_program.to_account_info();
-
-        let accounts = Transfer{
+        let accounts = TransferChecked{
             from:from.to_account_info(),
+            mint:mint.to_account_info(),
             to:to.to_account_info(),
             authority:self.config.to_account_info()
         };
 
-        let seeds = &[
-            &b"config"[..],
-            &self.config.seed.to_be_bytes(),
-            &[self.config.config_bump],
-        ];
+        let seed = Config::encode_seed(self.config.seed);
+        let seeds = self.config.signer_seeds(&seed);
 
         let signer_seeds = &[&seeds[..]];
 
-        let cpi_ctx = CpiContext::new_with_signer(cpi_program, accounts, signer_seeds);
+        let cpi_ctx =
+            CpiContext::new_with_signer(cpi_program.to_account_info(), accounts, signer_seeds);
 
-        transfer(cpi_ctx, amount)?;
+        transfer_checked(cpi_ctx, amount, mint.decimals)?;
 
         Ok(())
     }
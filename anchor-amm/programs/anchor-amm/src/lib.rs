@@ -1,6 +1,16 @@
+#![allow(unexpected_cfgs)]
+// The IDL instructions `#[program]` generates beside the program module still
+// call the deprecated `AccountInfo::realloc`.
+#![allow(deprecated)]
+
 pub mod constants;
+pub mod curves;
 pub mod error;
+pub mod events;
+pub mod extensions;
 pub mod instructions;
+pub mod math;
+pub mod native;
 pub mod state;
 
 use anchor_lang::prelude::*;
@@ -15,7 +25,395 @@ declare_id!("zKEMUfo6DCa3uD1vRQ8eq5W4aA3akCdRAEMoKDNS9RP");
 pub mod anchor_amm {
     use super::*;
 
-    // pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-    //     initialize::handler(ctx)
-    // }
+    pub fn initialize_factory(ctx: Context<InitializeFactory>, fee_tiers: Vec<u16>) -> Result<()> {
+        ctx.accounts.init(fee_tiers, ctx.bumps)
+    }
+
+    pub fn add_fee_tier(ctx: Context<UpdateFactory>, fee: u16) -> Result<()> {
+        ctx.accounts.add_fee_tier(fee)
+    }
+
+    pub fn create_pool(
+        ctx: Context<CreatePool>,
+        seed: u64,
+        fee: u16,
+        curve: CurveType,
+        amp: u64,
+    ) -> Result<()> {
+        ctx.accounts.create(seed, fee, curve, amp, ctx.bumps)
+    }
+
+    pub fn create_weighted_pool(
+        ctx: Context<CreateWeightedPool>,
+        seed: u64,
+        fee: u16,
+        weight_x: u16,
+    ) -> Result<()> {
+        ctx.accounts.create(seed, fee, weight_x, ctx.bumps)
+    }
+
+    pub fn create_concentrated_pool(
+        ctx: Context<CreateConcentratedPool>,
+        seed: u64,
+        fee: u16,
+        tick_spacing: u16,
+        sqrt_price: u128,
+    ) -> Result<()> {
+        ctx.accounts
+            .create(seed, fee, tick_spacing, sqrt_price, ctx.bumps)
+    }
+
+    pub fn initialize_tick(ctx: Context<InitializeTick>, index: i32) -> Result<()> {
+        ctx.accounts.init(index, ctx.bumps)
+    }
+
+    pub fn open_position(
+        ctx: Context<OpenPosition>,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> Result<()> {
+        ctx.accounts.open(tick_lower, tick_upper, ctx.bumps)
+    }
+
+    pub fn add_liquidity(
+        ctx: Context<ModifyPosition>,
+        liquidity: u64,
+        max_x: u64,
+        max_y: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .add_liquidity(liquidity, max_x, max_y, expiration)
+    }
+
+    pub fn remove_liquidity(
+        ctx: Context<ModifyPosition>,
+        liquidity: u64,
+        min_x: u64,
+        min_y: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .remove_liquidity(liquidity, min_x, min_y, expiration)
+    }
+
+    pub fn collect_fees(ctx: Context<ModifyPosition>) -> Result<()> {
+        ctx.accounts.collect_fees()
+    }
+
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        ctx.accounts.close()
+    }
+
+    pub fn deposit(
+        ctx: Context<Deposit>,
+        amount: u64,
+        max_x: u64,
+        max_y: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts.deposit(amount, max_x, max_y, expiration)
+    }
+
+    pub fn withdraw(
+        ctx: Context<Withdraw>,
+        amount: u64,
+        min_x: u64,
+        min_y: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts.withdraw(amount, min_x, min_y, expiration)
+    }
+
+    pub fn deposit_sol(
+        ctx: Context<DepositSol>,
+        amount: u64,
+        max_x: u64,
+        max_y: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts.deposit_sol(amount, max_x, max_y, expiration)
+    }
+
+    pub fn withdraw_sol(
+        ctx: Context<WithdrawSol>,
+        amount: u64,
+        min_x: u64,
+        min_y: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts.withdraw_sol(amount, min_x, min_y, expiration)
+    }
+
+    pub fn deposit_single(
+        ctx: Context<Deposit>,
+        is_x: bool,
+        amount_in: u64,
+        min_lp: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .deposit_single(is_x, amount_in, min_lp, expiration)
+    }
+
+    pub fn withdraw_single(
+        ctx: Context<Withdraw>,
+        is_x: bool,
+        amount: u64,
+        min_out: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .withdraw_single(is_x, amount, min_out, expiration)
+    }
+
+    pub fn swap(
+        ctx: Context<Swap>,
+        is_x: bool,
+        amount: u64,
+        min: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .swap(is_x, amount, min, expiration, ctx.remaining_accounts)
+    }
+
+    pub fn swap_sol(
+        ctx: Context<SwapSol>,
+        is_x: bool,
+        amount: u64,
+        min: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .swap_sol(is_x, amount, min, expiration, ctx.remaining_accounts)
+    }
+
+    pub fn swap_exact_out(
+        ctx: Context<Swap>,
+        is_x: bool,
+        amount_out: u64,
+        max_in: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .swap_exact_out(is_x, amount_out, max_in, expiration, ctx.remaining_accounts)
+    }
+
+    pub fn route_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RouteSwap<'info>>,
+        hops: Vec<Hop>,
+        amount: u64,
+        min: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .route_swap(&hops, amount, min, expiration, ctx.remaining_accounts)
+    }
+
+    pub fn flash_borrow(ctx: Context<FlashBorrow>, amount_x: u64, amount_y: u64) -> Result<()> {
+        ctx.accounts.flash_borrow(amount_x, amount_y)
+    }
+
+    pub fn flash_repay(ctx: Context<FlashRepay>) -> Result<()> {
+        ctx.accounts.flash_repay()
+    }
+
+    pub fn observe(ctx: Context<Observe>, window: u32) -> Result<Twap> {
+        ctx.accounts.observe(window)
+    }
+
+    pub fn quote_swap(
+        ctx: Context<Quote>,
+        is_x: bool,
+        amount: u64,
+        exact_in: bool,
+    ) -> Result<SwapQuote> {
+        ctx.accounts.quote_swap(is_x, amount, exact_in)
+    }
+
+    pub fn quote_deposit(ctx: Context<Quote>, lp: u64) -> Result<LiquidityQuote> {
+        ctx.accounts.quote_deposit(lp)
+    }
+
+    pub fn quote_withdraw(ctx: Context<Quote>, lp: u64) -> Result<LiquidityQuote> {
+        ctx.accounts.quote_withdraw(lp)
+    }
+
+    pub fn lock(ctx: Context<Update>) -> Result<()> {
+        ctx.accounts.lock()
+    }
+
+    pub fn unlock(ctx: Context<Update>) -> Result<()> {
+        ctx.accounts.unlock()
+    }
+
+    pub fn set_paused(
+        ctx: Context<Update>,
+        swaps: bool,
+        deposits: bool,
+        withdrawals: bool,
+    ) -> Result<()> {
+        ctx.accounts.set_paused(swaps, deposits, withdrawals)
+    }
+
+    pub fn transfer_authority(
+        ctx: Context<Update>,
+        new_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.transfer_authority(new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<Update>) -> Result<()> {
+        ctx.accounts.accept_authority()
+    }
+
+    pub fn renounce_authority(ctx: Context<Update>) -> Result<()> {
+        ctx.accounts.renounce_authority()
+    }
+
+    pub fn set_fee(ctx: Context<Update>, fee: u16) -> Result<()> {
+        ctx.accounts.set_fee(fee)
+    }
+
+    pub fn set_dynamic_fee(ctx: Context<Update>, min_fee: u16, max_fee: u16) -> Result<()> {
+        ctx.accounts.set_dynamic_fee(min_fee, max_fee)
+    }
+
+    pub fn set_protocol_fee(ctx: Context<Update>, protocol_fee: u16) -> Result<()> {
+        ctx.accounts.set_protocol_fee(protocol_fee)
+    }
+
+    pub fn ramp_amp(ctx: Context<Update>, target_amp: u64, stop_timestamp: i64) -> Result<()> {
+        ctx.accounts.ramp_amp(target_amp, stop_timestamp)
+    }
+
+    pub fn stop_ramp_amp(ctx: Context<Update>) -> Result<()> {
+        ctx.accounts.stop_ramp_amp()
+    }
+
+    pub fn collect_protocol_fees(ctx: Context<CollectProtocolFees>) -> Result<()> {
+        ctx.accounts.collect()
+    }
+
+    pub fn initialize_multi_pool<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitializeMultiPool<'info>>,
+        seed: u64,
+        fee: u16,
+        authority: Option<Pubkey>,
+        weights: Vec<u16>,
+    ) -> Result<()> {
+        ctx.accounts
+            .init(seed, fee, authority, &weights, ctx.bumps, ctx.remaining_accounts)
+    }
+
+    pub fn deposit_multi<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MultiPoolLiquidity<'info>>,
+        lp: u64,
+        max_amounts: Vec<u64>,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .deposit(lp, &max_amounts, expiration, ctx.remaining_accounts)
+    }
+
+    pub fn deposit_multi_single<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MultiPoolLiquidity<'info>>,
+        index: u8,
+        amount_in: u64,
+        min_lp: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .deposit_single(index, amount_in, min_lp, expiration, ctx.remaining_accounts)
+    }
+
+    pub fn withdraw_multi<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MultiPoolLiquidity<'info>>,
+        lp: u64,
+        min_amounts: Vec<u64>,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .withdraw(lp, &min_amounts, expiration, ctx.remaining_accounts)
+    }
+
+    pub fn withdraw_multi_single<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MultiPoolLiquidity<'info>>,
+        index: u8,
+        lp: u64,
+        min_out: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .withdraw_single(index, lp, min_out, expiration, ctx.remaining_accounts)
+    }
+
+    pub fn swap_multi<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapMulti<'info>>,
+        index_in: u8,
+        index_out: u8,
+        amount_in: u64,
+        min_out: u64,
+        expiration: i64,
+    ) -> Result<()> {
+        ctx.accounts.swap(
+            index_in,
+            index_out,
+            amount_in,
+            min_out,
+            expiration,
+            ctx.remaining_accounts,
+        )
+    }
+
+    pub fn set_multi_pool_locked(ctx: Context<UpdateMultiPool>, locked: bool) -> Result<()> {
+        ctx.accounts.set_locked(locked)
+    }
+
+    pub fn create_farm(ctx: Context<CreateFarm>) -> Result<()> {
+        ctx.accounts.create(ctx.bumps)
+    }
+
+    pub fn fund_farm(ctx: Context<FundFarm>, amount: u64) -> Result<()> {
+        ctx.accounts.fund(amount)
+    }
+
+    pub fn set_emission(ctx: Context<UpdateFarm>, reward_rate: u64, emission_end: i64) -> Result<()> {
+        ctx.accounts.set_emission(reward_rate, emission_end)
+    }
+
+    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+        ctx.accounts.stake(amount, ctx.bumps)
+    }
+
+    pub fn unstake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+        ctx.accounts.unstake(amount)
+    }
+
+    pub fn harvest(ctx: Context<Harvest>) -> Result<()> {
+        ctx.accounts.harvest()
+    }
+
+    pub fn place_order(
+        ctx: Context<PlaceOrder>,
+        id: u64,
+        is_x: bool,
+        amount: u64,
+        min_out: u64,
+    ) -> Result<()> {
+        ctx.accounts.place(id, is_x, amount, min_out, ctx.bumps)
+    }
+
+    pub fn fill_order<'info>(ctx: Context<'_, '_, 'info, 'info, FillOrder<'info>>) -> Result<()> {
+        ctx.accounts.fill(ctx.remaining_accounts)
+    }
+
+    pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
+        ctx.accounts.cancel()
+    }
+
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        ctx.accounts.migrate(ctx.bumps)
+    }
 }
@@ -0,0 +1,145 @@
+/// Integer square root, rounded down.
+pub fn sqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    // Newton's method from an initial guess above the root.
+    let mut x = 1u128 << ((128 - n.leading_zeros()).div_ceil(2));
+    loop {
+        let y = (x + n / x) >> 1;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// `a * b / c` with a 256-bit intermediate product, rounded down. `None`
+/// if `c` is zero or the quotient does not fit in 128 bits.
+pub fn mul_div(a: u128, b: u128, c: u128) -> Option<u128> {
+    if c == 0 {
+        return None;
+    }
+
+    let (hi, lo) = full_mul(a, b);
+    if hi == 0 {
+        return Some(lo / c);
+    }
+    if hi >= c {
+        return None;
+    }
+
+    // Shift-subtract long division of hi:lo by c, keeping rem < c.
+    let mut rem = hi;
+    let mut quotient = 0u128;
+    for i in (0..128).rev() {
+        let carry = rem >> 127;
+        rem = (rem << 1) | ((lo >> i) & 1);
+        quotient <<= 1;
+        if carry == 1 || rem >= c {
+            rem = rem.wrapping_sub(c);
+            quotient |= 1;
+        }
+    }
+
+    Some(quotient)
+}
+
+/// `a * b / c` rounded up.
+pub fn mul_div_ceil(a: u128, b: u128, c: u128) -> Option<u128> {
+    let quotient = mul_div(a, b, c)?;
+    match full_mul(quotient, c) == full_mul(a, b) {
+        true => Some(quotient),
+        false => quotient.checked_add(1),
+    }
+}
+
+/// Full 256-bit product of `a` and `b` as (high, low) halves.
+fn full_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+
+    let (a_lo, a_hi) = (a & MASK, a >> 64);
+    let (b_lo, b_hi) = (b & MASK, b >> 64);
+
+    let ll = a_lo * b_lo;
+    let lh = a_lo * b_hi;
+    let hl = a_hi * b_lo;
+    let hh = a_hi * b_hi;
+
+    let mid = (ll >> 64) + (lh & MASK) + (hl & MASK);
+    let lo = (ll & MASK) | (mid << 64);
+    let hi = hh + (lh >> 64) + (hl >> 64) + (mid >> 64);
+
+    (hi, lo)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn mul_div_known_values() {
+        assert_eq!(mul_div(7, 3, 2), Some(10));
+        assert_eq!(mul_div(1, 1, 0), None);
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+        assert_eq!(mul_div(u128::MAX, 2, 1), None);
+        assert_eq!(mul_div(1 << 127, 4, 8), Some(1 << 126));
+        assert_eq!(mul_div(u128::MAX, u128::MAX - 1, u128::MAX), Some(u128::MAX - 1));
+        assert_eq!(mul_div(u64::MAX as u128, u64::MAX as u128, 1), Some(u64::MAX as u128 * u64::MAX as u128));
+    }
+
+    #[test]
+    fn mul_div_ceil_rounds_up() {
+        assert_eq!(mul_div_ceil(7, 3, 2), Some(11));
+        assert_eq!(mul_div_ceil(6, 3, 2), Some(9));
+        assert_eq!(mul_div_ceil(0, 3, 2), Some(0));
+        assert_eq!(mul_div_ceil(1, 1, 0), None);
+        assert_eq!(mul_div_ceil(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+        assert_eq!(mul_div_ceil(u128::MAX, u128::MAX - 1, u128::MAX), Some(u128::MAX - 1));
+        assert_eq!(mul_div_ceil(1 << 127, 3, 1 << 126), Some(6));
+        assert_eq!(mul_div_ceil((1 << 127) + 1, 3, 1 << 126), Some(7));
+    }
+
+    #[test]
+    fn sqrt_known_values() {
+        let cases = [
+            (0, 0),
+            (1, 1),
+            (2, 1),
+            (3, 1),
+            (4, 2),
+            (15, 3),
+            (16, 4),
+            (u64::MAX as u128 * u64::MAX as u128, u64::MAX as u128),
+            (u64::MAX as u128 * u64::MAX as u128 - 1, u64::MAX as u128 - 1),
+            (u128::MAX, u64::MAX as u128),
+        ];
+        for (n, root) in cases {
+            assert_eq!(sqrt(n), root, "sqrt({n})");
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn mul_div_matches_u128(a in 0..=u64::MAX as u128, b in 0..=u64::MAX as u128, c in 1..=u128::MAX) {
+            prop_assert_eq!(mul_div(a, b, c), Some(a * b / c));
+            prop_assert_eq!(mul_div_ceil(a, b, c), Some((a * b).div_ceil(c)));
+        }
+
+        #[test]
+        fn mul_div_inverts_a_full_product(a: u128, b in 1..=u128::MAX) {
+            prop_assert_eq!(mul_div(a, b, b), Some(a));
+            prop_assert_eq!(mul_div_ceil(a, b, b), Some(a));
+        }
+
+        #[test]
+        fn sqrt_rounds_down(n: u128) {
+            let root = sqrt(n);
+            prop_assert!(root * root <= n);
+            prop_assert!((root + 1).checked_mul(root + 1).is_none_or(|square| square > n));
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::{
+    token::spl_token,
+    token_2022::spl_token_2022,
+    token_interface::{close_account, sync_native, CloseAccount, SyncNative, TokenAccount, TokenInterface},
+};
+
+use crate::{constants::WSOL_SEED, error::AmmError};
+
+/// Whether `mint` is wrapped SOL, of either token program.
+pub fn is_native(mint: &Pubkey) -> bool {
+    *mint == spl_token::native_mint::ID || *mint == spl_token_2022::native_mint::ID
+}
+
+/// Whether wrapped SOL is the pool's x (`true`) or y token.
+pub fn native_side(mint_x: &Pubkey, mint_y: &Pubkey) -> Result<bool> {
+    match (is_native(mint_x), is_native(mint_y)) {
+        (true, _) => Ok(true),
+        (_, true) => Ok(false),
+        _ => err!(AmmError::NotNativePool),
+    }
+}
+
+/// Address of `user`'s temporary wrapped SOL account.
+pub fn find_wsol(user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WSOL_SEED, user.as_ref()], &crate::ID)
+}
+
+type UserAccount<'info> = Box<InterfaceAccount<'info, TokenAccount>>;
+
+/// The user's x and y accounts for an instruction moving lamports on the
+/// pool's wrapped SOL side (`native_x`): the temporary `wsol` account on
+/// that side, and the user's own account, which must be the only one
+/// passed, on the other.
+pub fn user_accounts<'info>(
+    native_x: bool,
+    wsol: &InterfaceAccount<'info, TokenAccount>,
+    user_x: Option<&InterfaceAccount<'info, TokenAccount>>,
+    user_y: Option<&InterfaceAccount<'info, TokenAccount>>,
+) -> Result<(UserAccount<'info>, UserAccount<'info>)> {
+    match (native_x, user_x, user_y) {
+        (true, None, Some(user_y)) => Ok((Box::new(wsol.clone()), Box::new(user_y.clone()))),
+        (false, Some(user_x), None) => Ok((Box::new(user_x.clone()), Box::new(wsol.clone()))),
+        _ => err!(AmmError::InvalidTokenAccounts),
+    }
+}
+
+/// Moves `lamports` from `user` into its wrapped SOL `account` and syncs
+/// the account's balance with them.
+pub fn wrap<'info>(
+    user: &Signer<'info>,
+    account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    system_program: &Program<'info, System>,
+    lamports: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: user.to_account_info(),
+        to: account.to_account_info(),
+    };
+    transfer(CpiContext::new(system_program.to_account_info(), cpi_accounts), lamports)?;
+
+    let cpi_accounts = SyncNative {
+        account: account.to_account_info(),
+    };
+    sync_native(CpiContext::new(token_program.to_account_info(), cpi_accounts))
+}
+
+/// Closes `user`'s temporary wrapped SOL `account`, unwrapping everything
+/// in it, rent included, back to `user`.
+pub fn unwrap<'info>(
+    user: &Signer<'info>,
+    account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let cpi_accounts = CloseAccount {
+        account: account.to_account_info(),
+        destination: user.to_account_info(),
+        authority: user.to_account_info(),
+    };
+    close_account(CpiContext::new(token_program.to_account_info(), cpi_accounts))
+}
@@ -0,0 +1,244 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{
+        CONFIG_SEED, DEAD_SEED, FLASH_LOAN_FEE, LP_SEED, VOLATILITY_FEE_SHARE, VOLATILITY_WINDOW,
+    },
+    curves,
+    error::AmmError,
+    math,
+};
+
+/// Invariant a pool prices swaps and first deposits with.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum CurveType {
+    ConstantProduct,
+    /// Curve-style StableSwap for pegged pairs, see `Config::amp`.
+    StableSwap,
+    /// Liquidity provided over tick ranges through `Position` accounts
+    /// instead of the fungible LP mint.
+    Concentrated,
+    /// Balancer-style weighted product for pairs held in unequal shares,
+    /// see `Config::weight_x`.
+    Weighted,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config{
+    pub seed: u64,
+    pub authority:Option<Pubkey>,
+    pub mint_x: Pubkey,
+    pub mint_y: Pubkey,
+    pub fee: u16,
+    /// Stops every instruction that moves the reserves.
+    pub locked: bool,
+    pub config_bump: u8,
+    pub lp_bump: u8,
+    /// Layout of the fields below, `CONFIG_VERSION` once `migrate_config`
+    /// has run. The fields above are laid out as in the first release and
+    /// must stay that way. Zero for configs created by it, which end here.
+    pub version: u8,
+    /// Proposed by `transfer_authority`, takes over once it accepts.
+    pub pending_authority: Option<Pubkey>,
+    /// Dynamic fee mode: every price update sets `fee` from `volatility`,
+    /// between `min_fee` and `max_fee`. See `Config::volatility_fee`.
+    pub dynamic_fee: bool,
+    pub min_fee: u16,
+    pub max_fee: u16,
+    pub curve: CurveType,
+    /// StableSwap amplification, moving linearly from `initial_amp` to
+    /// `target_amp` between the ramp timestamps. Zero for other curves.
+    pub initial_amp: u64,
+    pub target_amp: u64,
+    pub ramp_start_timestamp: i64,
+    pub ramp_stop_timestamp: i64,
+    /// Weighted pool weights, in basis points of `TOTAL_WEIGHT`. Zero for
+    /// other curves.
+    pub weight_x: u16,
+    pub weight_y: u16,
+    /// Concentrated liquidity state, unused by the other curves: the Q64.64
+    /// square root price and its tick, the initialized ticks either side of
+    /// it, the liquidity in range and the swap fees earned per unit of it.
+    pub tick_spacing: u16,
+    pub sqrt_price: u128,
+    pub tick_current: i32,
+    pub tick_below: i32,
+    pub tick_above: i32,
+    pub liquidity: u64,
+    pub fee_growth_global_x: u128,
+    pub fee_growth_global_y: u128,
+    /// Share of each swap fee, in basis points of the fee, owed to the protocol.
+    pub protocol_fee: u16,
+    /// Protocol fees held in the vaults but excluded from the reserves.
+    pub protocol_fees_x: u64,
+    pub protocol_fees_y: u64,
+    /// Time-weighted sums of the Q64.64 spot prices, wrapping on overflow.
+    pub price_x_cumulative: u128,
+    pub price_y_cumulative: u128,
+    pub last_update_timestamp: i64,
+    /// Q64.64 spot price of x in y at the first price update of the slot
+    /// `reference_slot`, and the price moves between such updates since, in
+    /// basis points, decaying linearly over `VOLATILITY_WINDOW`. Tracked
+    /// whatever the fee mode.
+    pub reference_price: u128,
+    pub volatility: u64,
+    /// Amounts lent by the outstanding flash loan, zero outside a
+    /// `flash_borrow`/`flash_repay` pair.
+    pub flash_loan_x: u64,
+    pub flash_loan_y: u64,
+    /// Finer-grained pauses than `locked`, so withdrawals can stay open
+    /// while swaps or deposits are stopped.
+    pub swaps_paused: bool,
+    pub deposits_paused: bool,
+    pub withdrawals_paused: bool,
+    /// Slot whose first price update set `reference_price`.
+    pub reference_slot: u64,
+}
+
+impl Config {
+    /// Canonical encoding of the pool seed inside the config PDA seeds.
+    /// Every instruction and client must derive the config through this.
+    pub fn encode_seed(seed: u64) -> [u8; 8] {
+        seed.to_le_bytes()
+    }
+
+    pub fn find_address(seed: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[CONFIG_SEED, &Self::encode_seed(seed)], &crate::ID)
+    }
+
+    pub fn find_lp_mint(config: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[LP_SEED, config.as_ref()], &crate::ID)
+    }
+
+    /// Owner of the permanently locked minimum liquidity. Nothing ever
+    /// signs for it.
+    pub fn find_dead(config: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[DEAD_SEED, config.as_ref()], &crate::ID)
+    }
+
+    /// Seeds the config signs vault and LP mint CPIs with. `seed` must come
+    /// from `Config::encode_seed(self.seed)`.
+    pub fn signer_seeds<'a>(&'a self, seed: &'a [u8; 8]) -> [&'a [u8]; 3] {
+        [CONFIG_SEED, seed, std::slice::from_ref(&self.config_bump)]
+    }
+
+    pub fn check_authority(&self, key: &Pubkey) -> Result<()> {
+        let authority = self.authority.ok_or(AmmError::NoAuthoritySet)?;
+        require_keys_eq!(*key, authority, AmmError::InvalidAuthority);
+        Ok(())
+    }
+
+    /// Pool reserves given the vault balances, net of accrued protocol fees.
+    pub fn reserves(&self, vault_x: u64, vault_y: u64) -> Result<(u64, u64)> {
+        Ok((
+            vault_x.checked_sub(self.protocol_fees_x).ok_or(AmmError::Underflow)?,
+            vault_y.checked_sub(self.protocol_fees_y).ok_or(AmmError::Underflow)?,
+        ))
+    }
+
+    /// Sets aside the protocol's share of a swap fee paid in x (`is_x`) or y
+    /// and returns it.
+    pub fn accrue_protocol_fee(&mut self, is_x: bool, fee: u64) -> Result<u64> {
+        let share = (fee as u128 * self.protocol_fee as u128 / 10_000) as u64;
+        let accrued = match is_x {
+            true => &mut self.protocol_fees_x,
+            false => &mut self.protocol_fees_y,
+        };
+        *accrued = accrued.checked_add(share).ok_or(AmmError::Overflow)?;
+        Ok(share)
+    }
+
+    /// Whether a flash loan is outstanding. Everything that reads or moves
+    /// the reserves must wait for it to be repaid.
+    pub fn flash_loan_active(&self) -> bool {
+        self.flash_loan_x != 0 || self.flash_loan_y != 0
+    }
+
+    /// Fee owed on a flash loan of `amount`, rounded up.
+    pub fn flash_loan_fee(amount: u64) -> Result<u64> {
+        let fee = math::mul_div_ceil(amount as u128, FLASH_LOAN_FEE as u128, 10_000)
+            .ok_or(AmmError::Overflow)?;
+        Ok(fee as u64)
+    }
+
+    /// Amplification coefficient in effect at `now`.
+    pub fn amp(&self, now: i64) -> u64 {
+        if now >= self.ramp_stop_timestamp {
+            return self.target_amp;
+        }
+
+        let elapsed = now.saturating_sub(self.ramp_start_timestamp).max(0) as u128;
+        let duration = (self.ramp_stop_timestamp - self.ramp_start_timestamp) as u128;
+        let (initial, target) = (self.initial_amp as u128, self.target_amp as u128);
+        let amp = match target >= initial {
+            true => initial + (target - initial) * elapsed / duration,
+            false => initial - (initial - target) * elapsed / duration,
+        };
+        amp as u64
+    }
+
+    /// Weights of the input and output side of a swap of x (`is_x`) or y.
+    pub fn weights(&self, is_x: bool) -> (u16, u16) {
+        match is_x {
+            true => (self.weight_x, self.weight_y),
+            false => (self.weight_y, self.weight_x),
+        }
+    }
+
+    /// Accrues the prices that held since the last update and, on the first
+    /// update of a slot, the move of the price since the previous slot's
+    /// into the volatility. Call with the reserves as they were before the
+    /// current instruction changes them.
+    ///
+    /// Counting one move per slot keeps swaps back and forth within a slot
+    /// from inflating the fee: only the net move is charged, in the next
+    /// slot.
+    pub fn accumulate_prices(&mut self, x: u64, y: u64, now: i64, slot: u64) {
+        let elapsed = now.saturating_sub(self.last_update_timestamp).max(0);
+
+        if let Some((price_x, price_y)) = curves::spot_prices(self, x, y, now) {
+            self.price_x_cumulative = self
+                .price_x_cumulative
+                .wrapping_add(price_x.wrapping_mul(elapsed as u128));
+            self.price_y_cumulative = self
+                .price_y_cumulative
+                .wrapping_add(price_y.wrapping_mul(elapsed as u128));
+            if slot > self.reference_slot {
+                self.accumulate_volatility(price_x, elapsed);
+                self.reference_slot = slot;
+            }
+        }
+        self.last_update_timestamp = self.last_update_timestamp.max(now);
+    }
+
+    /// Decays the volatility over `elapsed` seconds, adds the move from the
+    /// reference price to `price` and, in dynamic fee mode, reprices the fee.
+    fn accumulate_volatility(&mut self, price: u128, elapsed: i64) {
+        let decayed = match elapsed < VOLATILITY_WINDOW {
+            true => {
+                self.volatility as u128 * (VOLATILITY_WINDOW - elapsed) as u128
+                    / VOLATILITY_WINDOW as u128
+            }
+            false => 0,
+        };
+        let moved = match self.reference_price {
+            0 => 0,
+            reference => price.abs_diff(reference).saturating_mul(10_000) / reference,
+        };
+
+        self.volatility = u64::try_from(decayed.saturating_add(moved)).unwrap_or(u64::MAX);
+        self.reference_price = price;
+        if self.dynamic_fee {
+            self.fee = self.volatility_fee();
+        }
+    }
+
+    /// Fee a dynamic fee pool charges at its current volatility: the minimum
+    /// fee plus `VOLATILITY_FEE_SHARE` of the volatility, capped at the
+    /// maximum fee.
+    pub fn volatility_fee(&self) -> u16 {
+        let surcharge = self.volatility as u128 * VOLATILITY_FEE_SHARE as u128 / 10_000;
+        (self.min_fee as u128 + surcharge).min(self.max_fee as u128) as u16
+    }
+}
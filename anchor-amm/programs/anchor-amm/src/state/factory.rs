@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{FACTORY_SEED, MAX_FEE_TIERS, POOL_KEY_SEED, REGISTRY_PAGE_SIZE, REGISTRY_SEED},
+    error::AmmError,
+    state::{Config, CurveType},
+};
+
+/// Singleton through which canonical pools are created, at most one per
+/// sorted mint pair, fee tier and curve.
+#[account]
+#[derive(InitSpace)]
+pub struct Factory {
+    /// Manages the fee tiers and becomes the authority of every pool the
+    /// factory creates.
+    pub authority: Pubkey,
+    /// Fees, in basis points, pools may be created with.
+    #[max_len(MAX_FEE_TIERS)]
+    pub fee_tiers: Vec<u16>,
+    /// Pools created so far. Pool `i` is entry `i % REGISTRY_PAGE_SIZE` of
+    /// registry page `i / REGISTRY_PAGE_SIZE`.
+    pub pool_count: u64,
+    pub bump: u8,
+}
+
+impl Factory {
+    pub fn find_address() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[FACTORY_SEED], &crate::ID)
+    }
+
+    /// The program's `ProgramData`, whose upgrade authority creates the
+    /// factory.
+    pub fn find_program_data() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[crate::ID.as_ref()], &ProgramData::owner())
+    }
+
+    /// Checks a pool of `mint_x` and `mint_y` may be created at `fee`.
+    pub fn check_pool(&self, mint_x: &Pubkey, mint_y: &Pubkey, fee: u16) -> Result<()> {
+        require!(mint_x < mint_y, AmmError::UnsortedMints);
+        require!(self.fee_tiers.contains(&fee), AmmError::InvalidFeeTier);
+        Ok(())
+    }
+
+    /// Lists the pool at `address` on `page`, which must be the page of the
+    /// next pool index, and points the pool's `key` at it.
+    pub fn register(
+        &mut self,
+        address: Pubkey,
+        config: &Config,
+        key: &mut PoolKey,
+        key_bump: u8,
+        page: &mut RegistryPage,
+        page_bump: u8,
+    ) {
+        let index = self.pool_count;
+        if page.pools.is_empty() {
+            page.index = RegistryPage::page_of(index);
+            page.bump = page_bump;
+        }
+        page.pools.push(PoolEntry {
+            config: address,
+            mint_x: config.mint_x,
+            mint_y: config.mint_y,
+            fee: config.fee,
+            curve: config.curve,
+        });
+        *key = PoolKey {
+            config: address,
+            index,
+            bump: key_bump,
+        };
+        self.pool_count = index + 1;
+    }
+}
+
+/// Marks the canonical pool of a mint pair, fee tier and curve. Its address
+/// is the lookup key, and creating it twice is what fails for duplicates.
+#[account]
+#[derive(InitSpace)]
+pub struct PoolKey {
+    pub config: Pubkey,
+    /// Position of the pool in the registry.
+    pub index: u64,
+    pub bump: u8,
+}
+
+impl PoolKey {
+    /// `mint_x` must sort before `mint_y`.
+    pub fn find_address(mint_x: &Pubkey, mint_y: &Pubkey, fee: u16, curve: CurveType) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                POOL_KEY_SEED,
+                mint_x.as_ref(),
+                mint_y.as_ref(),
+                &fee.to_le_bytes(),
+                &[curve as u8],
+            ],
+            &crate::ID,
+        )
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct PoolEntry {
+    pub config: Pubkey,
+    pub mint_x: Pubkey,
+    pub mint_y: Pubkey,
+    pub fee: u16,
+    pub curve: CurveType,
+}
+
+/// Fixed-size slice of the factory's pools in creation order, so clients
+/// and routers can enumerate them page by page.
+#[account]
+#[derive(InitSpace)]
+pub struct RegistryPage {
+    pub index: u32,
+    #[max_len(REGISTRY_PAGE_SIZE)]
+    pub pools: Vec<PoolEntry>,
+    pub bump: u8,
+}
+
+impl RegistryPage {
+    /// Canonical encoding of the page index inside the page PDA seeds.
+    pub fn encode_index(index: u32) -> [u8; 4] {
+        index.to_le_bytes()
+    }
+
+    /// Page holding the pool registered at `pool_index`.
+    pub fn page_of(pool_index: u64) -> u32 {
+        (pool_index / REGISTRY_PAGE_SIZE as u64) as u32
+    }
+
+    pub fn find_address(index: u32) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[REGISTRY_SEED, &Self::encode_index(index)], &crate::ID)
+    }
+}
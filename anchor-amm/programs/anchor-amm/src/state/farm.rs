@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{FARM_SEED, FARM_STAKE_SEED},
+    error::AmmError,
+    math::mul_div,
+};
+
+/// Liquidity mining for a pool's LP: stakers lock LP in the farm's vault
+/// and share `reward_rate` of the reward mint per second, in proportion to
+/// their stake, until `emission_end`. The pool's authority sets the
+/// schedule.
+#[account]
+#[derive(InitSpace)]
+pub struct Farm {
+    pub config: Pubkey,
+    pub mint_lp: Pubkey,
+    pub reward_mint: Pubkey,
+    /// Rewards emitted per second while anything is staked.
+    pub reward_rate: u64,
+    pub emission_end: i64,
+    /// Time emissions have been accrued up to.
+    pub last_update: i64,
+    /// Q64.64 rewards emitted per staked LP since the farm was created.
+    pub reward_per_share: u128,
+    pub total_staked: u64,
+    /// Rewards accrued to stakers and not yet harvested, which the reward
+    /// vault must hold on top of the remaining schedule.
+    pub rewards_unclaimed: u64,
+    pub bump: u8,
+}
+
+impl Farm {
+    pub fn find_address(config: &Pubkey, reward_mint: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[FARM_SEED, config.as_ref(), reward_mint.as_ref()],
+            &crate::ID,
+        )
+    }
+
+    pub fn signer_seeds(&self) -> [&[u8]; 4] {
+        [
+            FARM_SEED,
+            self.config.as_ref(),
+            self.reward_mint.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+
+    /// Accrues the emissions since the last update, up to `emission_end`,
+    /// to the LP staked over that time. Nothing accrues while nothing is
+    /// staked. Must run before the total stake or the schedule changes.
+    pub fn accrue(&mut self, now: i64) -> Result<()> {
+        let until = now.min(self.emission_end);
+        if until <= self.last_update {
+            return Ok(());
+        }
+
+        if self.total_staked != 0 {
+            let emitted = (self.reward_rate as u128)
+                .checked_mul((until - self.last_update) as u128)
+                .ok_or(AmmError::Overflow)?;
+            let growth =
+                mul_div(emitted, 1 << 64, self.total_staked as u128).ok_or(AmmError::Overflow)?;
+            self.reward_per_share = self.reward_per_share.wrapping_add(growth);
+            self.rewards_unclaimed = u64::try_from(emitted)
+                .ok()
+                .and_then(|emitted| self.rewards_unclaimed.checked_add(emitted))
+                .ok_or(AmmError::Overflow)?;
+        }
+        self.last_update = until;
+        Ok(())
+    }
+
+    /// Rewards still to be emitted from `now` to the end of the schedule.
+    pub fn remaining_emissions(&self, now: i64) -> Option<u64> {
+        let seconds = self.emission_end.saturating_sub(now).max(0) as u64;
+        self.reward_rate.checked_mul(seconds)
+    }
+}
+
+/// LP an owner has staked in a farm, and the rewards it has earned.
+#[account]
+#[derive(InitSpace)]
+pub struct FarmStake {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    /// The farm's reward per share when rewards were last credited.
+    pub reward_per_share_last: u128,
+    pub rewards_owed: u64,
+    pub bump: u8,
+}
+
+impl FarmStake {
+    pub fn find_address(farm: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[FARM_STAKE_SEED, farm.as_ref(), owner.as_ref()], &crate::ID)
+    }
+
+    /// Credits the rewards earned since the last update, rounded down.
+    /// Must run, after `Farm::accrue`, before the stake changes.
+    pub fn update_rewards(&mut self, farm: &Farm) -> Result<()> {
+        let earned = mul_div(
+            farm.reward_per_share.wrapping_sub(self.reward_per_share_last),
+            self.amount as u128,
+            1 << 64,
+        )
+        .ok_or(AmmError::Overflow)?;
+
+        self.rewards_owed = self
+            .rewards_owed
+            .checked_add(u64::try_from(earned).map_err(|_| AmmError::Overflow)?)
+            .ok_or(AmmError::Overflow)?;
+        self.reward_per_share_last = farm.reward_per_share;
+        Ok(())
+    }
+}
@@ -1,15 +1,17 @@
-use anchor_lang::prelude::*;
-
-#[account]
-#[derive(InitSpace)]
-pub struct Config{
-    pub seed: u64,
-    pub authority:Option<Pubkey>,
-    pub mint_x: Pubkey,
-    pub mint_y: Pubkey,
-    pub fee: u16,
-    pub locked: bool,
-    pub config_bump: u8,
-    pub lp_bum: u8,
-}
+pub mod config;
+pub mod factory;
+pub mod farm;
+pub mod multi_pool;
+pub mod oracle;
+pub mod order;
+pub mod position;
+pub mod tick;
 
+pub use config::*;
+pub use factory::*;
+pub use farm::*;
+pub use multi_pool::*;
+pub use oracle::*;
+pub use order::*;
+pub use position::*;
+pub use tick::*;
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{DEAD_SEED, LP_SEED, MAX_MULTI_POOL_TOKENS, MULTI_POOL_SEED},
+    error::AmmError,
+};
+
+/// One of the tokens of a `MultiPool`, held in the pool's associated token
+/// account for its mint.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct PoolToken {
+    pub mint: Pubkey,
+    /// Share of the pool's value held in this token, in basis points of
+    /// `TOTAL_WEIGHT`.
+    pub weight: u16,
+}
+
+/// Weighted pool of 3 to `MAX_MULTI_POOL_TOKENS` tokens, for index-style
+/// baskets. Its vaults are passed as remaining accounts, so one account
+/// layout serves any number of tokens, and it swaps between any two of
+/// them on the weighted product invariant.
+#[account]
+#[derive(InitSpace)]
+pub struct MultiPool {
+    pub seed: u64,
+    pub authority: Option<Pubkey>,
+    /// Swap fee in basis points, also charged on the swapped share of
+    /// single-token deposits and withdrawals.
+    pub fee: u16,
+    #[max_len(MAX_MULTI_POOL_TOKENS)]
+    pub tokens: Vec<PoolToken>,
+    pub locked: bool,
+    pub bump: u8,
+    pub lp_bump: u8,
+}
+
+impl MultiPool {
+    pub fn encode_seed(seed: u64) -> [u8; 8] {
+        seed.to_le_bytes()
+    }
+
+    pub fn find_address(seed: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[MULTI_POOL_SEED, &Self::encode_seed(seed)], &crate::ID)
+    }
+
+    pub fn find_lp_mint(pool: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[LP_SEED, pool.as_ref()], &crate::ID)
+    }
+
+    /// Owner of the permanently locked minimum liquidity. Nothing ever
+    /// signs for it.
+    pub fn find_dead(pool: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[DEAD_SEED, pool.as_ref()], &crate::ID)
+    }
+
+    pub fn signer_seeds<'a>(&'a self, seed: &'a [u8; 8]) -> [&'a [u8]; 3] {
+        [MULTI_POOL_SEED, seed, std::slice::from_ref(&self.bump)]
+    }
+
+    pub fn check_authority(&self, key: &Pubkey) -> Result<()> {
+        let authority = self.authority.ok_or(AmmError::NoAuthoritySet)?;
+        require_keys_eq!(*key, authority, AmmError::InvalidAuthority);
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ORACLE_CAPACITY, ORACLE_INTERVAL, ORACLE_SEED},
+    error::AmmError,
+    state::Config,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct Observation {
+    pub timestamp: i64,
+    pub price_x_cumulative: u128,
+    pub price_y_cumulative: u128,
+}
+
+impl Observation {
+    /// Cumulative prices at `target`, between `self` and the later `next`,
+    /// wrapping on overflow like the accumulators themselves.
+    pub fn interpolate(&self, next: &Observation, target: i64) -> (u128, u128) {
+        let span = (next.timestamp - self.timestamp) as u128;
+        let offset = (target - self.timestamp) as u128;
+
+        let average_x = next.price_x_cumulative.wrapping_sub(self.price_x_cumulative) / span;
+        let average_y = next.price_y_cumulative.wrapping_sub(self.price_y_cumulative) / span;
+
+        (
+            self.price_x_cumulative.wrapping_add(average_x.wrapping_mul(offset)),
+            self.price_y_cumulative.wrapping_add(average_y.wrapping_mul(offset)),
+        )
+    }
+}
+
+impl From<&Config> for Observation {
+    fn from(config: &Config) -> Self {
+        Self {
+            timestamp: config.last_update_timestamp,
+            price_x_cumulative: config.price_x_cumulative,
+            price_y_cumulative: config.price_y_cumulative,
+        }
+    }
+}
+
+/// Ring buffer of the pool's cumulative prices, at most one observation per
+/// `ORACLE_INTERVAL` seconds, for other programs to derive TWAPs from.
+#[account]
+#[derive(InitSpace)]
+pub struct Oracle {
+    pub config: Pubkey,
+    /// Position of the newest observation.
+    pub index: u16,
+    #[max_len(ORACLE_CAPACITY)]
+    pub observations: Vec<Observation>,
+    pub bump: u8,
+}
+
+impl Oracle {
+    pub fn find_address(config: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[ORACLE_SEED, config.as_ref()], &crate::ID)
+    }
+
+    /// Accrues prices into `config` from the current reserves and records an
+    /// observation when the last one is old enough.
+    pub fn update(&mut self, config: &mut Config, x: u64, y: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        config.accumulate_prices(x, y, clock.unix_timestamp, clock.slot);
+
+        let newest = self.observations[self.index as usize].timestamp;
+        if config.last_update_timestamp - newest < ORACLE_INTERVAL {
+            return Ok(());
+        }
+
+        let observation = Observation::from(&*config);
+        if self.observations.len() < ORACLE_CAPACITY {
+            self.observations.push(observation);
+            self.index = (self.observations.len() - 1) as u16;
+        } else {
+            self.index = ((self.index as usize + 1) % ORACLE_CAPACITY) as u16;
+            self.observations[self.index as usize] = observation;
+        }
+
+        Ok(())
+    }
+
+    /// Cumulative prices at `target`. Past the config's last update the
+    /// current `spot` prices are extrapolated; before it the observations
+    /// are interpolated.
+    pub fn cumulative_at(
+        &self,
+        config: &Config,
+        spot: Option<(u128, u128)>,
+        target: i64,
+    ) -> Result<(u128, u128)> {
+        if target >= config.last_update_timestamp {
+            let elapsed = (target - config.last_update_timestamp) as u128;
+            let (price_x, price_y) = spot.unwrap_or_default();
+            return Ok((
+                config.price_x_cumulative.wrapping_add(price_x.wrapping_mul(elapsed)),
+                config.price_y_cumulative.wrapping_add(price_y.wrapping_mul(elapsed)),
+            ));
+        }
+
+        // Walk oldest to newest, with the config itself as the final point.
+        let len = self.observations.len();
+        let start = match len < ORACLE_CAPACITY {
+            true => 0,
+            false => (self.index as usize + 1) % len,
+        };
+        let head = Observation::from(config);
+
+        let mut before: Option<Observation> = None;
+        for i in 0..=len {
+            let observation = match i == len {
+                true => head,
+                false => self.observations[(start + i) % len],
+            };
+            if observation.timestamp > target {
+                let before = before.ok_or(AmmError::OracleHistoryTooShort)?;
+                return Ok(before.interpolate(&observation, target));
+            }
+            before = Some(observation);
+        }
+
+        err!(AmmError::OracleHistoryTooShort)
+    }
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::ORDER_SEED;
+
+/// A maker's limit order against a pool: `amount` of `mint_in`, held in
+/// the order's escrow, for at least `min_out` of the pool's other token,
+/// a limit price of `min_out / amount`. Keepers fill it by swapping
+/// through the pool once its price allows.
+#[account]
+#[derive(InitSpace)]
+pub struct Order {
+    pub config: Pubkey,
+    pub maker: Pubkey,
+    /// Chosen by the maker to tell its orders on a pool apart.
+    pub id: u64,
+    pub mint_in: Pubkey,
+    pub amount: u64,
+    /// Least the maker receives, after the keeper's bounty and transfer
+    /// fees.
+    pub min_out: u64,
+    pub bump: u8,
+}
+
+impl Order {
+    pub fn encode_id(id: u64) -> [u8; 8] {
+        id.to_le_bytes()
+    }
+
+    pub fn find_address(config: &Pubkey, maker: &Pubkey, id: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[ORDER_SEED, config.as_ref(), maker.as_ref(), &Self::encode_id(id)],
+            &crate::ID,
+        )
+    }
+
+    pub fn signer_seeds<'a>(&'a self, id: &'a [u8; 8]) -> [&'a [u8]; 5] {
+        [
+            ORDER_SEED,
+            self.config.as_ref(),
+            self.maker.as_ref(),
+            id,
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+}
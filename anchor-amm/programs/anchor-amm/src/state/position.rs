@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::POSITION_SEED,
+    error::AmmError,
+    math::mul_div,
+    state::{Config, Tick},
+};
+
+/// Liquidity an owner provides to a concentrated liquidity pool over
+/// `[tick_lower, tick_upper)`, and the swap fees it has earned.
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub config: Pubkey,
+    pub owner: Pubkey,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: u64,
+    /// Fee growth inside the range when fees were last credited.
+    pub fee_growth_inside_x_last: u128,
+    pub fee_growth_inside_y_last: u128,
+    pub fees_owed_x: u64,
+    pub fees_owed_y: u64,
+    pub bump: u8,
+}
+
+impl Position {
+    pub fn find_address(
+        config: &Pubkey,
+        owner: &Pubkey,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                POSITION_SEED,
+                config.as_ref(),
+                owner.as_ref(),
+                &tick_lower.to_le_bytes(),
+                &tick_upper.to_le_bytes(),
+            ],
+            &crate::ID,
+        )
+    }
+
+    /// Credits the fees earned since the last update. Must run before the
+    /// position's liquidity changes.
+    pub fn update_fees(&mut self, config: &Config, lower: &Tick, upper: &Tick) -> Result<()> {
+        let (inside_x, inside_y) = Tick::fee_growth_inside(config, lower, upper);
+
+        let earned_x = mul_div(
+            inside_x.wrapping_sub(self.fee_growth_inside_x_last),
+            self.liquidity as u128,
+            1 << 64,
+        )
+        .ok_or(AmmError::Overflow)?;
+        let earned_y = mul_div(
+            inside_y.wrapping_sub(self.fee_growth_inside_y_last),
+            self.liquidity as u128,
+            1 << 64,
+        )
+        .ok_or(AmmError::Overflow)?;
+
+        self.fees_owed_x = self
+            .fees_owed_x
+            .checked_add(u64::try_from(earned_x).map_err(|_| AmmError::Overflow)?)
+            .ok_or(AmmError::Overflow)?;
+        self.fees_owed_y = self
+            .fees_owed_y
+            .checked_add(u64::try_from(earned_y).map_err(|_| AmmError::Overflow)?)
+            .ok_or(AmmError::Overflow)?;
+        self.fee_growth_inside_x_last = inside_x;
+        self.fee_growth_inside_y_last = inside_y;
+        Ok(())
+    }
+}
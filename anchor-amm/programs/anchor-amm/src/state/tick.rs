@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::TICK_SEED,
+    curves::concentrated::{MAX_TICK, MIN_TICK},
+    error::AmmError,
+    state::Config,
+};
+
+/// Initialized tick of a concentrated liquidity pool. A pool's ticks form a
+/// doubly linked list between sentinels at its lowest and highest usable
+/// ticks, so a swap can check it was handed every tick it crosses.
+#[account]
+#[derive(InitSpace)]
+pub struct Tick {
+    pub config: Pubkey,
+    pub index: i32,
+    /// Liquidity added to the pool when the price crosses this tick upwards.
+    pub liquidity_net: i128,
+    /// Fee growth on the side of this tick away from the current price.
+    pub fee_growth_outside_x: u128,
+    pub fee_growth_outside_y: u128,
+    /// Neighbouring initialized ticks. Sentinels point at themselves.
+    pub prev: i32,
+    pub next: i32,
+    pub bump: u8,
+}
+
+impl Tick {
+    pub fn find_address(config: &Pubkey, index: i32) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[TICK_SEED, config.as_ref(), &index.to_le_bytes()],
+            &crate::ID,
+        )
+    }
+
+    /// Lowest and highest ticks usable with `tick_spacing`, where the pool's
+    /// sentinels sit.
+    pub fn bounds(tick_spacing: u16) -> (i32, i32) {
+        let spacing = tick_spacing.max(1) as i32;
+        (MIN_TICK / spacing * spacing, MAX_TICK / spacing * spacing)
+    }
+
+    pub fn is_sentinel(&self) -> bool {
+        self.prev == self.index || self.next == self.index
+    }
+
+    /// Reads tick `index` of `config` from an account passed outside the
+    /// `Accounts` struct.
+    pub fn load(info: &AccountInfo, config: &Pubkey, index: i32) -> Result<Self> {
+        require_keys_eq!(*info.owner, crate::ID, AmmError::InvalidTick);
+        let tick = Self::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(
+            tick.config == *config && tick.index == index,
+            AmmError::InvalidTick
+        );
+        Ok(tick)
+    }
+
+    pub fn store(&self, info: &AccountInfo) -> Result<()> {
+        self.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+    }
+
+    /// Flips the fee growth outside as the price crosses this tick.
+    pub fn cross(&mut self, config: &Config) {
+        self.fee_growth_outside_x = config.fee_growth_global_x.wrapping_sub(self.fee_growth_outside_x);
+        self.fee_growth_outside_y = config.fee_growth_global_y.wrapping_sub(self.fee_growth_outside_y);
+    }
+
+    /// Fee growth per unit of liquidity between `lower` and `upper`, up to
+    /// a constant offset that cancels out between two readings.
+    pub fn fee_growth_inside(config: &Config, lower: &Tick, upper: &Tick) -> (u128, u128) {
+        let below = match config.tick_current >= lower.index {
+            true => (lower.fee_growth_outside_x, lower.fee_growth_outside_y),
+            false => (
+                config.fee_growth_global_x.wrapping_sub(lower.fee_growth_outside_x),
+                config.fee_growth_global_y.wrapping_sub(lower.fee_growth_outside_y),
+            ),
+        };
+        let above = match config.tick_current < upper.index {
+            true => (upper.fee_growth_outside_x, upper.fee_growth_outside_y),
+            false => (
+                config.fee_growth_global_x.wrapping_sub(upper.fee_growth_outside_x),
+                config.fee_growth_global_y.wrapping_sub(upper.fee_growth_outside_y),
+            ),
+        };
+
+        (
+            config.fee_growth_global_x.wrapping_sub(below.0).wrapping_sub(above.0),
+            config.fee_growth_global_y.wrapping_sub(below.1).wrapping_sub(above.1),
+        )
+    }
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use anchor_amm::{constants::MAX_FEE, error::AmmError};
+use common::*;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+#[test]
+fn authority_is_transferred_in_two_steps() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 1, 30);
+    let successor = Keypair::new();
+    let stranger = Keypair::new();
+    svm.airdrop(&successor.pubkey(), 1_000_000_000).unwrap();
+    svm.airdrop(&stranger.pubkey(), 1_000_000_000).unwrap();
+
+    let ix = pool.accept_authority(&successor.pubkey());
+    assert_amm_error(send(&mut svm, &successor, &[ix]), AmmError::NoPendingAuthority);
+    let ix = pool.transfer_authority(&successor.pubkey(), Some(successor.pubkey()));
+    assert_amm_error(send(&mut svm, &successor, &[ix]), AmmError::InvalidAuthority);
+
+    // A cancelled transfer cannot be accepted.
+    let ix = pool.transfer_authority(&admin.pubkey(), Some(stranger.pubkey()));
+    send(&mut svm, &admin, &[ix]).unwrap();
+    let ix = pool.transfer_authority(&admin.pubkey(), None);
+    send(&mut svm, &admin, &[ix]).unwrap();
+    let ix = pool.accept_authority(&stranger.pubkey());
+    assert_amm_error(send(&mut svm, &stranger, &[ix]), AmmError::NoPendingAuthority);
+
+    // The current authority keeps control until the transfer is accepted.
+    let ix = pool.transfer_authority(&admin.pubkey(), Some(successor.pubkey()));
+    send(&mut svm, &admin, &[ix]).unwrap();
+    let ix = pool.accept_authority(&stranger.pubkey());
+    assert_amm_error(send(&mut svm, &stranger, &[ix]), AmmError::InvalidAuthority);
+    let ix = pool.lock(&admin.pubkey());
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    let ix = pool.accept_authority(&successor.pubkey());
+    send(&mut svm, &successor, &[ix]).unwrap();
+    let config = fetch_config(&svm, &pool.config);
+    assert_eq!(config.authority, Some(successor.pubkey()));
+    assert_eq!(config.pending_authority, None);
+
+    let ix = pool.unlock(&admin.pubkey());
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidAuthority);
+    let ix = pool.unlock(&successor.pubkey());
+    send(&mut svm, &successor, &[ix]).unwrap();
+
+    // Renouncing leaves nobody in charge.
+    let ix = pool.renounce_authority(&successor.pubkey());
+    send(&mut svm, &successor, &[ix]).unwrap();
+    assert_eq!(fetch_config(&svm, &pool.config).authority, None);
+    let ix = pool.lock(&successor.pubkey());
+    assert_amm_error(send(&mut svm, &successor, &[ix]), AmmError::NoAuthoritySet);
+    let ix = pool.transfer_authority(&successor.pubkey(), Some(admin.pubkey()));
+    assert_amm_error(send(&mut svm, &successor, &[ix]), AmmError::NoAuthoritySet);
+}
+
+#[test]
+fn fee_updates_are_bounded() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 1, 30);
+    let trader = pool.create_user(&mut svm, &admin, 0, 0);
+
+    let ix = pool.set_fee(&trader.pubkey(), 100);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InvalidAuthority);
+    let ix = pool.set_fee(&admin.pubkey(), MAX_FEE + 1);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidFee);
+
+    let ix = pool.set_fee(&admin.pubkey(), MAX_FEE);
+    send(&mut svm, &admin, &[ix]).unwrap();
+    assert_eq!(fetch_config(&svm, &pool.config).fee, MAX_FEE);
+}
+
+#[test]
+fn pause_flags_are_independent() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 1, 30);
+    let lp = pool.create_user(&mut svm, &admin, 2_000_000, 2_000_000);
+    let ix = pool.deposit(&lp.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    let ix = pool.set_paused(&lp.pubkey(), true, true, false);
+    assert_amm_error(send(&mut svm, &lp, &[ix]), AmmError::InvalidAuthority);
+
+    // An incident: trading and deposits stop, withdrawals stay open.
+    let ix = pool.set_paused(&admin.pubkey(), true, true, false);
+    send(&mut svm, &admin, &[ix]).unwrap();
+    for (ix, error) in [
+        (pool.swap(&lp.pubkey(), true, 1_000, 1, NO_EXPIRATION), AmmError::SwapsPaused),
+        (pool.swap_exact_out(&lp.pubkey(), true, 1_000, 2_000, NO_EXPIRATION), AmmError::SwapsPaused),
+        (pool.deposit(&lp.pubkey(), 1_000, 2_000, 2_000, NO_EXPIRATION), AmmError::DepositsPaused),
+        (pool.deposit_single(&lp.pubkey(), true, 1_000, 1, NO_EXPIRATION), AmmError::DepositsPaused),
+        (pool.withdraw_single(&lp.pubkey(), true, 1_000, 1, NO_EXPIRATION), AmmError::SwapsPaused),
+    ] {
+        assert_amm_error(send(&mut svm, &lp, &[ix]), error);
+    }
+    let ix = pool.withdraw(&lp.pubkey(), 1_000, 1, 1, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    let ix = pool.set_paused(&admin.pubkey(), false, false, true);
+    send(&mut svm, &admin, &[ix]).unwrap();
+    let ix = pool.withdraw(&lp.pubkey(), 1_000, 1, 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &lp, &[ix]), AmmError::WithdrawalsPaused);
+    let ix = pool.swap(&lp.pubkey(), true, 1_000, 1, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    let ix = pool.deposit(&lp.pubkey(), 1_000, 2_000, 2_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+}
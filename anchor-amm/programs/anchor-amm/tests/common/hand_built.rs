@@ -0,0 +1,704 @@
+//! Addresses and instructions built by hand from the program's account and
+//! instruction types and its raw PDA seeds, independently of the client
+//! crate, so the client's builders have something to be checked against.
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::instruction::{AccountMeta, Instruction},
+    system_program, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address, get_associated_token_address_with_program_id},
+    token::spl_token,
+    token_2022::spl_token_2022,
+};
+
+use super::NO_EXPIRATION;
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &anchor_amm::ID).0
+}
+
+pub struct Pool {
+    pub seed: u64,
+    pub config: Pubkey,
+    pub mint_x: Pubkey,
+    pub mint_y: Pubkey,
+    pub token_program_x: Pubkey,
+    pub token_program_y: Pubkey,
+    pub mint_lp: Pubkey,
+    pub oracle: Pubkey,
+    pub vault_x: Pubkey,
+    pub vault_y: Pubkey,
+    pub dead: Pubkey,
+    pub locked_lp: Pubkey,
+}
+
+impl Pool {
+    pub fn new(seed: u64, (mint_x, token_program_x): (Pubkey, Pubkey), (mint_y, token_program_y): (Pubkey, Pubkey)) -> Self {
+        let config = pda(&[b"config", &seed.to_le_bytes()]);
+        let mint_lp = pda(&[b"lp", config.as_ref()]);
+        let dead = pda(&[b"dead", config.as_ref()]);
+
+        Self {
+            seed,
+            config,
+            mint_x,
+            mint_y,
+            token_program_x,
+            token_program_y,
+            mint_lp,
+            oracle: pda(&[b"oracle", config.as_ref()]),
+            vault_x: get_associated_token_address_with_program_id(&config, &mint_x, &token_program_x),
+            vault_y: get_associated_token_address_with_program_id(&config, &mint_y, &token_program_y),
+            dead,
+            locked_lp: get_associated_token_address(&dead, &mint_lp),
+        }
+    }
+
+    pub fn tick(&self, index: i32) -> Pubkey {
+        pda(&[b"tick", self.config.as_ref(), &index.to_le_bytes()])
+    }
+
+    pub fn user_x(&self, user: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(user, &self.mint_x, &self.token_program_x)
+    }
+
+    pub fn user_y(&self, user: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(user, &self.mint_y, &self.token_program_y)
+    }
+
+    pub fn user_lp(&self, user: &Pubkey) -> Pubkey {
+        get_associated_token_address(user, &self.mint_lp)
+    }
+
+    pub fn deposit_ix(&self, user: &Pubkey, amount: u64, max_x: u64, max_y: u64) -> Instruction {
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::Deposit {
+                user: *user,
+                mint_x: self.mint_x,
+                mint_y: self.mint_y,
+                mint_lp: self.mint_lp,
+                config: self.config,
+                oracle: self.oracle,
+                vault_x: self.vault_x,
+                vault_y: self.vault_y,
+                user_x: self.user_x(user),
+                user_y: self.user_y(user),
+                user_lp: self.user_lp(user),
+                dead: self.dead,
+                locked_lp: self.locked_lp,
+                token_program: spl_token::ID,
+                token_program_x: self.token_program_x,
+                token_program_y: self.token_program_y,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::Deposit {
+                amount,
+                max_x,
+                max_y,
+                expiration: NO_EXPIRATION,
+            }
+            .data(),
+        }
+    }
+
+    pub fn withdraw_ix(&self, user: &Pubkey, amount: u64, min_x: u64, min_y: u64) -> Instruction {
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::Withdraw {
+                user: *user,
+                mint_x: self.mint_x,
+                mint_y: self.mint_y,
+                mint_lp: self.mint_lp,
+                config: self.config,
+                oracle: self.oracle,
+                vault_x: self.vault_x,
+                vault_y: self.vault_y,
+                user_x: self.user_x(user),
+                user_y: self.user_y(user),
+                user_lp: self.user_lp(user),
+                token_program: spl_token::ID,
+                token_program_x: self.token_program_x,
+                token_program_y: self.token_program_y,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::Withdraw {
+                amount,
+                min_x,
+                min_y,
+                expiration: NO_EXPIRATION,
+            }
+            .data(),
+        }
+    }
+
+    pub fn swap_ix(&self, user: &Pubkey, is_x: bool, amount: u64, min: u64) -> Instruction {
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::Swap {
+                user: *user,
+                mint_x: self.mint_x,
+                mint_y: self.mint_y,
+                user_x: self.user_x(user),
+                user_y: self.user_y(user),
+                vault_x: self.vault_x,
+                vault_y: self.vault_y,
+                config: self.config,
+                oracle: self.oracle,
+                token_program_x: self.token_program_x,
+                token_program_y: self.token_program_y,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::Swap {
+                is_x,
+                amount,
+                min,
+                expiration: NO_EXPIRATION,
+            }
+            .data(),
+        }
+    }
+
+    /// The user's account of the pool's other token, and the wrapped SOL
+    /// mint and its token program.
+    fn native_accounts(&self, user: &Pubkey) -> (Option<Pubkey>, Option<Pubkey>, Pubkey, Pubkey) {
+        match self.mint_x == spl_token::native_mint::ID || self.mint_x == spl_token_2022::native_mint::ID {
+            true => (None, Some(self.user_y(user)), self.mint_x, self.token_program_x),
+            false => (Some(self.user_x(user)), None, self.mint_y, self.token_program_y),
+        }
+    }
+
+    pub fn wsol(&self, user: &Pubkey) -> Pubkey {
+        pda(&[b"wsol", user.as_ref()])
+    }
+
+    pub fn deposit_sol_ix(&self, user: &Pubkey, amount: u64, max_x: u64, max_y: u64) -> Instruction {
+        let (user_x, user_y, native_mint, native_token_program) = self.native_accounts(user);
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::DepositSol {
+                user: *user,
+                mint_x: self.mint_x,
+                mint_y: self.mint_y,
+                mint_lp: self.mint_lp,
+                config: self.config,
+                oracle: self.oracle,
+                vault_x: self.vault_x,
+                vault_y: self.vault_y,
+                user_x,
+                user_y,
+                user_lp: self.user_lp(user),
+                dead: self.dead,
+                locked_lp: self.locked_lp,
+                native_mint,
+                wsol: self.wsol(user),
+                token_program: spl_token::ID,
+                token_program_x: self.token_program_x,
+                token_program_y: self.token_program_y,
+                native_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::DepositSol {
+                amount,
+                max_x,
+                max_y,
+                expiration: NO_EXPIRATION,
+            }
+            .data(),
+        }
+    }
+
+    pub fn withdraw_sol_ix(&self, user: &Pubkey, amount: u64, min_x: u64, min_y: u64) -> Instruction {
+        let (user_x, user_y, native_mint, native_token_program) = self.native_accounts(user);
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::WithdrawSol {
+                user: *user,
+                mint_x: self.mint_x,
+                mint_y: self.mint_y,
+                mint_lp: self.mint_lp,
+                config: self.config,
+                oracle: self.oracle,
+                vault_x: self.vault_x,
+                vault_y: self.vault_y,
+                user_x,
+                user_y,
+                user_lp: self.user_lp(user),
+                native_mint,
+                wsol: self.wsol(user),
+                token_program: spl_token::ID,
+                token_program_x: self.token_program_x,
+                token_program_y: self.token_program_y,
+                native_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::WithdrawSol {
+                amount,
+                min_x,
+                min_y,
+                expiration: NO_EXPIRATION,
+            }
+            .data(),
+        }
+    }
+
+    pub fn swap_sol_ix(&self, user: &Pubkey, is_x: bool, amount: u64, min: u64) -> Instruction {
+        let (user_x, user_y, native_mint, native_token_program) = self.native_accounts(user);
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::SwapSol {
+                user: *user,
+                mint_x: self.mint_x,
+                mint_y: self.mint_y,
+                user_x,
+                user_y,
+                vault_x: self.vault_x,
+                vault_y: self.vault_y,
+                config: self.config,
+                oracle: self.oracle,
+                native_mint,
+                wsol: self.wsol(user),
+                token_program_x: self.token_program_x,
+                token_program_y: self.token_program_y,
+                native_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::SwapSol {
+                is_x,
+                amount,
+                min,
+                expiration: NO_EXPIRATION,
+            }
+            .data(),
+        }
+    }
+
+    pub fn lock_ix(&self, user: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::Update {
+                user: *user,
+                config: self.config,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::Lock {}.data(),
+        }
+    }
+}
+
+pub struct MultiPool {
+    pub seed: u64,
+    pub pool: Pubkey,
+    pub mints: Vec<Pubkey>,
+    pub token_programs: Vec<Pubkey>,
+    pub vaults: Vec<Pubkey>,
+    pub mint_lp: Pubkey,
+    pub dead: Pubkey,
+    pub locked_lp: Pubkey,
+}
+
+impl MultiPool {
+    pub fn new(seed: u64, tokens: &[(Pubkey, Pubkey)]) -> Self {
+        let pool = pda(&[b"multi_pool", &seed.to_le_bytes()]);
+        let mint_lp = pda(&[b"lp", pool.as_ref()]);
+        let dead = pda(&[b"dead", pool.as_ref()]);
+
+        Self {
+            seed,
+            pool,
+            mints: tokens.iter().map(|(mint, _)| *mint).collect(),
+            token_programs: tokens.iter().map(|(_, program)| *program).collect(),
+            vaults: tokens
+                .iter()
+                .map(|(mint, program)| get_associated_token_address_with_program_id(&pool, mint, program))
+                .collect(),
+            mint_lp,
+            dead,
+            locked_lp: get_associated_token_address(&dead, &mint_lp),
+        }
+    }
+
+    pub fn user_token(&self, user: &Pubkey, index: usize) -> Pubkey {
+        get_associated_token_address_with_program_id(user, &self.mints[index], &self.token_programs[index])
+    }
+
+    /// Remaining accounts of tokens `indices` for `user`.
+    fn token_metas(&self, user: &Pubkey, indices: &[usize]) -> Vec<AccountMeta> {
+        indices
+            .iter()
+            .flat_map(|index| {
+                [
+                    AccountMeta::new_readonly(self.mints[*index], false),
+                    AccountMeta::new(self.vaults[*index], false),
+                    AccountMeta::new(self.user_token(user, *index), false),
+                ]
+            })
+            .collect()
+    }
+
+    pub fn initialize_ix(&self, initializer: &Pubkey, fee: u16, authority: Option<Pubkey>, weights: Vec<u16>) -> Instruction {
+        let mut accounts = anchor_amm::accounts::InitializeMultiPool {
+            initializer: *initializer,
+            pool: self.pool,
+            mint_lp: self.mint_lp,
+            dead: self.dead,
+            locked_lp: self.locked_lp,
+            token_program: spl_token::ID,
+            token_program_2022: spl_token_2022::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None);
+        for (mint, vault) in self.mints.iter().zip(&self.vaults) {
+            accounts.push(AccountMeta::new_readonly(*mint, false));
+            accounts.push(AccountMeta::new(*vault, false));
+        }
+
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts,
+            data: anchor_amm::instruction::InitializeMultiPool {
+                seed: self.seed,
+                fee,
+                authority,
+                weights,
+            }
+            .data(),
+        }
+    }
+
+    fn liquidity_ix(&self, user: &Pubkey, indices: &[usize], data: impl InstructionData) -> Instruction {
+        let mut accounts = anchor_amm::accounts::MultiPoolLiquidity {
+            user: *user,
+            pool: self.pool,
+            mint_lp: self.mint_lp,
+            user_lp: get_associated_token_address(user, &self.mint_lp),
+            dead: self.dead,
+            locked_lp: self.locked_lp,
+            token_program: spl_token::ID,
+            token_program_2022: spl_token_2022::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(self.token_metas(user, indices));
+
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts,
+            data: data.data(),
+        }
+    }
+
+    pub fn deposit_ix(&self, user: &Pubkey, lp: u64, max_amounts: Vec<u64>) -> Instruction {
+        let all: Vec<_> = (0..self.mints.len()).collect();
+        self.liquidity_ix(
+            user,
+            &all,
+            anchor_amm::instruction::DepositMulti {
+                lp,
+                max_amounts,
+                expiration: NO_EXPIRATION,
+            },
+        )
+    }
+
+    pub fn withdraw_single_ix(&self, user: &Pubkey, index: u8, lp: u64, min_out: u64) -> Instruction {
+        self.liquidity_ix(
+            user,
+            &[index as usize],
+            anchor_amm::instruction::WithdrawMultiSingle {
+                index,
+                lp,
+                min_out,
+                expiration: NO_EXPIRATION,
+            },
+        )
+    }
+
+    pub fn swap_ix(&self, user: &Pubkey, index_in: u8, index_out: u8, amount_in: u64, min_out: u64) -> Instruction {
+        let mut accounts = anchor_amm::accounts::SwapMulti {
+            user: *user,
+            pool: self.pool,
+            token_program: spl_token::ID,
+            token_program_2022: spl_token_2022::ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(self.token_metas(user, &[index_in as usize, index_out as usize]));
+
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts,
+            data: anchor_amm::instruction::SwapMulti {
+                index_in,
+                index_out,
+                amount_in,
+                min_out,
+                expiration: NO_EXPIRATION,
+            }
+            .data(),
+        }
+    }
+}
+
+pub struct Farm {
+    pub farm: Pubkey,
+    pub config: Pubkey,
+    pub mint_lp: Pubkey,
+    pub reward_mint: Pubkey,
+    pub reward_token_program: Pubkey,
+    pub lp_vault: Pubkey,
+    pub reward_vault: Pubkey,
+}
+
+impl Farm {
+    pub fn new(pool: &Pool, (reward_mint, reward_token_program): (Pubkey, Pubkey)) -> Self {
+        let farm = pda(&[b"farm", pool.config.as_ref(), reward_mint.as_ref()]);
+
+        Self {
+            farm,
+            config: pool.config,
+            mint_lp: pool.mint_lp,
+            reward_mint,
+            reward_token_program,
+            lp_vault: get_associated_token_address(&farm, &pool.mint_lp),
+            reward_vault: get_associated_token_address_with_program_id(&farm, &reward_mint, &reward_token_program),
+        }
+    }
+
+    pub fn stake_address(&self, owner: &Pubkey) -> Pubkey {
+        pda(&[b"farm_stake", self.farm.as_ref(), owner.as_ref()])
+    }
+
+    pub fn create_ix(&self, authority: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::CreateFarm {
+                authority: *authority,
+                config: self.config,
+                mint_lp: self.mint_lp,
+                reward_mint: self.reward_mint,
+                farm: self.farm,
+                lp_vault: self.lp_vault,
+                reward_vault: self.reward_vault,
+                token_program: spl_token::ID,
+                reward_token_program: self.reward_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::CreateFarm {}.data(),
+        }
+    }
+
+    pub fn fund_ix(&self, funder: &Pubkey, funder_rewards: &Pubkey, amount: u64) -> Instruction {
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::FundFarm {
+                funder: *funder,
+                farm: self.farm,
+                reward_mint: self.reward_mint,
+                reward_vault: self.reward_vault,
+                funder_rewards: *funder_rewards,
+                reward_token_program: self.reward_token_program,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::FundFarm { amount }.data(),
+        }
+    }
+
+    pub fn set_emission_ix(&self, authority: &Pubkey, reward_rate: u64, emission_end: i64) -> Instruction {
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::UpdateFarm {
+                authority: *authority,
+                config: self.config,
+                farm: self.farm,
+                reward_mint: self.reward_mint,
+                reward_vault: self.reward_vault,
+                reward_token_program: self.reward_token_program,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::SetEmission {
+                reward_rate,
+                emission_end,
+            }
+            .data(),
+        }
+    }
+
+    pub fn stake_ix(&self, owner: &Pubkey, amount: u64) -> Instruction {
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::Stake {
+                owner: *owner,
+                farm: self.farm,
+                stake: self.stake_address(owner),
+                mint_lp: self.mint_lp,
+                owner_lp: get_associated_token_address(owner, &self.mint_lp),
+                lp_vault: self.lp_vault,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::Stake { amount }.data(),
+        }
+    }
+
+    pub fn unstake_ix(&self, owner: &Pubkey, amount: u64) -> Instruction {
+        let mut ix = self.stake_ix(owner, 0);
+        ix.data = anchor_amm::instruction::Unstake { amount }.data();
+        ix
+    }
+
+    pub fn harvest_ix(&self, owner: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::Harvest {
+                owner: *owner,
+                farm: self.farm,
+                stake: self.stake_address(owner),
+                reward_mint: self.reward_mint,
+                reward_vault: self.reward_vault,
+                owner_rewards: get_associated_token_address_with_program_id(
+                    owner,
+                    &self.reward_mint,
+                    &self.reward_token_program,
+                ),
+                reward_token_program: self.reward_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::Harvest {}.data(),
+        }
+    }
+}
+
+pub struct Order {
+    pub order: Pubkey,
+    pub config: Pubkey,
+    pub maker: Pubkey,
+    pub id: u64,
+    pub is_x: bool,
+    pub mint_in: Pubkey,
+    pub mint_out: Pubkey,
+    pub token_program_in: Pubkey,
+    pub token_program_out: Pubkey,
+    pub escrow: Pubkey,
+}
+
+impl Order {
+    pub fn new(pool: &Pool, maker: &Pubkey, id: u64, is_x: bool) -> Self {
+        let order = pda(&[b"order", pool.config.as_ref(), maker.as_ref(), &id.to_le_bytes()]);
+        let x = (pool.mint_x, pool.token_program_x);
+        let y = (pool.mint_y, pool.token_program_y);
+        let ((mint_in, token_program_in), (mint_out, token_program_out)) = match is_x {
+            true => (x, y),
+            false => (y, x),
+        };
+
+        Self {
+            order,
+            config: pool.config,
+            maker: *maker,
+            id,
+            is_x,
+            mint_in,
+            mint_out,
+            token_program_in,
+            token_program_out,
+            escrow: get_associated_token_address_with_program_id(&order, &mint_in, &token_program_in),
+        }
+    }
+
+    pub fn maker_in(&self) -> Pubkey {
+        get_associated_token_address_with_program_id(&self.maker, &self.mint_in, &self.token_program_in)
+    }
+
+    pub fn maker_out(&self) -> Pubkey {
+        get_associated_token_address_with_program_id(&self.maker, &self.mint_out, &self.token_program_out)
+    }
+
+    pub fn place_ix(&self, amount: u64, min_out: u64) -> Instruction {
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::PlaceOrder {
+                maker: self.maker,
+                config: self.config,
+                mint_in: self.mint_in,
+                order: self.order,
+                maker_in: self.maker_in(),
+                escrow: self.escrow,
+                token_program: self.token_program_in,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::PlaceOrder {
+                id: self.id,
+                is_x: self.is_x,
+                amount,
+                min_out,
+            }
+            .data(),
+        }
+    }
+
+    /// Fills the order by swapping through `pool` as `keeper`.
+    pub fn fill_ix(&self, pool: &Pool, keeper: &Pubkey) -> Instruction {
+        let mut accounts = anchor_amm::accounts::FillOrder {
+            keeper: *keeper,
+            maker: self.maker,
+            order: self.order,
+            mint_in: self.mint_in,
+            mint_out: self.mint_out,
+            escrow: self.escrow,
+            maker_out: self.maker_out(),
+            token_program_in: self.token_program_in,
+            token_program_out: self.token_program_out,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(pool.swap_ix(keeper, self.is_x, 0, 0).accounts);
+
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts,
+            data: anchor_amm::instruction::FillOrder {}.data(),
+        }
+    }
+
+    pub fn cancel_ix(&self) -> Instruction {
+        Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::CancelOrder {
+                maker: self.maker,
+                order: self.order,
+                mint_in: self.mint_in,
+                escrow: self.escrow,
+                maker_in: self.maker_in(),
+                token_program: self.token_program_in,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::CancelOrder {}.data(),
+        }
+    }
+}
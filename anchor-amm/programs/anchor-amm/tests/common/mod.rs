@@ -0,0 +1,692 @@
+#![allow(dead_code)]
+
+use anchor_amm::{
+    error::AmmError,
+    state::{Config, CurveType, Factory},
+};
+use anchor_lang::{
+    prelude::{Clock, ProgramData, Pubkey},
+    solana_program::{
+        instruction::{Instruction, InstructionError},
+        program_pack::Pack,
+    },
+    __private::base64::{engine::general_purpose::STANDARD, Engine},
+    AccountDeserialize, AnchorDeserialize, Event, Owner,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address_with_program_id},
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            interest_bearing_mint, transfer_fee, BaseStateWithExtensions, ExtensionType,
+            StateWithExtensions,
+        },
+    },
+};
+use litesvm::{types::TransactionMetadata, LiteSVM};
+use solana_sdk::{
+    account::Account,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+use solana_system_interface::instruction as system_instruction;
+
+pub mod hand_built;
+
+#[allow(unused_imports)]
+pub use anchor_amm_client::{
+    add_fee_tier, initialize_factory, migrate_config, pda, route_swap, Farm, LiquidityQuote, MultiPool, Order, Pool,
+    RouteHop, SwapQuote, Twap,
+};
+
+pub const DECIMALS: u8 = 6;
+
+/// Expiration for instructions that should never expire.
+pub const NO_EXPIRATION: i64 = i64::MAX;
+
+pub fn setup() -> (LiteSVM, Keypair) {
+    let mut svm = LiteSVM::new();
+    svm.add_program_from_file(anchor_amm::ID, "../../target/deploy/anchor_amm.so")
+        .expect("run `anchor build` before `cargo test`");
+
+    let admin = Keypair::new();
+    svm.airdrop(&admin.pubkey(), 100_000_000_000).unwrap();
+
+    // LiteSVM loads the program without a `ProgramData`, so stand one in
+    // naming `admin` as the upgrade authority that creates the factory.
+    // The loader's bincode layout: the `ProgramData` variant, its slot and
+    // the upgrade authority as an `Option`.
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend(0u64.to_le_bytes());
+    data.push(1);
+    data.extend(admin.pubkey().to_bytes());
+    let account = Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: ProgramData::owner(),
+        executable: false,
+        rent_epoch: 0,
+    };
+    svm.set_account(Factory::find_program_data().0, account).unwrap();
+
+    (svm, admin)
+}
+
+pub type TxResult = Result<TransactionMetadata, TransactionError>;
+
+pub fn send(svm: &mut LiteSVM, payer: &Keypair, ixs: &[Instruction]) -> TxResult {
+    let tx = Transaction::new_signed_with_payer(
+        ixs,
+        Some(&payer.pubkey()),
+        &[payer],
+        svm.latest_blockhash(),
+    );
+    let res = svm.send_transaction(tx).map_err(|failed| failed.err);
+    svm.expire_blockhash();
+    res
+}
+
+/// Simulates `ix` and decodes the `T` it returns.
+pub fn simulate<T: AnchorDeserialize>(
+    svm: &LiteSVM,
+    payer: &Keypair,
+    ix: Instruction,
+) -> Result<T, TransactionError> {
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer],
+        svm.latest_blockhash(),
+    );
+    let info = svm.simulate_transaction(tx).map_err(|failed| failed.err)?;
+    Ok(T::try_from_slice(&info.meta.return_data.data).unwrap())
+}
+
+/// Events of type `T` the transaction emitted, in order.
+pub fn events<T: Event>(meta: &TransactionMetadata) -> Vec<T> {
+    meta.logs
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| STANDARD.decode(data).ok())
+        .filter(|data| data.starts_with(T::DISCRIMINATOR))
+        .map(|data| T::deserialize(&mut &data[T::DISCRIMINATOR.len()..]).unwrap())
+        .collect()
+}
+
+pub fn assert_amm_error<T: std::fmt::Debug>(res: Result<T, TransactionError>, error: AmmError) {
+    assert_amm_error_at(res, 0, error);
+}
+
+/// Like `assert_amm_error` for the instruction at `index` of the transaction.
+pub fn assert_amm_error_at<T: std::fmt::Debug>(
+    res: Result<T, TransactionError>,
+    index: u8,
+    error: AmmError,
+) {
+    let err = res.expect_err("transaction should have failed");
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(index, InstructionError::Custom(error.into()))
+    );
+}
+
+pub fn assert_anchor_error(res: TxResult, error: anchor_lang::error::ErrorCode) {
+    let err = res.expect_err("transaction should have failed");
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+    );
+}
+
+pub fn create_mint(svm: &mut LiteSVM, authority: &Keypair) -> Pubkey {
+    let mint = Keypair::new();
+    let rent = svm.minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN);
+
+    let ixs = [
+        system_instruction::create_account(
+            &authority.pubkey(),
+            &mint.pubkey(),
+            rent,
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_mint2(
+            &spl_token::ID,
+            &mint.pubkey(),
+            &authority.pubkey(),
+            None,
+            DECIMALS,
+        )
+        .unwrap(),
+    ];
+
+    let tx = Transaction::new_signed_with_payer(
+        &ixs,
+        Some(&authority.pubkey()),
+        &[authority, &mint],
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx).unwrap();
+
+    mint.pubkey()
+}
+
+/// Two fresh mints owned by `authority`, sorted as a pool's must be.
+pub fn sorted_mints(svm: &mut LiteSVM, authority: &Keypair) -> (Pubkey, Pubkey) {
+    let (a, b) = (create_mint(svm, authority), create_mint(svm, authority));
+    (a.min(b), a.max(b))
+}
+
+/// Creates mints with `create_x` and `create_y` until the x one sorts
+/// first, for pools whose mints are not interchangeable.
+pub fn sort_mints(
+    svm: &mut LiteSVM,
+    create_x: impl Fn(&mut LiteSVM) -> Pubkey,
+    create_y: impl Fn(&mut LiteSVM) -> Pubkey,
+) -> (Pubkey, Pubkey) {
+    loop {
+        let (mint_x, mint_y) = (create_x(svm), create_y(svm));
+        if mint_x < mint_y {
+            return (mint_x, mint_y);
+        }
+    }
+}
+
+/// Creates a Token-2022 mint with `extensions`, set up by `init` before the
+/// mint itself is initialized.
+pub fn create_mint_2022(
+    svm: &mut LiteSVM,
+    authority: &Keypair,
+    extensions: &[ExtensionType],
+    init: impl FnOnce(&Pubkey) -> Vec<Instruction>,
+) -> Pubkey {
+    let mint = Keypair::new();
+    let space =
+        ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(extensions).unwrap();
+    let rent = svm.minimum_balance_for_rent_exemption(space);
+
+    let mut ixs = vec![system_instruction::create_account(
+        &authority.pubkey(),
+        &mint.pubkey(),
+        rent,
+        space as u64,
+        &spl_token_2022::ID,
+    )];
+    ixs.extend(init(&mint.pubkey()));
+    ixs.push(
+        spl_token_2022::instruction::initialize_mint2(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            &authority.pubkey(),
+            None,
+            DECIMALS,
+        )
+        .unwrap(),
+    );
+
+    let tx = Transaction::new_signed_with_payer(
+        &ixs,
+        Some(&authority.pubkey()),
+        &[authority, &mint],
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx).unwrap();
+
+    mint.pubkey()
+}
+
+/// Token-2022 mint charging `basis_points` on transfers, up to `maximum_fee`.
+pub fn create_transfer_fee_mint(
+    svm: &mut LiteSVM,
+    authority: &Keypair,
+    basis_points: u16,
+    maximum_fee: u64,
+) -> Pubkey {
+    let key = authority.pubkey();
+    create_mint_2022(svm, authority, &[ExtensionType::TransferFeeConfig], |mint| {
+        vec![transfer_fee::instruction::initialize_transfer_fee_config(
+            &spl_token_2022::ID,
+            mint,
+            Some(&key),
+            Some(&key),
+            basis_points,
+            maximum_fee,
+        )
+        .unwrap()]
+    })
+}
+
+pub fn create_interest_bearing_mint(svm: &mut LiteSVM, authority: &Keypair, rate: i16) -> Pubkey {
+    let key = authority.pubkey();
+    create_mint_2022(svm, authority, &[ExtensionType::InterestBearingConfig], |mint| {
+        vec![interest_bearing_mint::instruction::initialize(
+            &spl_token_2022::ID,
+            mint,
+            Some(key),
+            rate,
+        )
+        .unwrap()]
+    })
+}
+
+pub fn create_permanent_delegate_mint(svm: &mut LiteSVM, authority: &Keypair) -> Pubkey {
+    let key = authority.pubkey();
+    create_mint_2022(svm, authority, &[ExtensionType::PermanentDelegate], |mint| {
+        vec![spl_token_2022::instruction::initialize_permanent_delegate(
+            &spl_token_2022::ID,
+            mint,
+            &key,
+        )
+        .unwrap()]
+    })
+}
+
+/// The classic token program's wrapped SOL mint, stored unless LiteSVM
+/// already has it.
+pub fn native_mint(svm: &mut LiteSVM) -> Pubkey {
+    let mint = spl_token::native_mint::ID;
+    if svm.get_account(&mint).is_none() {
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            decimals: spl_token::native_mint::DECIMALS,
+            is_initialized: true,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        let account = Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        svm.set_account(mint, account).unwrap();
+    }
+    mint
+}
+
+pub fn lamports(svm: &LiteSVM, address: &Pubkey) -> u64 {
+    svm.get_account(address).map_or(0, |account| account.lamports)
+}
+
+/// Token program owning `mint`.
+pub fn token_program_of(svm: &LiteSVM, mint: &Pubkey) -> Pubkey {
+    svm.get_account(mint).unwrap().owner
+}
+
+pub fn create_ata(svm: &mut LiteSVM, payer: &Keypair, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    let token_program = token_program_of(svm, mint);
+    let ix = associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+        &payer.pubkey(),
+        owner,
+        mint,
+        &token_program,
+    );
+    send(svm, payer, &[ix]).unwrap();
+
+    get_associated_token_address_with_program_id(owner, mint, &token_program)
+}
+
+pub fn mint_to(svm: &mut LiteSVM, authority: &Keypair, mint: &Pubkey, to: &Pubkey, amount: u64) {
+    let ix = spl_token_2022::instruction::mint_to(
+        &token_program_of(svm, mint),
+        mint,
+        to,
+        &authority.pubkey(),
+        &[],
+        amount,
+    )
+    .unwrap();
+    send(svm, authority, &[ix]).unwrap();
+}
+
+pub fn transfer(svm: &mut LiteSVM, owner: &Keypair, from: &Pubkey, to: &Pubkey, amount: u64) {
+    let account = svm.get_account(from).unwrap();
+    let mint = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+        .unwrap()
+        .base
+        .mint;
+    let ix = spl_token_2022::instruction::transfer_checked(
+        &account.owner,
+        from,
+        &mint,
+        to,
+        &owner.pubkey(),
+        &[],
+        amount,
+        DECIMALS,
+    )
+    .unwrap();
+    send(svm, owner, &[ix]).unwrap();
+}
+
+pub fn token_balance(svm: &LiteSVM, address: &Pubkey) -> u64 {
+    svm.get_account(address)
+        .map(|account| {
+            StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+                .unwrap()
+                .base
+                .amount
+        })
+        .unwrap_or(0)
+}
+
+pub fn mint_supply(svm: &LiteSVM, mint: &Pubkey) -> u64 {
+    let account = svm.get_account(mint).unwrap();
+    StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&account.data)
+        .unwrap()
+        .base
+        .supply
+}
+
+/// Fee withheld from a transfer of `amount` of `mint` this epoch.
+pub fn transfer_fee_of(svm: &LiteSVM, mint: &Pubkey, amount: u64) -> u64 {
+    let account = svm.get_account(mint).unwrap();
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&account.data).unwrap();
+    let clock: Clock = svm.get_sysvar();
+    state
+        .get_extension::<transfer_fee::TransferFeeConfig>()
+        .map(|config| config.calculate_epoch_fee(clock.epoch, amount).unwrap())
+        .unwrap_or(0)
+}
+
+/// Replaces the trailing `expiration` argument of a swap, deposit or
+/// withdraw instruction.
+pub fn with_expiration(mut ix: Instruction, expiration: i64) -> Instruction {
+    let len = ix.data.len();
+    ix.data[len - 8..].copy_from_slice(&expiration.to_le_bytes());
+    ix
+}
+
+/// Moves the clock `seconds` forward, into a later slot.
+pub fn warp_seconds(svm: &mut LiteSVM, seconds: i64) {
+    let mut clock: Clock = svm.get_sysvar();
+    clock.unix_timestamp += seconds;
+    clock.slot += 1;
+    svm.set_sysvar(&clock);
+}
+
+/// Moves to the next slot within the same second.
+#[allow(dead_code)]
+pub fn warp_slot(svm: &mut LiteSVM) {
+    let mut clock: Clock = svm.get_sysvar();
+    clock.slot += 1;
+    svm.set_sysvar(&clock);
+}
+
+pub fn fetch_config(svm: &LiteSVM, address: &Pubkey) -> Config {
+    fetch_account(svm, address)
+}
+
+pub fn fetch_account<T: AccountDeserialize>(svm: &LiteSVM, address: &Pubkey) -> T {
+    let account = svm.get_account(address).unwrap();
+    T::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+/// Test setup for pools, on top of the client's addresses and builders.
+pub trait PoolExt: Sized {
+    /// Addresses of the pool for existing mints, looking up their token
+    /// programs.
+    fn for_mints(svm: &LiteSVM, seed: u64, mint_x: Pubkey, mint_y: Pubkey) -> Self;
+
+    /// Creates two fresh mints owned by `admin` and initializes a constant
+    /// product pool for them.
+    fn create(svm: &mut LiteSVM, admin: &Keypair, seed: u64, fee: u16) -> Self {
+        Self::create_with_curve(svm, admin, seed, fee, CurveType::ConstantProduct, 0)
+    }
+
+    fn create_with_curve(svm: &mut LiteSVM, admin: &Keypair, seed: u64, fee: u16, curve: CurveType, amp: u64)
+        -> Self;
+
+    /// Creates two fresh mints owned by `admin` and initializes a weighted
+    /// pool holding `weight_x` basis points of its value in x.
+    fn create_weighted(svm: &mut LiteSVM, admin: &Keypair, seed: u64, fee: u16, weight_x: u16) -> Self;
+
+    /// Initializes a constant product pool for existing mints of either
+    /// token program, which must be sorted.
+    fn create_with_mints(
+        svm: &mut LiteSVM,
+        admin: &Keypair,
+        seed: u64,
+        fee: u16,
+        mint_x: Pubkey,
+        mint_y: Pubkey,
+    ) -> Self;
+
+    /// Creates two fresh mints owned by `admin` and initializes a
+    /// concentrated liquidity pool for them.
+    fn create_concentrated(
+        svm: &mut LiteSVM,
+        admin: &Keypair,
+        seed: u64,
+        fee: u16,
+        tick_spacing: u16,
+        sqrt_price: u128,
+    ) -> Self;
+
+    /// Funds a new user with SOL and `x`/`y` tokens of the pool's mints.
+    fn create_user(&self, svm: &mut LiteSVM, admin: &Keypair, x: u64, y: u64) -> Keypair;
+}
+
+impl PoolExt for Pool {
+    fn for_mints(svm: &LiteSVM, seed: u64, mint_x: Pubkey, mint_y: Pubkey) -> Self {
+        Self::new(
+            seed,
+            (mint_x, token_program_of(svm, &mint_x)),
+            (mint_y, token_program_of(svm, &mint_y)),
+        )
+    }
+
+    fn create_with_curve(svm: &mut LiteSVM, admin: &Keypair, seed: u64, fee: u16, curve: CurveType, amp: u64) -> Self {
+        let (mint_x, mint_y) = sorted_mints(svm, admin);
+        let pool = Self::for_mints(svm, seed, mint_x, mint_y);
+
+        let pool_count = factory_with_tier(svm, admin, fee);
+        let ix = pool.create_pool(&admin.pubkey(), fee, curve, amp, pool_count);
+        send(svm, admin, &[ix]).unwrap();
+
+        pool
+    }
+
+    fn create_weighted(svm: &mut LiteSVM, admin: &Keypair, seed: u64, fee: u16, weight_x: u16) -> Self {
+        let (mint_x, mint_y) = sorted_mints(svm, admin);
+        let pool = Self::for_mints(svm, seed, mint_x, mint_y);
+
+        let pool_count = factory_with_tier(svm, admin, fee);
+        let ix = pool.create_weighted_pool(&admin.pubkey(), fee, weight_x, pool_count);
+        send(svm, admin, &[ix]).unwrap();
+
+        pool
+    }
+
+    fn create_with_mints(
+        svm: &mut LiteSVM,
+        admin: &Keypair,
+        seed: u64,
+        fee: u16,
+        mint_x: Pubkey,
+        mint_y: Pubkey,
+    ) -> Self {
+        let pool = Self::for_mints(svm, seed, mint_x, mint_y);
+
+        let pool_count = factory_with_tier(svm, admin, fee);
+        let ix = pool.create_pool(&admin.pubkey(), fee, CurveType::ConstantProduct, 0, pool_count);
+        send(svm, admin, &[ix]).unwrap();
+
+        pool
+    }
+
+    fn create_concentrated(
+        svm: &mut LiteSVM,
+        admin: &Keypair,
+        seed: u64,
+        fee: u16,
+        tick_spacing: u16,
+        sqrt_price: u128,
+    ) -> Self {
+        let (mint_x, mint_y) = sorted_mints(svm, admin);
+        let pool = Self::for_mints(svm, seed, mint_x, mint_y);
+
+        let pool_count = factory_with_tier(svm, admin, fee);
+        let ix = pool.create_concentrated_pool(&admin.pubkey(), fee, tick_spacing, sqrt_price, pool_count);
+        send(svm, admin, &[ix]).unwrap();
+
+        pool
+    }
+
+    fn create_user(&self, svm: &mut LiteSVM, admin: &Keypair, x: u64, y: u64) -> Keypair {
+        let user = Keypair::new();
+        svm.airdrop(&user.pubkey(), 10_000_000_000).unwrap();
+
+        let user_x = create_ata(svm, admin, &user.pubkey(), &self.mint_x);
+        let user_y = create_ata(svm, admin, &user.pubkey(), &self.mint_y);
+        if x > 0 {
+            mint_to(svm, admin, &self.mint_x, &user_x, x);
+        }
+        if y > 0 {
+            mint_to(svm, admin, &self.mint_y, &user_y, y);
+        }
+
+        user
+    }
+}
+
+/// Appends the ticks a swap on a concentrated liquidity pool may cross.
+pub fn with_ticks(mut ix: Instruction, pool: &Pool, ticks: &[i32]) -> Instruction {
+    ix.accounts.extend(pool.tick_metas(ticks));
+    ix
+}
+
+/// Test setup for multi-asset pools.
+pub trait MultiPoolExt: Sized {
+    /// Addresses of the pool for existing mints, looking up their token
+    /// programs.
+    fn for_mints(svm: &LiteSVM, seed: u64, mints: &[Pubkey]) -> Self;
+
+    /// Creates one fresh mint owned by `admin` per weight and initializes
+    /// a pool of them.
+    fn create(svm: &mut LiteSVM, admin: &Keypair, seed: u64, fee: u16, weights: &[u16]) -> Self;
+
+    /// Funds a new user with SOL and `amounts` of the pool's tokens.
+    fn create_user(&self, svm: &mut LiteSVM, admin: &Keypair, amounts: &[u64]) -> Keypair;
+
+    fn balances(&self, svm: &LiteSVM) -> Vec<u64>;
+}
+
+impl MultiPoolExt for MultiPool {
+    fn for_mints(svm: &LiteSVM, seed: u64, mints: &[Pubkey]) -> Self {
+        Self::new(
+            seed,
+            mints.iter().map(|mint| (*mint, token_program_of(svm, mint))).collect(),
+        )
+    }
+
+    fn create(svm: &mut LiteSVM, admin: &Keypair, seed: u64, fee: u16, weights: &[u16]) -> Self {
+        let mints: Vec<_> = weights.iter().map(|_| create_mint(svm, admin)).collect();
+        let pool = Self::for_mints(svm, seed, &mints);
+
+        let ix = pool.initialize(&admin.pubkey(), fee, Some(admin.pubkey()), weights.to_vec());
+        send(svm, admin, &[ix]).unwrap();
+
+        pool
+    }
+
+    fn create_user(&self, svm: &mut LiteSVM, admin: &Keypair, amounts: &[u64]) -> Keypair {
+        let user = Keypair::new();
+        svm.airdrop(&user.pubkey(), 10_000_000_000).unwrap();
+
+        for ((mint, _), amount) in self.tokens.iter().zip(amounts) {
+            let account = create_ata(svm, admin, &user.pubkey(), mint);
+            if *amount > 0 {
+                mint_to(svm, admin, mint, &account, *amount);
+            }
+        }
+
+        user
+    }
+
+    fn balances(&self, svm: &LiteSVM) -> Vec<u64> {
+        self.vaults.iter().map(|vault| token_balance(svm, vault)).collect()
+    }
+}
+
+/// Test setup for farms.
+pub trait FarmExt: Sized {
+    /// Addresses of the farm of `pool` paying `reward_mint`, looking up its
+    /// token program.
+    fn for_mint(svm: &LiteSVM, pool: &Pool, reward_mint: Pubkey) -> Self;
+
+    /// Creates a fresh reward mint owned by `admin` and a farm of `pool`
+    /// paying it.
+    fn setup(svm: &mut LiteSVM, admin: &Keypair, pool: &Pool) -> Self;
+
+    /// Mints `amount` of the reward mint to `admin` and funds the farm with
+    /// it.
+    fn mint_and_fund(&self, svm: &mut LiteSVM, admin: &Keypair, amount: u64);
+}
+
+impl FarmExt for Farm {
+    fn for_mint(svm: &LiteSVM, pool: &Pool, reward_mint: Pubkey) -> Self {
+        Self::new(pool, (reward_mint, token_program_of(svm, &reward_mint)))
+    }
+
+    fn setup(svm: &mut LiteSVM, admin: &Keypair, pool: &Pool) -> Self {
+        let reward_mint = create_mint(svm, admin);
+        let farm = Self::for_mint(svm, pool, reward_mint);
+
+        let ix = farm.create(&admin.pubkey());
+        send(svm, admin, &[ix]).unwrap();
+
+        farm
+    }
+
+    fn mint_and_fund(&self, svm: &mut LiteSVM, admin: &Keypair, amount: u64) {
+        let rewards = create_ata(svm, admin, &admin.pubkey(), &self.reward_mint);
+        mint_to(svm, admin, &self.reward_mint, &rewards, amount);
+        let ix = self.fund(&admin.pubkey(), &rewards, amount);
+        send(svm, admin, &[ix]).unwrap();
+    }
+}
+
+/// The maker's accounts of the tokens an order sells and buys.
+pub trait OrderExt {
+    fn maker_in(&self) -> Pubkey;
+
+    fn maker_out(&self) -> Pubkey;
+}
+
+impl OrderExt for Order {
+    fn maker_in(&self) -> Pubkey {
+        match self.is_x {
+            true => self.pool.user_x(&self.maker),
+            false => self.pool.user_y(&self.maker),
+        }
+    }
+
+    fn maker_out(&self) -> Pubkey {
+        match self.is_x {
+            true => self.pool.user_y(&self.maker),
+            false => self.pool.user_x(&self.maker),
+        }
+    }
+}
+
+/// Creates the factory with `admin` as its authority, or adds `fee` to its
+/// tiers, so pools can be created at `fee`, and returns its pool count.
+pub fn factory_with_tier(svm: &mut LiteSVM, admin: &Keypair, fee: u16) -> u64 {
+    let address = pda::factory();
+    let ix = match svm.get_account(&address) {
+        None => initialize_factory(&admin.pubkey(), vec![fee]),
+        Some(_) => {
+            let factory: Factory = fetch_account(svm, &address);
+            if factory.fee_tiers.contains(&fee) {
+                return factory.pool_count;
+            }
+            add_fee_tier(&admin.pubkey(), fee)
+        }
+    };
+    send(svm, admin, &[ix]).unwrap();
+    fetch_account::<Factory>(svm, &address).pool_count
+}
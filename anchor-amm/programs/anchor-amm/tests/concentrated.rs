@@ -0,0 +1,251 @@
+mod common;
+
+use anchor_amm::{
+    curves::concentrated::{self, MAX_TICK, MIN_TICK},
+    error::AmmError,
+    math::mul_div,
+    state::{Position, Tick},
+};
+use anchor_lang::prelude::Clock;
+use common::*;
+use litesvm::LiteSVM;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+const ONE: u128 = 1 << 64;
+const SPACING: u16 = 10;
+
+#[test]
+fn tick_math_round_trips() {
+    assert_eq!(concentrated::sqrt_price_at_tick(0), ONE);
+
+    for tick in [MIN_TICK + 1, -200_000, -1, 0, 1, 23_027, 200_000, MAX_TICK - 1] {
+        let price = concentrated::sqrt_price_at_tick(tick);
+        assert!(price < concentrated::sqrt_price_at_tick(tick + 1));
+        assert_eq!(concentrated::tick_at_sqrt_price(price), tick);
+        assert_eq!(concentrated::tick_at_sqrt_price(price + 1), tick);
+        assert_eq!(concentrated::tick_at_sqrt_price(price - 1), tick - 1);
+    }
+
+    // 1.0001^23027 is just under 10.
+    let price = concentrated::sqrt_price_at_tick(23_027);
+    assert_eq!(mul_div(price, price, ONE).unwrap() >> 64, 9);
+}
+
+#[test]
+fn swap_steps_round_in_the_pools_favour() {
+    let (lower, upper) = (
+        concentrated::sqrt_price_at_tick(-1_000),
+        concentrated::sqrt_price_at_tick(1_000),
+    );
+    let (x, y) = concentrated::amounts_for_liquidity(ONE, lower, upper, 10_000_000, true).unwrap();
+    let (x_out, y_out) =
+        concentrated::amounts_for_liquidity(ONE, lower, upper, 10_000_000, false).unwrap();
+    assert!(x == x_out + 1 && y == y_out + 1);
+    assert_eq!(
+        concentrated::amounts_for_liquidity(upper, lower, upper, 10_000_000, true).unwrap().0,
+        0
+    );
+
+    // Inside a range the whole input is consumed, part of it as fee.
+    let step = concentrated::compute_swap_step(ONE, lower, 10_000_000, 10_000, 30, true).unwrap();
+    assert!(step.sqrt_price < ONE && step.sqrt_price > lower);
+    assert_eq!(step.amount_in + step.fee, 10_000);
+    assert!(step.fee >= 30);
+    assert!(step.amount_out < step.amount_in);
+
+    // A step that reaches its target keeps the rest of the input.
+    let step = concentrated::compute_swap_step(ONE, lower, 10_000_000, 10_000_000, 30, true).unwrap();
+    assert_eq!(step.sqrt_price, lower);
+    assert_eq!(
+        step.amount_in,
+        concentrated::delta_x(lower, ONE, 10_000_000, true).unwrap()
+    );
+    assert!(step.amount_in + step.fee < 10_000_000);
+}
+
+#[test]
+fn exact_out_steps_match_exact_in() {
+    let (lower, upper) = (
+        concentrated::sqrt_price_at_tick(-1_000),
+        concentrated::sqrt_price_at_tick(1_000),
+    );
+
+    for (is_x, target) in [(true, lower), (false, upper)] {
+        // Paying the quoted input back in buys at least the same output.
+        let step =
+            concentrated::compute_swap_step_exact_out(ONE, target, 10_000_000, 10_000, 30, is_x).unwrap();
+        assert_eq!(step.amount_out, 10_000);
+        assert!(step.fee >= 30);
+        let back =
+            concentrated::compute_swap_step(ONE, target, 10_000_000, step.amount_in + step.fee, 30, is_x)
+                .unwrap();
+        assert!(back.amount_out >= 10_000);
+
+        // Asking for more than the range holds stops at its end.
+        let step =
+            concentrated::compute_swap_step_exact_out(ONE, target, 10_000_000, 10_000_000, 30, is_x).unwrap();
+        assert_eq!(step.sqrt_price, target);
+        assert!(step.amount_out < 10_000_000);
+    }
+}
+
+/// Initializes `ticks` in ascending order between the sentinels.
+fn init_ticks(svm: &mut LiteSVM, admin: &Keypair, pool: &Pool, ticks: &[i32]) {
+    let (min, max) = Tick::bounds(SPACING);
+    let mut initialized = vec![min, max];
+    for &index in ticks {
+        let next = initialized.iter().position(|&t| t > index).unwrap();
+        let ix = pool.initialize_tick(&admin.pubkey(), index, initialized[next - 1], initialized[next]);
+        send(svm, admin, &[ix]).unwrap();
+        initialized.insert(next, index);
+    }
+}
+
+#[test]
+fn positions_earn_fees_only_in_range() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create_concentrated(&mut svm, &admin, 1, 30, SPACING, ONE);
+    let wide = pool.create_user(&mut svm, &admin, 10_000_000, 10_000_000);
+    let narrow = pool.create_user(&mut svm, &admin, 10_000_000, 10_000_000);
+    let trader = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+    init_ticks(&mut svm, &admin, &pool, &[-1_000, 1_000, 100, 200]);
+
+    let ix = pool.open_position(&wide.pubkey(), -1_000, 1_000);
+    send(&mut svm, &wide, &[ix]).unwrap();
+    let ix = pool.add_liquidity(&wide.pubkey(), (-1_000, 1_000), 10_000_000, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &wide, &[ix]).unwrap();
+
+    // A range above the price is funded with x only.
+    let ix = pool.open_position(&narrow.pubkey(), 100, 200);
+    send(&mut svm, &narrow, &[ix]).unwrap();
+    let ix = pool.add_liquidity(&narrow.pubkey(), (100, 200), 10_000_000, 1_000_000, 0, NO_EXPIRATION);
+    send(&mut svm, &narrow, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_y(&narrow.pubkey())), 10_000_000);
+
+    let config = fetch_config(&svm, &pool.config);
+    assert_eq!((config.liquidity, config.tick_below, config.tick_above), (10_000_000, -1_000, 100));
+
+    // A small swap stays below 100 and pays only the wide position.
+    let ix = with_ticks(pool.swap(&trader.pubkey(), false, 10_000, 1, NO_EXPIRATION), &pool, &[]);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    let config = fetch_config(&svm, &pool.config);
+    assert!(config.tick_current > 0 && config.tick_current < 100);
+
+    let ix = pool.collect_fees(&narrow.pubkey(), (100, 200));
+    send(&mut svm, &narrow, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_y(&narrow.pubkey())), 10_000_000);
+
+    let y_before = token_balance(&svm, &pool.user_y(&wide.pubkey()));
+    let ix = pool.collect_fees(&wide.pubkey(), (-1_000, 1_000));
+    send(&mut svm, &wide, &[ix]).unwrap();
+    let earned = token_balance(&svm, &pool.user_y(&wide.pubkey())) - y_before;
+    assert!((29..=31).contains(&earned));
+
+    // Crossing 100 requires its tick account and activates the narrow range.
+    let ix = with_ticks(pool.swap(&trader.pubkey(), false, 100_000, 1, NO_EXPIRATION), &pool, &[]);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InvalidTick);
+    let ix = with_ticks(pool.swap(&trader.pubkey(), false, 100_000, 1, NO_EXPIRATION), &pool, &[200]);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InvalidTick);
+
+    let ix = with_ticks(pool.swap(&trader.pubkey(), false, 100_000, 1, NO_EXPIRATION), &pool, &[100]);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    let config = fetch_config(&svm, &pool.config);
+    assert_eq!((config.liquidity, config.tick_below, config.tick_above), (20_000_000, 100, 200));
+    assert!(config.tick_current >= 100 && config.tick_current < 200);
+
+    let ix = pool.collect_fees(&narrow.pubkey(), (100, 200));
+    send(&mut svm, &narrow, &[ix]).unwrap();
+    assert!(token_balance(&svm, &pool.user_y(&narrow.pubkey())) > 10_000_000);
+
+    // Swapping back down crosses 100 again and deactivates it.
+    let ix = with_ticks(pool.swap(&trader.pubkey(), true, 200_000, 1, NO_EXPIRATION), &pool, &[100]);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    let config = fetch_config(&svm, &pool.config);
+    assert_eq!((config.liquidity, config.tick_below, config.tick_above), (10_000_000, -1_000, 100));
+    assert!(config.tick_current < 100);
+}
+
+#[test]
+fn liquidity_is_withdrawn_and_position_closed() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create_concentrated(&mut svm, &admin, 1, 30, SPACING, ONE);
+    let lp = pool.create_user(&mut svm, &admin, 10_000_000, 10_000_000);
+    let trader = pool.create_user(&mut svm, &admin, 100_000, 0);
+    init_ticks(&mut svm, &admin, &pool, &[-1_000, 1_000]);
+    let range = (-1_000, 1_000);
+
+    let ix = pool.open_position(&lp.pubkey(), 1_000, -1_000);
+    assert_amm_error(send(&mut svm, &lp, &[ix]), AmmError::InvalidTickRange);
+    let ix = pool.open_position(&lp.pubkey(), -1_000, 1_005);
+    assert_amm_error(send(&mut svm, &lp, &[ix]), AmmError::InvalidTickRange);
+
+    let ix = pool.open_position(&lp.pubkey(), -1_000, 1_000);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    let ix = pool.add_liquidity(&lp.pubkey(), range, 10_000_000, 1, 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &lp, &[ix]), AmmError::SlippageExceeded);
+    let now = svm.get_sysvar::<Clock>().unix_timestamp;
+    let ix = pool.add_liquidity(&lp.pubkey(), range, 10_000_000, 1_000_000, 1_000_000, now - 1);
+    assert_amm_error(send(&mut svm, &lp, &[ix]), AmmError::OfferExpired);
+    let ix = pool.add_liquidity(&lp.pubkey(), range, 10_000_000, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    let ix = with_ticks(pool.swap(&trader.pubkey(), true, 100_000, 1, NO_EXPIRATION), &pool, &[]);
+    send(&mut svm, &trader, &[ix]).unwrap();
+
+    let ix = pool.remove_liquidity(&lp.pubkey(), range, 10_000_001, 0, 0, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &lp, &[ix]), AmmError::InsufficientBalance);
+    let ix = pool.remove_liquidity(&lp.pubkey(), range, 10_000_000, 0, 0, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    let position: Position = fetch_account(&svm, &pool.position(&lp.pubkey(), -1_000, 1_000));
+    assert_eq!(position.liquidity, 0);
+    assert!(position.fees_owed_x >= 299);
+    assert_eq!(fetch_config(&svm, &pool.config).liquidity, 0);
+
+    let ix = pool.close_position(&lp.pubkey(), range);
+    assert_amm_error(send(&mut svm, &lp, &[ix]), AmmError::PositionNotEmpty);
+
+    let ix = pool.collect_fees(&lp.pubkey(), range);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    let ix = pool.close_position(&lp.pubkey(), range);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    assert!(svm.get_account(&pool.position(&lp.pubkey(), -1_000, 1_000)).is_none());
+
+    // Only rounding dust remains in the vaults.
+    assert!(token_balance(&svm, &pool.vault_x) < 10);
+    assert!(token_balance(&svm, &pool.vault_y) < 10);
+}
+
+#[test]
+fn exact_out_swaps_cross_ticks() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create_concentrated(&mut svm, &admin, 1, 30, SPACING, ONE);
+    let lp = pool.create_user(&mut svm, &admin, 10_000_000, 10_000_000);
+    let trader = pool.create_user(&mut svm, &admin, 0, 1_000_000);
+    init_ticks(&mut svm, &admin, &pool, &[-1_000, 100, 1_000]);
+
+    let ix = pool.open_position(&lp.pubkey(), -1_000, 1_000);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    let ix = pool.add_liquidity(&lp.pubkey(), (-1_000, 1_000), 10_000_000, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    // 100,000 x moves the price past 100.
+    let ix = with_ticks(pool.swap_exact_out(&trader.pubkey(), false, 100_000, 1_000_000, NO_EXPIRATION), &pool, &[]);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InvalidTick);
+    let ix = with_ticks(pool.swap_exact_out(&trader.pubkey(), false, 100_000, 100_000, NO_EXPIRATION), &pool, &[100]);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::SlippageExceeded);
+
+    let ix = with_ticks(pool.swap_exact_out(&trader.pubkey(), false, 100_000, 1_000_000, NO_EXPIRATION), &pool, &[100]);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_x(&trader.pubkey())), 100_000);
+    let spent = 1_000_000 - token_balance(&svm, &pool.user_y(&trader.pubkey()));
+    assert!(spent > 100_000 && spent < 102_000);
+    let config = fetch_config(&svm, &pool.config);
+    assert!(config.tick_current >= 100 && config.tick_above == 1_000);
+
+    // More than the ranges hold runs into the sentinel.
+    let (_, max) = Tick::bounds(SPACING);
+    let swap = pool.swap_exact_out(&trader.pubkey(), false, 1_000_000, 1_000_000, NO_EXPIRATION);
+    let ix = with_ticks(swap, &pool, &[1_000, max]);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InsufficientLiquidity);
+}
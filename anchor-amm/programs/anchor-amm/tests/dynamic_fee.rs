@@ -0,0 +1,133 @@
+mod common;
+
+use anchor_amm::{
+    constants::{MAX_FEE, VOLATILITY_WINDOW},
+    error::AmmError,
+    events::Swapped,
+};
+use common::*;
+use litesvm::LiteSVM;
+use solana_sdk::signer::Signer;
+
+#[test]
+fn dynamic_fee_is_authority_gated_and_bounded() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 1, 30);
+    let user = pool.create_user(&mut svm, &admin, 0, 0);
+
+    let ix = pool.set_dynamic_fee(&user.pubkey(), 5, 100);
+    assert_amm_error(send(&mut svm, &user, &[ix]), AmmError::InvalidAuthority);
+
+    let ix = pool.set_dynamic_fee(&admin.pubkey(), 100, 5);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidFee);
+
+    let ix = pool.set_dynamic_fee(&admin.pubkey(), 5, MAX_FEE + 1);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidFee);
+
+    // Nothing has moved yet, so the fee starts at its minimum.
+    let ix = pool.set_dynamic_fee(&admin.pubkey(), 5, 100);
+    send(&mut svm, &admin, &[ix]).unwrap();
+    let config = fetch_config(&svm, &pool.config);
+    assert!(config.dynamic_fee);
+    assert_eq!((config.fee, config.min_fee, config.max_fee), (5, 5, 100));
+
+    // A fixed fee leaves dynamic mode.
+    let ix = pool.set_fee(&admin.pubkey(), 30);
+    send(&mut svm, &admin, &[ix]).unwrap();
+    let config = fetch_config(&svm, &pool.config);
+    assert!(!config.dynamic_fee);
+    assert_eq!(config.fee, 30);
+}
+
+#[test]
+fn dynamic_fee_follows_volatility() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 2, 30);
+    let lp = pool.create_user(&mut svm, &admin, 10_000_000, 10_000_000);
+    let trader = pool.create_user(&mut svm, &admin, 2_000_000, 2_000_000);
+
+    let ix = pool.deposit(&lp.pubkey(), 1, 10_000_000, 10_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    let ix = pool.set_dynamic_fee(&admin.pubkey(), 5, 100);
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    let swap = |svm: &mut LiteSVM, is_x: bool, amount: u64| -> Swapped {
+        let ix = pool.swap(&trader.pubkey(), is_x, amount, 1, NO_EXPIRATION);
+        events::<Swapped>(&send(svm, &trader, &[ix]).unwrap()).remove(0)
+    };
+
+    // The first price update only sets the reference price.
+    warp_seconds(&mut svm, 1);
+    assert_eq!(swap(&mut svm, true, 1_000).fee_rate, 5);
+
+    // A swap moving the price 17% only reprices the fee of the swaps after
+    // it.
+    assert_eq!(swap(&mut svm, true, 1_000_000).fee_rate, 5);
+
+    warp_seconds(&mut svm, 1);
+    let quote = simulate::<SwapQuote>(&svm, &trader, pool.quote_swap(false, 10_000, true)).unwrap();
+    let swapped = swap(&mut svm, false, 10_000);
+    let config = fetch_config(&svm, &pool.config);
+    assert!(config.volatility > 1_700);
+    assert_eq!(swapped.fee_rate, 100);
+    assert_eq!(config.fee, 100);
+    assert_eq!((quote.fee, quote.amount_out), (swapped.fee, swapped.amount_out));
+
+    // Part way through the window the volatility has partly decayed, until
+    // the fee is back to its minimum.
+    warp_seconds(&mut svm, VOLATILITY_WINDOW * 29 / 30);
+    let swapped = swap(&mut svm, false, 1_000);
+    let config = fetch_config(&svm, &pool.config);
+    assert!(config.volatility < 100);
+    assert_eq!(swapped.fee_rate, config.volatility_fee());
+    assert!(swapped.fee_rate > 5 && swapped.fee_rate < 100);
+
+    warp_seconds(&mut svm, VOLATILITY_WINDOW);
+    assert_eq!(swap(&mut svm, true, 2_000).fee_rate, 5);
+}
+
+#[test]
+fn dynamic_fee_counts_one_move_per_slot() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 1, 30);
+    let lp = pool.create_user(&mut svm, &admin, 10_000_000, 10_000_000);
+    let trader = pool.create_user(&mut svm, &admin, 2_000_000, 2_000_000);
+
+    let ix = pool.deposit(&lp.pubkey(), 1, 10_000_000, 10_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    let ix = pool.set_dynamic_fee(&admin.pubkey(), 5, 100);
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    let swap = |svm: &mut LiteSVM, is_x: bool, amount: u64| -> Swapped {
+        let ix = pool.swap(&trader.pubkey(), is_x, amount, 1, NO_EXPIRATION);
+        events::<Swapped>(&send(svm, &trader, &[ix]).unwrap()).remove(0)
+    };
+
+    warp_seconds(&mut svm, 1);
+    assert_eq!(swap(&mut svm, true, 1_000).fee_rate, 5);
+    let before = fetch_config(&svm, &pool.config);
+
+    // Swapping back and forth within a slot moves neither the volatility
+    // nor the fee.
+    assert_eq!(swap(&mut svm, true, 300_000).fee_rate, 5);
+    assert_eq!(swap(&mut svm, false, 300_000).fee_rate, 5);
+    assert_eq!(swap(&mut svm, true, 300_000).fee_rate, 5);
+    let config = fetch_config(&svm, &pool.config);
+    assert_eq!(config.volatility, before.volatility);
+    assert_eq!(config.reference_price, before.reference_price);
+
+    // Only a second passing accrues the cumulative prices.
+    assert_eq!(config.last_update_timestamp, before.last_update_timestamp);
+    assert_eq!(config.price_x_cumulative, before.price_x_cumulative);
+
+    // The next slot is charged for the net move of about 6%, not for the
+    // three moves it took.
+    warp_slot(&mut svm);
+    let quote = simulate::<SwapQuote>(&svm, &trader, pool.quote_swap(false, 10_000, true)).unwrap();
+    let swapped = swap(&mut svm, false, 10_000);
+    assert!(swapped.fee_rate > 50 && swapped.fee_rate < 100, "fee rate {}", swapped.fee_rate);
+    assert_eq!((quote.fee, quote.amount_out), (swapped.fee, swapped.amount_out));
+    let config = fetch_config(&svm, &pool.config);
+    assert_eq!(config.last_update_timestamp, before.last_update_timestamp);
+    assert!(config.volatility > 500 && config.volatility < 1_000);
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use anchor_amm::{
+    constants::MINIMUM_LIQUIDITY,
+    events::{FeeUpdated, LiquidityAdded, LiquidityRemoved, LockUpdated, PoolCreated, Swapped},
+    state::CurveType,
+};
+use common::*;
+use solana_sdk::signer::Signer;
+
+#[test]
+fn pool_history_can_be_rebuilt_from_events() {
+    let (mut svm, admin) = setup();
+    let (mint_x, mint_y) = sorted_mints(&mut svm, &admin);
+    let pool = Pool::for_mints(&svm, 1, mint_x, mint_y);
+
+    let pool_count = factory_with_tier(&mut svm, &admin, 30);
+    let ix = pool.create_pool(&admin.pubkey(), 30, CurveType::ConstantProduct, 0, pool_count);
+    let meta = send(&mut svm, &admin, &[ix]).unwrap();
+    let created = &events::<PoolCreated>(&meta)[0];
+    assert_eq!((created.config, created.mint_x, created.mint_y), (pool.config, mint_x, mint_y));
+    assert_eq!((created.fee, created.curve), (30, CurveType::ConstantProduct));
+    assert_eq!(created.authority, Some(admin.pubkey()));
+
+    let lp = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+    let trader = pool.create_user(&mut svm, &admin, 10_000, 0);
+
+    let ix = pool.deposit(&lp.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+    let added = &events::<LiquidityAdded>(&send(&mut svm, &lp, &[ix]).unwrap())[0];
+    assert_eq!((added.user, added.amount_x, added.amount_y), (lp.pubkey(), 1_000_000, 1_000_000));
+    assert_eq!(added.lp, 1_000_000 - MINIMUM_LIQUIDITY);
+    assert_eq!(
+        (added.reserve_x_before, added.reserve_y_before, added.lp_supply_before),
+        (0, 0, 0)
+    );
+    assert_eq!(
+        (added.reserve_x_after, added.reserve_y_after, added.lp_supply_after),
+        (1_000_000, 1_000_000, 1_000_000)
+    );
+
+    let ix = pool.swap(&trader.pubkey(), true, 10_000, 1, NO_EXPIRATION);
+    let swapped = &events::<Swapped>(&send(&mut svm, &trader, &[ix]).unwrap())[0];
+    let y_out = token_balance(&svm, &pool.user_y(&trader.pubkey()));
+    assert_eq!((swapped.is_x, swapped.amount_in, swapped.amount_out), (true, 10_000, y_out));
+    assert_eq!((swapped.fee, swapped.fee_rate), (30, 30));
+    assert_eq!(
+        (swapped.reserve_x_before, swapped.reserve_y_before),
+        (added.reserve_x_after, added.reserve_y_after)
+    );
+    assert_eq!(
+        (swapped.reserve_x_after, swapped.reserve_y_after),
+        (1_010_000, 1_000_000 - y_out)
+    );
+
+    let ix = pool.withdraw(&lp.pubkey(), 100_000, 1, 1, NO_EXPIRATION);
+    let removed = &events::<LiquidityRemoved>(&send(&mut svm, &lp, &[ix]).unwrap())[0];
+    assert_eq!(removed.lp, 100_000);
+    assert_eq!(
+        (removed.reserve_x_before, removed.reserve_y_before, removed.lp_supply_before),
+        (swapped.reserve_x_after, swapped.reserve_y_after, 1_000_000)
+    );
+    assert_eq!(
+        (removed.reserve_x_after, removed.reserve_y_after, removed.lp_supply_after),
+        (
+            token_balance(&svm, &pool.vault_x),
+            token_balance(&svm, &pool.vault_y),
+            900_000
+        )
+    );
+    assert_eq!(removed.reserve_x_before - removed.reserve_x_after, removed.amount_x);
+    assert_eq!(removed.reserve_y_before - removed.reserve_y_after, removed.amount_y);
+}
+
+#[test]
+fn admin_changes_emit_events() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 1, 30);
+
+    let ix = pool.set_fee(&admin.pubkey(), 50);
+    let updated = &events::<FeeUpdated>(&send(&mut svm, &admin, &[ix]).unwrap())[0];
+    assert_eq!((updated.config, updated.fee, updated.protocol_fee), (pool.config, 50, 0));
+
+    let ix = pool.set_protocol_fee(&admin.pubkey(), 2_000);
+    let updated = &events::<FeeUpdated>(&send(&mut svm, &admin, &[ix]).unwrap())[0];
+    assert_eq!((updated.fee, updated.protocol_fee), (50, 2_000));
+
+    let ix = pool.set_dynamic_fee(&admin.pubkey(), 10, 100);
+    let updated = &events::<FeeUpdated>(&send(&mut svm, &admin, &[ix]).unwrap())[0];
+    assert_eq!(
+        (updated.fee, updated.dynamic_fee, updated.min_fee, updated.max_fee),
+        (10, true, 10, 100)
+    );
+
+    let ix = pool.lock(&admin.pubkey());
+    let locked = &events::<LockUpdated>(&send(&mut svm, &admin, &[ix]).unwrap())[0];
+    assert!(locked.locked && !locked.swaps_paused);
+
+    let ix = pool.set_paused(&admin.pubkey(), true, false, true);
+    let paused = &events::<LockUpdated>(&send(&mut svm, &admin, &[ix]).unwrap())[0];
+    assert_eq!(
+        (paused.locked, paused.swaps_paused, paused.deposits_paused, paused.withdrawals_paused),
+        (true, true, false, true)
+    );
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use anchor_amm::{
+    constants::{MAX_FEE, REGISTRY_PAGE_SIZE},
+    error::AmmError,
+    state::{CurveType, Factory, PoolEntry, PoolKey, RegistryPage},
+};
+use common::*;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+#[test]
+fn factory_creates_one_pool_per_pair_and_tier() {
+    let (mut svm, admin) = setup();
+    let creator = Keypair::new();
+    svm.airdrop(&creator.pubkey(), 10_000_000_000).unwrap();
+
+    // Only the program's upgrade authority creates the factory.
+    let ix = initialize_factory(&creator.pubkey(), vec![5, 30, 100]);
+    assert_amm_error(send(&mut svm, &creator, &[ix]), AmmError::InvalidAuthority);
+    let ix = initialize_factory(&admin.pubkey(), vec![30, 30]);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidFeeTier);
+    let ix = initialize_factory(&admin.pubkey(), vec![5, 30, 100]);
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    let (mint_x, mint_y) = sorted_mints(&mut svm, &admin);
+
+    let pool = Pool::for_mints(&svm, 1, mint_x, mint_y);
+    let ix = pool.create_pool(&creator.pubkey(), 30, CurveType::ConstantProduct, 0, 0);
+    send(&mut svm, &creator, &[ix]).unwrap();
+
+    // Anyone creates pools, the factory authority administers them.
+    let config = fetch_config(&svm, &pool.config);
+    assert_eq!((config.fee, config.authority), (30, Some(admin.pubkey())));
+    let key: PoolKey = fetch_account(&svm, &PoolKey::find_address(&mint_x, &mint_y, 30, CurveType::ConstantProduct).0);
+    assert_eq!((key.config, key.index), (pool.config, 0));
+
+    // A second pool for the pair, tier and curve is refused, whatever its seed.
+    let ix = Pool::for_mints(&svm, 2, mint_x, mint_y)
+        .create_pool(&creator.pubkey(), 30, CurveType::ConstantProduct, 0, 1);
+    assert!(send(&mut svm, &creator, &[ix]).is_err());
+
+    let ix = Pool::for_mints(&svm, 2, mint_y, mint_x)
+        .create_pool(&creator.pubkey(), 30, CurveType::ConstantProduct, 0, 1);
+    assert_amm_error(send(&mut svm, &creator, &[ix]), AmmError::UnsortedMints);
+
+    let ix = Pool::for_mints(&svm, 2, mint_x, mint_y)
+        .create_pool(&creator.pubkey(), 50, CurveType::ConstantProduct, 0, 1);
+    assert_amm_error(send(&mut svm, &creator, &[ix]), AmmError::InvalidFeeTier);
+
+    // Another tier is another pool.
+    let other = Pool::for_mints(&svm, 2, mint_x, mint_y);
+    let ix = other.create_pool(&creator.pubkey(), 5, CurveType::ConstantProduct, 0, 1);
+    send(&mut svm, &creator, &[ix]).unwrap();
+
+    let ix = add_fee_tier(&creator.pubkey(), 50);
+    assert_amm_error(send(&mut svm, &creator, &[ix]), AmmError::InvalidAuthority);
+    let ix = add_fee_tier(&admin.pubkey(), 30);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidFeeTier);
+    let ix = add_fee_tier(&admin.pubkey(), MAX_FEE + 1);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidFee);
+    let ix = add_fee_tier(&admin.pubkey(), 50);
+    send(&mut svm, &admin, &[ix]).unwrap();
+    let ix = Pool::for_mints(&svm, 3, mint_x, mint_y)
+        .create_pool(&creator.pubkey(), 50, CurveType::ConstantProduct, 0, 2);
+    send(&mut svm, &creator, &[ix]).unwrap();
+
+    let factory: Factory = fetch_account(&svm, &Factory::find_address().0);
+    assert_eq!(factory.fee_tiers, vec![5, 30, 100, 50]);
+    assert_eq!(factory.pool_count, 3);
+
+    let page: RegistryPage = fetch_account(&svm, &RegistryPage::find_address(0).0);
+    assert_eq!(
+        page.pools[..2],
+        [
+            PoolEntry { config: pool.config, mint_x, mint_y, fee: 30, curve: CurveType::ConstantProduct },
+            PoolEntry { config: other.config, mint_x, mint_y, fee: 5, curve: CurveType::ConstantProduct },
+        ]
+    );
+}
+
+#[test]
+fn registry_pages_fill_in_order() {
+    let (mut svm, admin) = setup();
+    let ix = initialize_factory(&admin.pubkey(), vec![30]);
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    let mut configs = vec![];
+    for i in 0..=REGISTRY_PAGE_SIZE as u64 {
+        let (mint_x, mint_y) = sorted_mints(&mut svm, &admin);
+        let pool = Pool::for_mints(&svm, i, mint_x, mint_y);
+        let ix = pool.create_pool(&admin.pubkey(), 30, CurveType::ConstantProduct, 0, i);
+        send(&mut svm, &admin, &[ix]).unwrap();
+        configs.push(pool.config);
+    }
+
+    let first: RegistryPage = fetch_account(&svm, &RegistryPage::find_address(0).0);
+    let second: RegistryPage = fetch_account(&svm, &RegistryPage::find_address(1).0);
+    assert_eq!((first.index, second.index), (0, 1));
+    assert_eq!(first.pools.len(), REGISTRY_PAGE_SIZE);
+    assert_eq!(second.pools.len(), 1);
+
+    let listed: Vec<_> = first.pools.iter().chain(&second.pools).map(|entry| entry.config).collect();
+    assert_eq!(listed, configs);
+}
+
+#[test]
+fn each_curve_has_its_own_pool_per_pair_and_tier() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create_concentrated(&mut svm, &admin, 1, 30, 10, 1 << 64);
+    let (mint_x, mint_y) = (pool.mint_x, pool.mint_y);
+
+    let config = fetch_config(&svm, &pool.config);
+    assert_eq!((config.curve, config.authority), (CurveType::Concentrated, Some(admin.pubkey())));
+    let ix = Pool::for_mints(&svm, 2, mint_x, mint_y).create_concentrated_pool(&admin.pubkey(), 30, 10, 1 << 64, 1);
+    assert!(send(&mut svm, &admin, &[ix]).is_err());
+
+    // The other curves price the pair differently, so each gets a pool.
+    let stable = Pool::for_mints(&svm, 3, mint_x, mint_y);
+    let ix = stable.create_pool(&admin.pubkey(), 30, CurveType::StableSwap, 100, 1);
+    send(&mut svm, &admin, &[ix]).unwrap();
+    let constant = Pool::for_mints(&svm, 4, mint_x, mint_y);
+    let ix = constant.create_pool(&admin.pubkey(), 30, CurveType::ConstantProduct, 0, 2);
+    send(&mut svm, &admin, &[ix]).unwrap();
+    let weighted = Pool::for_mints(&svm, 5, mint_x, mint_y);
+    let ix = weighted.create_weighted_pool(&admin.pubkey(), 30, 8_000, 3);
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    let ix = Pool::for_mints(&svm, 6, mint_x, mint_y).create_pool(&admin.pubkey(), 30, CurveType::StableSwap, 200, 4);
+    assert!(send(&mut svm, &admin, &[ix]).is_err());
+    let ix = Pool::for_mints(&svm, 6, mint_x, mint_y).create_weighted_pool(&admin.pubkey(), 30, 5_000, 4);
+    assert!(send(&mut svm, &admin, &[ix]).is_err());
+
+    let page: RegistryPage = fetch_account(&svm, &RegistryPage::find_address(0).0);
+    let entry = |config, curve| PoolEntry { config, mint_x, mint_y, fee: 30, curve };
+    assert_eq!(
+        page.pools,
+        [
+            entry(pool.config, CurveType::Concentrated),
+            entry(stable.config, CurveType::StableSwap),
+            entry(constant.config, CurveType::ConstantProduct),
+            entry(weighted.config, CurveType::Weighted),
+        ]
+    );
+    let key: PoolKey = fetch_account(&svm, &PoolKey::find_address(&mint_x, &mint_y, 30, CurveType::Weighted).0);
+    assert_eq!((key.config, key.index), (weighted.config, 3));
+
+    let ix = Pool::for_mints(&svm, 6, mint_y, mint_x).create_concentrated_pool(&admin.pubkey(), 30, 10, 1 << 64, 4);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::UnsortedMints);
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use anchor_amm::{error::AmmError, state::FarmStake};
+use anchor_lang::prelude::Clock;
+use common::*;
+use litesvm::LiteSVM;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+fn now(svm: &LiteSVM) -> i64 {
+    svm.get_sysvar::<Clock>().unix_timestamp
+}
+
+/// Rewards are rounded down once per update, so each payout may fall a
+/// unit or two short of the exact share.
+fn assert_paid(paid: u64, exact: u64) {
+    assert!(paid <= exact && paid + 2 >= exact, "paid {paid}, expected {exact}");
+}
+
+/// A pool with two LPs, `lp_a` holding about three times `lp_b`'s LP.
+fn setup_pool() -> (LiteSVM, Keypair, Pool, Keypair, Keypair) {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 1, 30);
+    let lp_a = pool.create_user(&mut svm, &admin, 3_000_000, 3_000_000);
+    let lp_b = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+
+    let ix = pool.deposit(&lp_a.pubkey(), 1, 3_000_000, 3_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp_a, &[ix]).unwrap();
+    let ix = pool.deposit(&lp_b.pubkey(), 1_000_000, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp_b, &[ix]).unwrap();
+
+    (svm, admin, pool, lp_a, lp_b)
+}
+
+#[test]
+fn farms_are_created_and_scheduled_by_the_pool_authority() {
+    let (mut svm, admin, pool, lp_a, _) = setup_pool();
+    let reward_mint = create_mint(&mut svm, &admin);
+    let farm = Farm::for_mint(&svm, &pool, reward_mint);
+
+    // Rewards in the staked LP would share the staking vault.
+    let ix = Farm::for_mint(&svm, &pool, pool.mint_lp).create(&admin.pubkey());
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidRewardMint);
+
+    let ix = farm.create(&lp_a.pubkey());
+    assert_amm_error(send(&mut svm, &lp_a, &[ix]), AmmError::InvalidAuthority);
+    let ix = farm.create(&admin.pubkey());
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    let end = now(&svm) + 100;
+    let ix = farm.set_emission(&lp_a.pubkey(), 1_000, end);
+    assert_amm_error(send(&mut svm, &lp_a, &[ix]), AmmError::InvalidAuthority);
+    let ix = farm.set_emission(&admin.pubkey(), 1_000, now(&svm) - 1);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidEmissionSchedule);
+
+    // The vault must hold the whole schedule up front.
+    farm.mint_and_fund(&mut svm, &admin, 99_999);
+    let ix = farm.set_emission(&admin.pubkey(), 1_000, end);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InsufficientRewards);
+    farm.mint_and_fund(&mut svm, &admin, 1);
+    let ix = farm.set_emission(&admin.pubkey(), 1_000, end);
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    let state: anchor_amm::state::Farm = fetch_account(&svm, &farm.farm);
+    assert_eq!((state.reward_rate, state.emission_end), (1_000, end));
+    assert_eq!((state.config, state.mint_lp), (pool.config, pool.mint_lp));
+    assert_eq!(token_balance(&svm, &farm.reward_vault), 100_000);
+}
+
+#[test]
+fn stakers_share_emissions_by_stake() {
+    let (mut svm, admin, pool, lp_a, lp_b) = setup_pool();
+    let farm = Farm::setup(&mut svm, &admin, &pool);
+    farm.mint_and_fund(&mut svm, &admin, 1_000_000);
+    let ix = farm.set_emission(&admin.pubkey(), 1_000, now(&svm) + 100);
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    let staked_a = token_balance(&svm, &pool.user_lp(&lp_a.pubkey()));
+    let staked_b = token_balance(&svm, &pool.user_lp(&lp_b.pubkey()));
+
+    let ix = farm.stake(&lp_a.pubkey(), staked_a + 1);
+    assert!(send(&mut svm, &lp_a, &[ix]).is_err());
+    let ix = farm.stake(&lp_a.pubkey(), staked_a);
+    send(&mut svm, &lp_a, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &farm.lp_vault), staked_a);
+
+    // Alone in the farm for 10 seconds, `lp_a` earns everything.
+    warp_seconds(&mut svm, 10);
+    let ix = farm.stake(&lp_b.pubkey(), staked_b);
+    send(&mut svm, &lp_b, &[ix]).unwrap();
+    let stake: FarmStake = fetch_account(&svm, &farm.stake_address(&lp_b.pubkey()));
+    assert_eq!((stake.amount, stake.rewards_owed), (staked_b, 0));
+
+    // Then both share by stake.
+    warp_seconds(&mut svm, 10);
+    let total = (staked_a + staked_b) as u128;
+    let ix = farm.harvest(&lp_a.pubkey());
+    send(&mut svm, &lp_a, &[ix]).unwrap();
+    let share_a = (10_000 * staked_a as u128 / total) as u64;
+    assert_paid(token_balance(&svm, &farm.owner_rewards(&lp_a.pubkey())), 10_000 + share_a);
+
+    // Unstaking keeps what was earned for a later harvest.
+    let ix = farm.unstake(&lp_b.pubkey(), staked_b + 1);
+    assert_amm_error(send(&mut svm, &lp_b, &[ix]), AmmError::InsufficientBalance);
+    let ix = farm.unstake(&lp_b.pubkey(), staked_b);
+    send(&mut svm, &lp_b, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_lp(&lp_b.pubkey())), staked_b);
+    warp_seconds(&mut svm, 10);
+    let ix = farm.harvest(&lp_b.pubkey());
+    send(&mut svm, &lp_b, &[ix]).unwrap();
+    assert_paid(token_balance(&svm, &farm.owner_rewards(&lp_b.pubkey())), 10_000 - share_a);
+
+    let ix = farm.harvest(&lp_b.pubkey());
+    assert_amm_error(send(&mut svm, &lp_b, &[ix]), AmmError::InvalidAmount);
+
+    // Emissions stop at the end of the schedule.
+    warp_seconds(&mut svm, 1_000);
+    let ix = farm.harvest(&lp_a.pubkey());
+    send(&mut svm, &lp_a, &[ix]).unwrap();
+    assert_paid(token_balance(&svm, &farm.owner_rewards(&lp_a.pubkey())), 100_000 - 10_000 + share_a);
+
+    let state: anchor_amm::state::Farm = fetch_account(&svm, &farm.farm);
+    assert!(state.rewards_unclaimed <= 2);
+    assert_eq!(state.total_staked, staked_a);
+}
+
+#[test]
+fn emission_changes_accrue_the_old_schedule_first() {
+    let (mut svm, admin, pool, lp_a, _) = setup_pool();
+    let farm = Farm::setup(&mut svm, &admin, &pool);
+    farm.mint_and_fund(&mut svm, &admin, 1_000_000);
+    let ix = farm.set_emission(&admin.pubkey(), 1_000, now(&svm) + 100);
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    let staked = token_balance(&svm, &pool.user_lp(&lp_a.pubkey()));
+    let ix = farm.stake(&lp_a.pubkey(), staked);
+    send(&mut svm, &lp_a, &[ix]).unwrap();
+
+    // 20 seconds at 1_000, then 10 at 5_000.
+    warp_seconds(&mut svm, 20);
+    let ix = farm.set_emission(&admin.pubkey(), 5_000, now(&svm) + 10);
+    send(&mut svm, &admin, &[ix]).unwrap();
+    warp_seconds(&mut svm, 30);
+
+    // A schedule set after the last one ended starts from then, not from
+    // its end. The vault holds 1_000_000, of which 70_000 is owed.
+    let ix = farm.set_emission(&admin.pubkey(), 10_000, now(&svm) + 94);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InsufficientRewards);
+    let ix = farm.set_emission(&admin.pubkey(), 10_000, now(&svm) + 93);
+    send(&mut svm, &admin, &[ix]).unwrap();
+    warp_seconds(&mut svm, 1);
+
+    let ix = farm.harvest(&lp_a.pubkey());
+    send(&mut svm, &lp_a, &[ix]).unwrap();
+    assert_paid(token_balance(&svm, &farm.owner_rewards(&lp_a.pubkey())), 20_000 + 50_000 + 10_000);
+}
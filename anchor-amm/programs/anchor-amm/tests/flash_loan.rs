@@ -0,0 +1,125 @@
+mod common;
+
+use anchor_amm::error::AmmError;
+use anchor_lang::prelude::AccountMeta;
+use common::*;
+use solana_sdk::signer::Signer;
+
+#[test]
+fn flash_loan_is_repaid_with_a_fee() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 1, 30);
+    let lp = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+    let borrower = pool.create_user(&mut svm, &admin, 1_000, 1_000);
+
+    let ix = pool.deposit(&lp.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    let ix = pool.set_protocol_fee(&admin.pubkey(), 5_000);
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    // 0.09% on each side, rounded up, half of it for the protocol.
+    let borrow = pool.flash_borrow(&borrower.pubkey(), 500_000, 100_001);
+    let repay = pool.flash_repay(&borrower.pubkey());
+    send(&mut svm, &borrower, &[borrow, repay]).unwrap();
+
+    assert_eq!(token_balance(&svm, &pool.vault_x), 1_000_450);
+    assert_eq!(token_balance(&svm, &pool.vault_y), 1_000_091);
+    assert_eq!(token_balance(&svm, &pool.user_x(&borrower.pubkey())), 550);
+    assert_eq!(token_balance(&svm, &pool.user_y(&borrower.pubkey())), 909);
+
+    let config = fetch_config(&svm, &pool.config);
+    assert!(!config.flash_loan_active());
+    assert_eq!((config.protocol_fees_x, config.protocol_fees_y), (225, 45));
+
+    // The tokens borrowed may be used in between.
+    let borrow = pool.flash_borrow(&borrower.pubkey(), 0, 1_000_000);
+    let repay = pool.flash_repay(&borrower.pubkey());
+    send(&mut svm, &borrower, &[borrow, repay]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_y(&borrower.pubkey())), 9);
+}
+
+#[test]
+fn flash_loan_must_be_repaid_in_the_same_transaction() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 1, 30);
+    // The pair's other pool, at another fee tier.
+    let other = Pool::create_with_mints(&mut svm, &admin, 2, 5, pool.mint_x, pool.mint_y);
+
+    let lp = pool.create_user(&mut svm, &admin, 2_000_000, 2_000_000);
+    let borrower = pool.create_user(&mut svm, &admin, 1_000, 1_000);
+    let ix = pool.deposit(&lp.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    let ix = other.deposit(&lp.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    let borrow = pool.flash_borrow(&borrower.pubkey(), 10_000, 0);
+    assert_amm_error(send(&mut svm, &borrower, &[borrow]), AmmError::FlashLoanNotRepaid);
+
+    // Repaying another pool does not count.
+    let borrow = pool.flash_borrow(&borrower.pubkey(), 10_000, 0);
+    let repay = other.flash_repay(&borrower.pubkey());
+    assert_amm_error(
+        send(&mut svm, &borrower, &[borrow, repay]),
+        AmmError::FlashLoanNotRepaid,
+    );
+
+    // Nor does another pool's repayment passing this config as an extra account.
+    let borrow = pool.flash_borrow(&borrower.pubkey(), 10_000, 0);
+    let mut repay = other.flash_repay(&borrower.pubkey());
+    repay.accounts.push(AccountMeta::new(pool.config, false));
+    assert_amm_error(
+        send(&mut svm, &borrower, &[borrow, repay]),
+        AmmError::FlashLoanNotRepaid,
+    );
+
+    let repay = pool.flash_repay(&borrower.pubkey());
+    assert_amm_error(send(&mut svm, &borrower, &[repay]), AmmError::NoFlashLoan);
+
+    let ix = pool.flash_borrow(&borrower.pubkey(), 1_000_001, 0);
+    let repay = pool.flash_repay(&borrower.pubkey());
+    assert_amm_error(
+        send(&mut svm, &borrower, &[ix, repay]),
+        AmmError::InsufficientLiquidity,
+    );
+
+    // A borrower short of the fee cannot repay, and the loan unwinds.
+    let broke = pool.create_user(&mut svm, &admin, 0, 0);
+    let borrow = pool.flash_borrow(&broke.pubkey(), 10_000, 0);
+    let repay = pool.flash_repay(&broke.pubkey());
+    assert!(send(&mut svm, &broke, &[borrow, repay]).is_err());
+    assert_eq!(token_balance(&svm, &pool.vault_x), 1_000_000);
+    assert!(!fetch_config(&svm, &pool.config).flash_loan_active());
+}
+
+#[test]
+fn pool_is_guarded_during_a_flash_loan() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 1, 30);
+    let lp = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+    let borrower = pool.create_user(&mut svm, &admin, 100_000, 100_000);
+    let ix = pool.deposit(&lp.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    let guarded = [
+        pool.swap(&borrower.pubkey(), true, 10_000, 1, NO_EXPIRATION),
+        pool.swap_exact_out(&borrower.pubkey(), true, 10_000, 20_000, NO_EXPIRATION),
+        pool.deposit(&borrower.pubkey(), 1_000, 10_000, 10_000, NO_EXPIRATION),
+        pool.deposit_single(&borrower.pubkey(), true, 10_000, 1, NO_EXPIRATION),
+        pool.flash_borrow(&borrower.pubkey(), 10_000, 0),
+    ];
+    for ix in guarded {
+        let borrow = pool.flash_borrow(&borrower.pubkey(), 500_000, 0);
+        let repay = pool.flash_repay(&borrower.pubkey());
+        assert_amm_error_at(
+            send(&mut svm, &borrower, &[borrow, ix, repay]),
+            1,
+            AmmError::FlashLoanActive,
+        );
+    }
+
+    let ix = pool.lock(&admin.pubkey());
+    send(&mut svm, &admin, &[ix]).unwrap();
+    let borrow = pool.flash_borrow(&borrower.pubkey(), 500_000, 0);
+    let repay = pool.flash_repay(&borrower.pubkey());
+    assert_amm_error(send(&mut svm, &borrower, &[borrow, repay]), AmmError::PoolLocked);
+}
@@ -0,0 +1,328 @@
+//! Drives random sequences of deposits, withdrawals and swaps against a pool
+//! and checks its invariants after every step:
+//!
+//! - swaps never decrease the curve's invariant (`x * y`, StableSwap's D or
+//!   the weighted product)
+//! - the LP supply stays backed: reserves cover the protocol fees, the
+//!   minimum liquidity stays locked, and the reserves per LP share never
+//!   decrease, so every rounding favours the pool
+//! - no user gets out more than they put in, over a deposit withdrawn or a
+//!   swap swapped back straight away, where no fee can be earned between
+
+mod common;
+
+use anchor_amm::{
+    constants::MINIMUM_LIQUIDITY,
+    curves::{stable_swap, weighted},
+    state::CurveType,
+};
+use anchor_lang::solana_program::clock::Clock;
+use common::*;
+use litesvm::LiteSVM;
+use proptest::prelude::*;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+const USERS: usize = 3;
+
+/// Tokens of each mint every user starts with. Small enough for the
+/// invariant comparisons to fit in a u128.
+const FUNDS: u64 = 100_000_000;
+
+/// One step of a sequence. Amounts are basis points of what the user or
+/// the pool holds, so most steps are feasible whatever came before.
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    /// Deposits `share` of the LP supply.
+    Deposit { user: usize, share: u16 },
+    /// Withdraws `share` of the user's LP.
+    Withdraw { user: usize, share: u16 },
+    /// Swaps `share` of the user's balance in, or for `share` of the
+    /// reserve out.
+    Swap { user: usize, is_x: bool, share: u16, exact_in: bool },
+    /// Deposits `share` of the LP supply and withdraws it again.
+    DepositWithdraw { user: usize, share: u16 },
+    /// Swaps `share` of the user's balance in and all of the output back.
+    SwapBack { user: usize, is_x: bool, share: u16 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Snapshot {
+    /// Reserves, net of protocol fees.
+    x: u64,
+    y: u64,
+    supply: u64,
+    /// `x * y`, D or `x^0.8 * y^0.2`.
+    invariant: u128,
+}
+
+struct Harness {
+    svm: LiteSVM,
+    pool: Pool,
+    users: Vec<Keypair>,
+    curve: CurveType,
+}
+
+impl Harness {
+    fn new(curve: CurveType, fee: u16, protocol_fee: u16, (x, y): (u64, u64)) -> Self {
+        let (mut svm, admin) = setup();
+        let pool = match curve {
+            CurveType::StableSwap => Pool::create_with_curve(&mut svm, &admin, 1, fee, curve, 100),
+            CurveType::Weighted => Pool::create_weighted(&mut svm, &admin, 1, fee, 8_000),
+            _ => Pool::create_with_curve(&mut svm, &admin, 1, fee, curve, 0),
+        };
+        send(&mut svm, &admin, &[pool.set_protocol_fee(&admin.pubkey(), protocol_fee)]).unwrap();
+
+        let lp = pool.create_user(&mut svm, &admin, x, y);
+        send(&mut svm, &lp, &[pool.deposit(&lp.pubkey(), 1, x, y, NO_EXPIRATION)]).unwrap();
+
+        let users = (0..USERS)
+            .map(|_| pool.create_user(&mut svm, &admin, FUNDS, FUNDS))
+            .collect();
+
+        Self {
+            svm,
+            pool,
+            users,
+            curve,
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        let config = fetch_config(&self.svm, &self.pool.config);
+        let (vault_x, vault_y) = (
+            token_balance(&self.svm, &self.pool.vault_x),
+            token_balance(&self.svm, &self.pool.vault_y),
+        );
+        assert!(vault_x >= config.protocol_fees_x && vault_y >= config.protocol_fees_y);
+        let (x, y) = config.reserves(vault_x, vault_y).unwrap();
+
+        let invariant = match self.curve {
+            CurveType::StableSwap => {
+                let now = self.svm.get_sysvar::<Clock>().unix_timestamp;
+                stable_swap::compute_d(config.amp(now), x, y).unwrap()
+            }
+            CurveType::Weighted => weighted::compute_invariant(config.weight_x, x, y).unwrap(),
+            _ => x as u128 * y as u128,
+        };
+
+        Snapshot {
+            x,
+            y,
+            supply: mint_supply(&self.svm, &self.pool.mint_lp),
+            invariant,
+        }
+    }
+
+    fn balances(&self, user: usize) -> (u64, u64, u64) {
+        let user = self.users[user].pubkey();
+        (
+            token_balance(&self.svm, &self.pool.user_x(&user)),
+            token_balance(&self.svm, &self.pool.user_y(&user)),
+            token_balance(&self.svm, &self.pool.user_lp(&user)),
+        )
+    }
+
+    /// Sends `ix` signed by `user`, returning whether it succeeded. Failed
+    /// steps must leave the pool untouched.
+    fn send(&mut self, user: usize, ix: solana_sdk::instruction::Instruction) -> bool {
+        let before = self.snapshot();
+        let ok = send(&mut self.svm, &self.users[user], &[ix]).is_ok();
+        if !ok {
+            assert_eq!(self.snapshot(), before);
+        }
+        ok
+    }
+
+    fn deposit(&mut self, user: usize, share: u16) -> bool {
+        let (x, y, _) = self.balances(user);
+        let amount = bps(self.snapshot().supply, share);
+        let ix = self.pool.deposit(&self.users[user].pubkey(), amount, x, y, NO_EXPIRATION);
+        self.send(user, ix)
+    }
+
+    /// Withdraws at exactly the quoted amounts. Only withdrawals paying out
+    /// nothing may fail.
+    fn withdraw(&mut self, user: usize, amount: u64) -> bool {
+        let quote = simulate::<LiquidityQuote>(&self.svm, &self.users[user], self.pool.quote_withdraw(amount));
+        let (min_x, min_y) = match &quote {
+            Ok(quote) => (quote.amount_x, quote.amount_y),
+            Err(_) => (0, 1),
+        };
+        let ix = self.pool.withdraw(&self.users[user].pubkey(), amount, min_x, min_y, NO_EXPIRATION);
+        let ok = self.send(user, ix);
+        if amount != 0 && min_x + min_y != 0 {
+            assert!(ok, "withdrawing {amount} LP failed after quoting {quote:?}");
+        }
+        ok
+    }
+
+    /// Swaps and returns what the user received.
+    fn swap(&mut self, user: usize, is_x: bool, amount: u64, exact_in: bool) -> Option<u64> {
+        let key = self.users[user].pubkey();
+        let (x, y, _) = self.balances(user);
+        let ix = match exact_in {
+            true => self.pool.swap(&key, is_x, amount, 1, NO_EXPIRATION),
+            false => self.pool.swap_exact_out(&key, is_x, amount, u64::MAX, NO_EXPIRATION),
+        };
+        if !self.send(user, ix) {
+            return None;
+        }
+
+        let (x_after, y_after, _) = self.balances(user);
+        Some(match is_x {
+            true => y_after - y,
+            false => x_after - x,
+        })
+    }
+
+    fn apply(&mut self, op: Op) {
+        let before = self.snapshot();
+
+        match op {
+            Op::Deposit { user, share } => {
+                self.deposit(user, share);
+            }
+            Op::Withdraw { user, share } => {
+                let (_, _, lp) = self.balances(user);
+                self.withdraw(user, bps(lp, share));
+            }
+            Op::Swap { user, is_x, share, exact_in } => {
+                let (x, y, _) = self.balances(user);
+                let amount = match (exact_in, is_x) {
+                    (true, true) => bps(x, share),
+                    (true, false) => bps(y, share),
+                    (false, true) => bps(before.y, share),
+                    (false, false) => bps(before.x, share),
+                };
+                if self.swap(user, is_x, amount, exact_in).is_some() {
+                    self.check_swap(&before);
+                }
+            }
+            Op::DepositWithdraw { user, share } => {
+                let start = self.balances(user);
+                if self.deposit(user, share) {
+                    let (_, _, lp) = self.balances(user);
+                    if self.withdraw(user, lp - start.2) {
+                        let (x, y, lp) = self.balances(user);
+                        assert!(x <= start.0 && y <= start.1, "{op:?} paid out more than it took");
+                        assert_eq!(lp, start.2);
+                    }
+                }
+            }
+            Op::SwapBack { user, is_x, share } => {
+                let start = self.balances(user);
+                let amount = bps(
+                    match is_x {
+                        true => start.0,
+                        false => start.1,
+                    },
+                    share,
+                );
+                if let Some(out) = self.swap(user, is_x, amount, true) {
+                    self.check_swap(&before);
+                    let middle = self.snapshot();
+                    if self.swap(user, !is_x, out, true).is_some() {
+                        self.check_swap(&middle);
+                    }
+                    let (x, y, _) = self.balances(user);
+                    assert!(x <= start.0 && y <= start.1, "{op:?} paid out more than it took");
+                }
+            }
+        }
+
+        self.check(&before);
+    }
+
+    /// Swaps keep the invariant, up to the unit StableSwap's D and the
+    /// weighted product are exact to.
+    fn check_swap(&self, before: &Snapshot) {
+        let after = self.snapshot();
+        let tolerance = match self.curve {
+            CurveType::ConstantProduct => 0,
+            _ => 1,
+        };
+        assert!(
+            after.invariant + tolerance >= before.invariant,
+            "invariant fell from {before:?} to {after:?}"
+        );
+    }
+
+    /// The supply stays backed and the reserves per LP share never fall.
+    fn check(&self, before: &Snapshot) {
+        let after = self.snapshot();
+        assert!(after.x > 0 && after.y > 0);
+        assert!(after.supply >= MINIMUM_LIQUIDITY);
+        assert_eq!(
+            token_balance(&self.svm, &self.pool.locked_lp),
+            MINIMUM_LIQUIDITY
+        );
+
+        // x * y / supply^2, or the other invariants over the supply, never
+        // decreases. Those are exact to a unit either way.
+        let held = match self.curve {
+            CurveType::ConstantProduct => {
+                let (s0, s1) = (before.supply as u128, after.supply as u128);
+                after.invariant * s0 * s0 >= before.invariant * s1 * s1
+            }
+            _ => {
+                (after.invariant + 2) * before.supply as u128
+                    >= before.invariant * after.supply as u128
+            }
+        };
+        assert!(held, "reserves per share fell from {before:?} to {after:?}");
+    }
+}
+
+fn bps(amount: u64, share: u16) -> u64 {
+    (amount as u128 * share as u128 / 10_000) as u64
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let user = 0..USERS;
+    let share = 1u16..=10_000;
+    prop_oneof![
+        (user.clone(), share.clone()).prop_map(|(user, share)| Op::Deposit { user, share }),
+        (user.clone(), share.clone()).prop_map(|(user, share)| Op::Withdraw { user, share }),
+        (user.clone(), any::<bool>(), share.clone(), any::<bool>()).prop_map(
+            |(user, is_x, share, exact_in)| Op::Swap { user, is_x, share, exact_in }
+        ),
+        (user.clone(), share.clone())
+            .prop_map(|(user, share)| Op::DepositWithdraw { user, share }),
+        (user, any::<bool>(), share).prop_map(|(user, is_x, share)| Op::SwapBack { user, is_x, share }),
+    ]
+}
+
+fn curve() -> impl Strategy<Value = CurveType> {
+    prop_oneof![
+        Just(CurveType::ConstantProduct),
+        Just(CurveType::StableSwap),
+        Just(CurveType::Weighted),
+    ]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn pool_invariants_hold(
+        curve in curve(),
+        fee in 0u16..=1_000,
+        protocol_fee in 0u16..=10_000,
+        reserves in (10_000u64..=FUNDS, 10_000u64..=FUNDS),
+        ops in prop::collection::vec(op(), 1..40),
+    ) {
+        let mut harness = Harness::new(curve, fee, protocol_fee, reserves);
+        for op in ops {
+            harness.apply(op);
+        }
+
+        // Everyone can still leave.
+        for user in 0..USERS {
+            let (_, _, lp) = harness.balances(user);
+            let before = harness.snapshot();
+            if harness.withdraw(user, lp) {
+                harness.check(&before);
+            }
+        }
+    }
+}
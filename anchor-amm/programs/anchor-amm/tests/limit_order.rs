@@ -0,0 +1,110 @@
+mod common;
+
+use anchor_amm::{error::AmmError, ORDER_BOUNTY};
+use common::*;
+use litesvm::LiteSVM;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+/// A pool holding 1_000_000 of each token, a maker holding 10_000 of each
+/// and a keeper holding nothing.
+fn setup_pool() -> (LiteSVM, Keypair, Pool, Keypair, Keypair) {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 1, 30);
+    let lp = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+    let ix = pool.deposit(&lp.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    let maker = pool.create_user(&mut svm, &admin, 10_000, 10_000);
+    let keeper = pool.create_user(&mut svm, &admin, 0, 0);
+
+    (svm, admin, pool, maker, keeper)
+}
+
+#[test]
+fn orders_fill_once_the_pool_price_crosses_the_limit() {
+    let (mut svm, admin, pool, maker, keeper) = setup_pool();
+
+    // Sell 10_000 x for at least 12_000 y, above the pool's price.
+    let order = Order::new(&pool, &maker.pubkey(), 7, true);
+    let ix = order.place(&order.maker_in(), 10_000, 12_000);
+    send(&mut svm, &maker, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &order.escrow), 10_000);
+    assert_eq!(token_balance(&svm, &pool.user_x(&maker.pubkey())), 0);
+
+    let state: anchor_amm::state::Order = fetch_account(&svm, &order.order);
+    assert_eq!((state.amount, state.min_out, state.mint_in), (10_000, 12_000, pool.mint_x));
+
+    let ix = order.fill(&keeper.pubkey());
+    assert_amm_error(send(&mut svm, &keeper, &[ix]), AmmError::OrderPriceNotReached);
+
+    // Buying x pushes the price past the limit.
+    let trader = pool.create_user(&mut svm, &admin, 0, 300_000);
+    let ix = pool.swap(&trader.pubkey(), false, 300_000, 1, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+
+    // The keeper must swap through the order's pool.
+    let other = Pool::create(&mut svm, &admin, 2, 30);
+    let ix = Order { pool: other, ..order }.fill(&keeper.pubkey());
+    assert_amm_error(send(&mut svm, &keeper, &[ix]), AmmError::InvalidOrder);
+
+    let maker_rent = lamports(&svm, &order.order) + lamports(&svm, &order.escrow);
+    let maker_lamports = lamports(&svm, &maker.pubkey());
+    let ix = order.fill(&keeper.pubkey());
+    send(&mut svm, &keeper, &[ix]).unwrap();
+
+    let paid = token_balance(&svm, &pool.user_y(&maker.pubkey())) - 10_000;
+    let bounty = token_balance(&svm, &pool.user_y(&keeper.pubkey()));
+    assert!(paid >= 12_000);
+    assert_eq!(bounty, (paid + bounty) * ORDER_BOUNTY as u64 / 10_000);
+    assert!(bounty > 0);
+    assert_eq!(token_balance(&svm, &pool.user_x(&keeper.pubkey())), 0);
+
+    // The order and its escrow are closed to the maker.
+    assert_eq!(lamports(&svm, &order.order), 0);
+    assert_eq!(lamports(&svm, &order.escrow), 0);
+    assert_eq!(lamports(&svm, &maker.pubkey()), maker_lamports + maker_rent);
+
+    let ix = order.fill(&keeper.pubkey());
+    assert!(send(&mut svm, &keeper, &[ix]).is_err());
+}
+
+#[test]
+fn orders_selling_y_fill_at_the_pool_price() {
+    let (mut svm, _, pool, maker, keeper) = setup_pool();
+
+    let order = Order::new(&pool, &maker.pubkey(), 0, false);
+    let ix = order.place(&order.maker_in(), 10_000, 9_000);
+    send(&mut svm, &maker, &[ix]).unwrap();
+    let ix = order.fill(&keeper.pubkey());
+    send(&mut svm, &keeper, &[ix]).unwrap();
+
+    let paid = token_balance(&svm, &pool.user_x(&maker.pubkey())) - 10_000;
+    let bounty = token_balance(&svm, &pool.user_x(&keeper.pubkey()));
+    assert!(paid >= 9_000 && bounty > 0);
+    assert_eq!(token_balance(&svm, &pool.vault_y), 1_010_000);
+}
+
+#[test]
+fn makers_cancel_their_orders() {
+    let (mut svm, _, pool, maker, keeper) = setup_pool();
+
+    let order = Order::new(&pool, &maker.pubkey(), 0, true);
+    let ix = order.place(&order.maker_in(), 0, 12_000);
+    assert_amm_error(send(&mut svm, &maker, &[ix]), AmmError::InvalidAmount);
+    let ix = order.place(&order.maker_in(), 10_000, 12_000);
+    send(&mut svm, &maker, &[ix]).unwrap();
+
+    // Only the maker can cancel.
+    let mut ix = order.cancel();
+    ix.accounts[0].pubkey = keeper.pubkey();
+    assert!(send(&mut svm, &keeper, &[ix]).is_err());
+
+    let ix = order.cancel();
+    send(&mut svm, &maker, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_x(&maker.pubkey())), 10_000);
+    assert_eq!(lamports(&svm, &order.order), 0);
+    assert_eq!(lamports(&svm, &order.escrow), 0);
+
+    let ix = order.fill(&keeper.pubkey());
+    assert!(send(&mut svm, &keeper, &[ix]).is_err());
+}
@@ -0,0 +1,220 @@
+mod common;
+
+use anchor_amm::{
+    constants::{CONFIG_VERSION, MAX_FEE},
+    error::AmmError,
+    events::ConfigMigrated,
+    state::{Config, CurveType, Oracle},
+};
+use anchor_lang::{
+    prelude::{Clock, Pubkey},
+    solana_program::{program_option::COption, program_pack::Pack},
+    Discriminator, Space,
+};
+use anchor_spl::token::spl_token;
+use common::*;
+use litesvm::LiteSVM;
+use solana_sdk::{account::Account, signature::Keypair, signer::Signer};
+
+/// Size the first release allocated its configs with.
+const V0_SPACE: usize = 8 + 8 + 33 + 32 + 32 + 2 + 1 + 1 + 1;
+
+/// Writes pool `seed`'s config byte for byte as the first release stored
+/// it: `seed`, `authority`, `mint_x`, `mint_y`, `fee`, `locked`,
+/// `config_bump` and `lp_bump` after the discriminator, zero padded.
+fn write_v0_config(svm: &mut LiteSVM, pool: &Pool, authority: Option<Pubkey>, fee: u16, locked: bool) {
+    let (config, config_bump) = Config::find_address(pool.seed);
+    let lp_bump = Config::find_lp_mint(&config).1;
+
+    let mut data = Config::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&pool.seed.to_le_bytes());
+    match authority {
+        Some(authority) => {
+            data.push(1);
+            data.extend_from_slice(authority.as_ref());
+        }
+        None => data.push(0),
+    }
+    data.extend_from_slice(pool.mint_x.as_ref());
+    data.extend_from_slice(pool.mint_y.as_ref());
+    data.extend_from_slice(&fee.to_le_bytes());
+    data.extend_from_slice(&[locked as u8, config_bump, lp_bump]);
+    data.resize(V0_SPACE, 0);
+
+    set_packed(svm, config, anchor_amm::ID, data);
+}
+
+fn set_packed(svm: &mut LiteSVM, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+    let account = Account {
+        lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    };
+    svm.set_account(address, account).unwrap();
+}
+
+/// A constant product pool as the first release created it: its config,
+/// LP mint and empty vaults, without an oracle or locked LP account.
+fn create_v0_pool(
+    svm: &mut LiteSVM,
+    admin: &Keypair,
+    seed: u64,
+    authority: Option<Pubkey>,
+    fee: u16,
+    locked: bool,
+) -> Pool {
+    let (mint_x, mint_y) = (create_mint(svm, admin), create_mint(svm, admin));
+    let pool = Pool::for_mints(svm, seed, mint_x, mint_y);
+    write_v0_config(svm, &pool, authority, fee, locked);
+
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    let mint = spl_token::state::Mint {
+        mint_authority: COption::Some(pool.config),
+        supply: 0,
+        decimals: DECIMALS,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    mint.pack_into_slice(&mut data);
+    set_packed(svm, pool.mint_lp, spl_token::ID, data);
+
+    create_ata(svm, admin, &pool.config, &pool.mint_x);
+    create_ata(svm, admin, &pool.config, &pool.mint_y);
+    pool
+}
+
+/// Funds the vaults with `x` and `y` and gives `lp_holder` an LP supply of
+/// `lp`, which only the config could mint.
+fn fund_v0_pool(svm: &mut LiteSVM, admin: &Keypair, pool: &Pool, lp_holder: &Pubkey, (x, y, lp): (u64, u64, u64)) {
+    mint_to(svm, admin, &pool.mint_x, &pool.vault_x, x);
+    mint_to(svm, admin, &pool.mint_y, &pool.vault_y, y);
+
+    let mut account = svm.get_account(&pool.mint_lp).unwrap();
+    let mut mint = spl_token::state::Mint::unpack(&account.data).unwrap();
+    mint.supply = lp;
+    mint.pack_into_slice(&mut account.data);
+    svm.set_account(pool.mint_lp, account).unwrap();
+
+    let user_lp = create_ata(svm, admin, lp_holder, &pool.mint_lp);
+    let mut account = svm.get_account(&user_lp).unwrap();
+    let mut token = spl_token::state::Account::unpack(&account.data).unwrap();
+    token.amount = lp;
+    token.pack_into_slice(&mut account.data);
+    svm.set_account(user_lp, account).unwrap();
+}
+
+#[test]
+fn canonical_derivation_matches_initialize() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 0x0102_0304_0506_0708, 30);
+
+    let config = fetch_config(&svm, &pool.config);
+    assert_eq!(Config::find_address(config.seed), (pool.config, config.config_bump));
+    assert_eq!(Config::find_lp_mint(&pool.config), (pool.mint_lp, config.lp_bump));
+}
+
+#[test]
+fn first_release_config_is_migrated() {
+    let (mut svm, admin) = setup();
+    let pool = create_v0_pool(&mut svm, &admin, 7, Some(admin.pubkey()), 30, true);
+
+    send(&mut svm, &admin, &[migrate_config(&admin.pubkey(), pool.seed)]).unwrap();
+
+    let account = svm.get_account(&pool.config).unwrap();
+    assert_eq!(account.data.len(), 8 + Config::INIT_SPACE);
+    assert_eq!(account.lamports, svm.minimum_balance_for_rent_exemption(8 + Config::INIT_SPACE));
+
+    let config = fetch_config(&svm, &pool.config);
+    let now = svm.get_sysvar::<Clock>().unix_timestamp;
+    assert_eq!(Config::find_address(config.seed), (pool.config, config.config_bump));
+    assert_eq!(config.lp_bump, Config::find_lp_mint(&pool.config).1);
+    assert_eq!(config.authority, Some(admin.pubkey()));
+    assert_eq!((config.mint_x, config.mint_y), (pool.mint_x, pool.mint_y));
+    assert_eq!(config.fee, 30);
+    assert!(config.locked);
+    assert_eq!(config.version, CONFIG_VERSION);
+    assert_eq!(config.curve, CurveType::ConstantProduct);
+    assert_eq!(config.pending_authority, None);
+    assert_eq!(config.protocol_fee, 0);
+    assert_eq!(config.last_update_timestamp, now);
+    assert!(!config.swaps_paused && !config.deposits_paused && !config.withdrawals_paused);
+
+    let oracle: Oracle = fetch_account(&svm, &pool.oracle);
+    assert_eq!(oracle.config, pool.config);
+    assert_eq!(oracle.observations.len(), 1);
+    assert_eq!(oracle.observations[0].timestamp, now);
+    assert_eq!(token_balance(&svm, &pool.locked_lp), 0);
+}
+
+#[test]
+fn config_without_authority_is_migrated() {
+    let (mut svm, admin) = setup();
+    // Without an authority the fields after it start 32 bytes earlier.
+    let pool = create_v0_pool(&mut svm, &admin, 7, None, 2_000, false);
+
+    let meta = send(&mut svm, &admin, &[migrate_config(&admin.pubkey(), pool.seed)]).unwrap();
+    let migrated = events::<ConfigMigrated>(&meta).remove(0);
+    assert_eq!(migrated.config, pool.config);
+    assert_eq!(migrated.version, CONFIG_VERSION);
+    assert_eq!((migrated.legacy_fee, migrated.fee), (2_000, MAX_FEE));
+
+    let config = fetch_config(&svm, &pool.config);
+    assert_eq!(config.authority, None);
+    assert_eq!((config.mint_x, config.mint_y), (pool.mint_x, pool.mint_y));
+    assert_eq!(config.fee, MAX_FEE);
+    assert!(!config.locked);
+    assert_eq!(config.lp_bump, Config::find_lp_mint(&pool.config).1);
+    assert_eq!(config.version, CONFIG_VERSION);
+}
+
+#[test]
+fn first_release_pool_trades_once_migrated() {
+    let (mut svm, admin) = setup();
+    let pool = create_v0_pool(&mut svm, &admin, 7, Some(admin.pubkey()), 30, false);
+    fund_v0_pool(&mut svm, &admin, &pool, &admin.pubkey(), (1_000_000, 2_000_000, 1_000_000));
+    let user = pool.create_user(&mut svm, &admin, 100_000, 100_000);
+
+    let ix = pool.swap(&user.pubkey(), true, 10_000, 1, NO_EXPIRATION);
+    assert_anchor_error(
+        send(&mut svm, &user, &[ix]),
+        anchor_lang::error::ErrorCode::AccountDidNotDeserialize,
+    );
+
+    // Only the program's upgrade authority may migrate it.
+    let ix = migrate_config(&user.pubkey(), pool.seed);
+    assert_amm_error(send(&mut svm, &user, &[ix]), AmmError::InvalidAuthority);
+    send(&mut svm, &admin, &[migrate_config(&admin.pubkey(), pool.seed)]).unwrap();
+
+    // The vaults now sign with the seeds the config was created with.
+    let ix = pool.withdraw(&admin.pubkey(), 500_000, 500_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &admin, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.vault_x), 500_000);
+    assert_eq!(token_balance(&svm, &pool.vault_y), 1_000_000);
+    assert_eq!(mint_supply(&svm, &pool.mint_lp), 500_000);
+
+    let ix = pool.swap(&user.pubkey(), true, 10_000, 1, NO_EXPIRATION);
+    send(&mut svm, &user, &[ix]).unwrap();
+    assert!(token_balance(&svm, &pool.user_y(&user.pubkey())) > 100_000);
+
+    let ix = pool.deposit(&user.pubkey(), 1_000, 10_000, 10_000, NO_EXPIRATION);
+    send(&mut svm, &user, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_lp(&user.pubkey())), 1_000);
+    assert_eq!(token_balance(&svm, &pool.locked_lp), 0);
+}
+
+#[test]
+fn current_config_is_not_migrated() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 1, 30);
+    assert_eq!(fetch_config(&svm, &pool.config).version, CONFIG_VERSION);
+
+    let ix = migrate_config(&admin.pubkey(), pool.seed);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::ConfigUpToDate);
+
+    let pool = create_v0_pool(&mut svm, &admin, 7, Some(admin.pubkey()), 30, false);
+    send(&mut svm, &admin, &[migrate_config(&admin.pubkey(), pool.seed)]).unwrap();
+    let ix = migrate_config(&admin.pubkey(), pool.seed);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::ConfigUpToDate);
+}
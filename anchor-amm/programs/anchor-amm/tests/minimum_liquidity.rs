@@ -0,0 +1,82 @@
+mod common;
+
+use anchor_amm::{constants::MINIMUM_LIQUIDITY, error::AmmError};
+use common::*;
+use solana_sdk::signer::Signer;
+
+#[test]
+fn first_deposit_must_exceed_minimum_liquidity() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 1, 30);
+    let lp = pool.create_user(&mut svm, &admin, 10_000, 10_000);
+
+    // sqrt(1_000 * 1_000) leaves nothing for the depositor.
+    let ix = pool.deposit(&lp.pubkey(), 1, 1_000, 1_000, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &lp, &[ix]), AmmError::LiquidityLessThanMinimum);
+
+    // `amount` bounds the LP received on the first deposit.
+    let ix = pool.deposit(&lp.pubkey(), 1_001, 2_000, 2_000, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &lp, &[ix]), AmmError::SlippageExceeded);
+
+    let ix = pool.deposit(&lp.pubkey(), 1_000, 2_000, 2_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_lp(&lp.pubkey())), 1_000);
+    assert_eq!(token_balance(&svm, &pool.locked_lp), MINIMUM_LIQUIDITY);
+}
+
+#[test]
+fn donation_before_first_deposit_does_not_block_pool() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 2, 30);
+    let griefer = pool.create_user(&mut svm, &admin, 1, 1);
+    let lp = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+
+    transfer(&mut svm, &griefer, &pool.user_x(&griefer.pubkey()), &pool.vault_x, 1);
+    transfer(&mut svm, &griefer, &pool.user_y(&griefer.pubkey()), &pool.vault_y, 1);
+
+    let ix = pool.deposit(&lp.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.vault_x), 1_000_001);
+    assert_eq!(
+        token_balance(&svm, &pool.user_lp(&lp.pubkey())),
+        1_000_000 - MINIMUM_LIQUIDITY
+    );
+}
+
+#[test]
+fn share_inflation_donation_is_unprofitable() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 3, 30);
+    let attacker = pool.create_user(&mut svm, &admin, 1_001_001, 1_001_001);
+    let victim = pool.create_user(&mut svm, &admin, 1_010_000, 1_010_000);
+
+    // The attacker takes the smallest possible position...
+    let ix = pool.deposit(&attacker.pubkey(), 1, 1_001, 1_001, NO_EXPIRATION);
+    send(&mut svm, &attacker, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_lp(&attacker.pubkey())), 1);
+
+    // ...and donates to the vaults to inflate the value of each LP token.
+    let (attacker_x, attacker_y) = (pool.user_x(&attacker.pubkey()), pool.user_y(&attacker.pubkey()));
+    transfer(&mut svm, &attacker, &attacker_x, &pool.vault_x, 1_000_000);
+    transfer(&mut svm, &attacker, &attacker_y, &pool.vault_y, 1_000_000);
+
+    // The victim deposits at the inflated price.
+    let ix = pool.deposit(&victim.pubkey(), 1_000, 1_010_000, 1_010_000, NO_EXPIRATION);
+    send(&mut svm, &victim, &[ix]).unwrap();
+
+    // Both exit.
+    let ix = pool.withdraw(&attacker.pubkey(), 1, 1, 1, NO_EXPIRATION);
+    send(&mut svm, &attacker, &[ix]).unwrap();
+    let ix = pool.withdraw(&victim.pubkey(), 1_000, 1, 1, NO_EXPIRATION);
+    send(&mut svm, &victim, &[ix]).unwrap();
+
+    // Almost the whole donation now backs the locked liquidity.
+    assert!(token_balance(&svm, &attacker_x) < 2_000);
+    assert!(token_balance(&svm, &attacker_y) < 2_000);
+
+    // The victim gets back what they put in, give or take rounding.
+    let victim_x = token_balance(&svm, &pool.user_x(&victim.pubkey()));
+    let victim_y = token_balance(&svm, &pool.user_y(&victim.pubkey()));
+    assert!(victim_x > 1_009_000, "victim lost {} x", 1_010_000 - victim_x);
+    assert!(victim_y > 1_009_000, "victim lost {} y", 1_010_000 - victim_y);
+}
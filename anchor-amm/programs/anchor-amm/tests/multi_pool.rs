@@ -0,0 +1,259 @@
+mod common;
+
+use anchor_amm::{
+    constants::{MINIMUM_LIQUIDITY, MIN_WEIGHT},
+    curves::weighted,
+    error::AmmError,
+    state::PoolToken,
+};
+use anchor_lang::{prelude::Pubkey, InstructionData};
+use common::*;
+use litesvm::LiteSVM;
+use solana_sdk::signer::Signer;
+
+const WEIGHTS: [u16; 4] = [4_000, 3_000, 2_000, 1_000];
+
+/// Balances worth the same per unit of every token at `WEIGHTS`.
+const BALANCES: [u64; 4] = [4_000_000, 3_000_000, 2_000_000, 1_000_000];
+
+fn invariant(balances: &[u64]) -> u64 {
+    let tokens: Vec<_> = WEIGHTS.iter().copied().zip(balances.iter().copied()).collect();
+    weighted::compute_multi_invariant(&tokens).unwrap() as u64
+}
+
+#[test]
+fn single_token_joins_and_exits_round_in_the_pools_favour() {
+    let (balance, supply) = (2_000_000_000u64, 5_000_000_000u64);
+    for (weight, amount) in [(2_000u16, 10_000_000u64), (5_000, 1_000), (100, 500_000_000), (9_900, 7_777_777)] {
+        let w = weight as f64 / 10_000.0;
+
+        // supply * ((balance + amount) / balance)^w - 1), without fees.
+        let exact = supply as f64 * (((balance + amount) as f64 / balance as f64).powf(w) - 1.0);
+        let lp = weighted::deposit_single_lp(weight, balance, supply, amount, 0).unwrap();
+        assert!(lp as f64 <= exact && lp as f64 >= exact * (1.0 - 1e-9) - 2.0);
+
+        // balance * (1 - ((supply - lp) / supply)^(1 / w)), without fees.
+        let exact = balance as f64 * (1.0 - ((supply - lp) as f64 / supply as f64).powf(1.0 / w));
+        let out = weighted::withdraw_single_out(weight, balance, supply, lp, 0).unwrap();
+        assert!(out as f64 <= exact && out as f64 >= exact * (1.0 - 1e-9) - 2.0);
+        assert!(out <= amount);
+
+        // Only the share swapped into the other tokens, 1 - w, pays the fee.
+        let charged = weighted::deposit_single_lp(weight, balance, supply, amount, 100).unwrap();
+        let net = amount - (amount as f64 * 0.01 * (1.0 - w)).ceil() as u64;
+        assert_eq!(charged, weighted::deposit_single_lp(weight, balance, supply, net, 0).unwrap());
+    }
+
+    let exact: f64 = WEIGHTS
+        .iter()
+        .zip(BALANCES)
+        .map(|(weight, balance)| (balance as f64).powf(*weight as f64 / 10_000.0))
+        .product();
+    let invariant = invariant(&BALANCES) as f64;
+    assert!(invariant <= exact && invariant >= exact - 1.0);
+}
+
+#[test]
+fn multi_pool_tokens_are_validated() {
+    let (mut svm, admin) = setup();
+    let mints: Vec<_> = (0..9).map(|_| create_mint(&mut svm, &admin)).collect();
+
+    let init = |svm: &LiteSVM, seed: u64, mints: &[Pubkey], weights: Vec<u16>| {
+        MultiPool::for_mints(svm, seed, mints).initialize(&admin.pubkey(), 30, None, weights)
+    };
+
+    // Between 3 and 8 tokens.
+    let ix = init(&svm, 1, &mints[..2], vec![5_000, 5_000]);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidPoolTokens);
+    let ix = init(&svm, 2, &mints, vec![1_000, 1_000, 1_000, 1_000, 1_000, 1_000, 1_000, 1_000, 2_000]);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidPoolTokens);
+
+    // Weights of at least `MIN_WEIGHT` summing to `TOTAL_WEIGHT`.
+    let ix = init(&svm, 3, &mints[..3], vec![5_000, 3_000, 1_000]);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidWeight);
+    let ix = init(&svm, 4, &mints[..3], vec![5_000, 5_000 - MIN_WEIGHT + 1, MIN_WEIGHT - 1]);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidWeight);
+
+    // Each token once.
+    let ix = init(&svm, 5, &[mints[0], mints[1], mints[0]], vec![4_000, 3_000, 3_000]);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidPoolTokens);
+
+    // The same mint extensions as two-token pools.
+    let delegated = create_permanent_delegate_mint(&mut svm, &admin);
+    let ix = init(&svm, 6, &[mints[0], mints[1], delegated], vec![4_000, 3_000, 3_000]);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::UnsupportedMint);
+
+    // Vaults must be the pool's associated token accounts.
+    let mut ix = init(&svm, 7, &mints[..3], vec![4_000, 3_000, 3_000]);
+    let last = ix.accounts.len() - 1;
+    ix.accounts[last].pubkey = create_ata(&mut svm, &admin, &admin.pubkey(), &mints[2]);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidTokenAccounts);
+
+    let mint_2022 = create_transfer_fee_mint(&mut svm, &admin, 100, u64::MAX);
+    let pool = MultiPool::for_mints(&svm, 8, &[mints[0], mints[1], mints[2], mint_2022]);
+    let ix = pool.initialize(&admin.pubkey(), 30, None, WEIGHTS.to_vec());
+    send(&mut svm, &admin, &[ix]).unwrap();
+    let state: anchor_amm::state::MultiPool = fetch_account(&svm, &pool.pool);
+    assert_eq!(state.tokens[3], PoolToken { mint: mint_2022, weight: 1_000 });
+    assert_eq!(state.authority, None);
+    assert_eq!(pool.balances(&svm), vec![0; 4]);
+}
+
+#[test]
+fn multi_pool_deposits_swaps_and_withdraws() {
+    let (mut svm, admin) = setup();
+    let pool = MultiPool::create(&mut svm, &admin, 1, 30, &WEIGHTS);
+    let lp = pool.create_user(&mut svm, &admin, &BALANCES);
+    let trader = pool.create_user(&mut svm, &admin, &[100_000, 0, 0, 0]);
+    let other = pool.create_user(&mut svm, &admin, &BALANCES);
+
+    // The first deposit sets the prices and mints the invariant.
+    let ix = pool.deposit(&lp.pubkey(), invariant(&BALANCES) - MINIMUM_LIQUIDITY + 1, BALANCES.to_vec(), NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &lp, &[ix]), AmmError::SlippageExceeded);
+    let ix = pool.deposit(&lp.pubkey(), 1, BALANCES.to_vec(), NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    let supply = mint_supply(&svm, &pool.mint_lp);
+    assert_eq!(supply, invariant(&BALANCES));
+    assert_eq!(token_balance(&svm, &pool.locked_lp), MINIMUM_LIQUIDITY);
+
+    // Any pair trades directly at its two weights.
+    let expected = weighted::swap_out(4_000, 1_000, BALANCES[0], BALANCES[3], 100_000 - 300).unwrap();
+    let ix = pool.swap(&trader.pubkey(), (0, 3), 100_000, expected + 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::SlippageExceeded);
+    let ix = pool.swap(&trader.pubkey(), (0, 3), 100_000, expected, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_token(&trader.pubkey(), 3)), expected);
+    let balances = pool.balances(&svm);
+    assert_eq!(balances, vec![4_100_000, 3_000_000, 2_000_000, 1_000_000 - expected]);
+    assert!(invariant(&balances) > supply);
+
+    // Later deposits take every token in proportion, rounded up.
+    let amount = supply / 10;
+    let needed: Vec<_> = balances
+        .iter()
+        .map(|balance| (*balance as u128 * amount as u128).div_ceil(supply as u128) as u64)
+        .collect();
+    let mut short = needed.clone();
+    short[2] -= 1;
+    let ix = pool.deposit(&other.pubkey(), amount, short, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &other, &[ix]), AmmError::SlippageExceeded);
+    let ix = pool.deposit(&other.pubkey(), amount, needed.clone(), NO_EXPIRATION);
+    send(&mut svm, &other, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_lp(&other.pubkey())), amount);
+    for (index, needed) in needed.iter().enumerate() {
+        let held = token_balance(&svm, &pool.user_token(&other.pubkey(), index));
+        assert_eq!(held, BALANCES[index] - needed);
+    }
+
+    // Withdrawals pay every token out in proportion, rounded down.
+    let balances = pool.balances(&svm);
+    let supply = mint_supply(&svm, &pool.mint_lp);
+    let paid: Vec<_> = balances
+        .iter()
+        .map(|balance| (*balance as u128 * amount as u128 / supply as u128) as u64)
+        .collect();
+    let mut greedy = paid.clone();
+    greedy[3] += 1;
+    let ix = pool.withdraw(&other.pubkey(), amount, greedy, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &other, &[ix]), AmmError::SlippageExceeded);
+    let ix = pool.withdraw(&other.pubkey(), amount, paid.clone(), NO_EXPIRATION);
+    send(&mut svm, &other, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_lp(&other.pubkey())), 0);
+    for (index, (needed, paid)) in needed.iter().zip(&paid).enumerate() {
+        let held = token_balance(&svm, &pool.user_token(&other.pubkey(), index));
+        assert_eq!(held, BALANCES[index] - needed + paid);
+        assert!(paid <= needed);
+    }
+}
+
+#[test]
+fn multi_pool_single_token_deposits_and_withdrawals() {
+    let (mut svm, admin) = setup();
+    let pool = MultiPool::create(&mut svm, &admin, 1, 30, &WEIGHTS);
+    let lp = pool.create_user(&mut svm, &admin, &BALANCES);
+    let user = pool.create_user(&mut svm, &admin, &[0, 0, 200_000, 0]);
+
+    let ix = pool.deposit_single(&user.pubkey(), 2, 200_000, 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &user, &[ix]), AmmError::NoLiquidityInPool);
+    let ix = pool.deposit(&lp.pubkey(), 1, BALANCES.to_vec(), NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    let supply = mint_supply(&svm, &pool.mint_lp);
+    let expected = weighted::deposit_single_lp(2_000, BALANCES[2], supply, 200_000, 30).unwrap();
+    let ix = pool.deposit_single(&user.pubkey(), 2, 200_000, expected + 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &user, &[ix]), AmmError::SlippageExceeded);
+    let ix = pool.deposit_single(&user.pubkey(), 2, 200_000, expected, NO_EXPIRATION);
+    send(&mut svm, &user, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_lp(&user.pubkey())), expected);
+    assert_eq!(pool.balances(&svm)[2], BALANCES[2] + 200_000);
+
+    // Exiting through the same token pays the fee again and rounding both
+    // ways, so the round trip loses a little.
+    let supply = supply + expected;
+    let out = weighted::withdraw_single_out(2_000, BALANCES[2] + 200_000, supply, expected, 30).unwrap();
+    let ix = pool.withdraw_single(&user.pubkey(), 2, expected, out + 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &user, &[ix]), AmmError::SlippageExceeded);
+    let ix = pool.withdraw_single(&user.pubkey(), 2, expected, out, NO_EXPIRATION);
+    send(&mut svm, &user, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_token(&user.pubkey(), 2)), out);
+    assert!(out < 200_000 && out > 198_000);
+
+    // Exiting into any other token works the same way.
+    let ix = pool.withdraw_single(&lp.pubkey(), 3, supply / 100, 1, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    assert!(token_balance(&svm, &pool.user_token(&lp.pubkey(), 3)) > 0);
+}
+
+#[test]
+fn multi_pool_accounts_and_lock_are_checked() {
+    let (mut svm, admin) = setup();
+    let pool = MultiPool::create(&mut svm, &admin, 1, 30, &WEIGHTS);
+    let lp = pool.create_user(&mut svm, &admin, &BALANCES);
+    let trader = pool.create_user(&mut svm, &admin, &[100_000, 0, 0, 0]);
+    let ix = pool.deposit(&lp.pubkey(), 1, BALANCES.to_vec(), NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    let ix = pool.swap(&trader.pubkey(), (0, 0), 1_000, 0, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InvalidTokenAccounts);
+    let mut ix = pool.swap(&trader.pubkey(), (0, 3), 1_000, 0, NO_EXPIRATION);
+    ix.data = anchor_amm::instruction::SwapMulti {
+        index_in: 0,
+        index_out: 4,
+        amount_in: 1_000,
+        min_out: 0,
+        expiration: NO_EXPIRATION,
+    }
+    .data();
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InvalidTokenAccounts);
+
+    // Token 1's accounts passed as token 3's.
+    let mut ix = pool.swap(&trader.pubkey(), (0, 3), 1_000, 0, NO_EXPIRATION);
+    let len = ix.accounts.len();
+    ix.accounts[len - 3..].clone_from_slice(&pool.token_metas(&trader.pubkey(), 1));
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InvalidTokenAccounts);
+
+    // Someone else's token account.
+    let mut ix = pool.swap(&trader.pubkey(), (0, 3), 1_000, 0, NO_EXPIRATION);
+    ix.accounts[len - 1].pubkey = pool.user_token(&lp.pubkey(), 3);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InvalidTokenAccounts);
+
+    // Proportional deposits need every token.
+    let mut ix = pool.deposit(&lp.pubkey(), 1_000, vec![u64::MAX; 4], NO_EXPIRATION);
+    ix.accounts.truncate(ix.accounts.len() - 3);
+    assert_amm_error(send(&mut svm, &lp, &[ix]), AmmError::InvalidTokenAccounts);
+
+    let ix = pool.set_locked(&trader.pubkey(), true);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InvalidAuthority);
+    let ix = pool.set_locked(&admin.pubkey(), true);
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    let ix = pool.swap(&trader.pubkey(), (0, 3), 1_000, 0, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::PoolLocked);
+    let ix = pool.withdraw(&lp.pubkey(), 1_000, vec![0; 4], NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &lp, &[ix]), AmmError::PoolLocked);
+
+    let ix = pool.set_locked(&admin.pubkey(), false);
+    send(&mut svm, &admin, &[ix]).unwrap();
+    let ix = pool.swap(&trader.pubkey(), (0, 3), 1_000, 1, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+}
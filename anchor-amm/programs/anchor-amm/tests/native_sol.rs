@@ -0,0 +1,149 @@
+mod common;
+
+use anchor_amm::error::AmmError;
+use anchor_spl::token::spl_token;
+use common::*;
+use litesvm::LiteSVM;
+use solana_sdk::{signature::Keypair, signer::Signer};
+use solana_system_interface::instruction as system_instruction;
+
+const SOL: u64 = 1_000_000_000;
+
+/// LiteSVM's fee for a transaction with one signature.
+const TX_FEE: u64 = 5_000;
+
+/// A user with SOL and `y` of the pool's y token, but no wrapped SOL
+/// account.
+fn create_user(svm: &mut LiteSVM, admin: &Keypair, pool: &Pool, y: u64) -> Keypair {
+    let user = Keypair::new();
+    svm.airdrop(&user.pubkey(), 100 * SOL).unwrap();
+    let user_y = create_ata(svm, admin, &user.pubkey(), &pool.mint_y);
+    mint_to(svm, admin, &pool.mint_y, &user_y, y);
+    user
+}
+
+/// A pool of wrapped SOL (x) and a fresh token (y), seeded from lamports
+/// with 10 SOL for 10_000_000_000 y.
+fn setup_pool() -> (LiteSVM, Keypair, Pool) {
+    let (mut svm, admin) = setup();
+    let (mint_x, mint_y) = sort_mints(&mut svm, native_mint, |svm| create_mint(svm, &admin));
+    let pool = Pool::create_with_mints(&mut svm, &admin, 1, 30, mint_x, mint_y);
+
+    let lp = create_user(&mut svm, &admin, &pool, 10 * SOL);
+    let before = lamports(&svm, &lp.pubkey());
+    let ix = pool.deposit_sol(&lp.pubkey(), 1, 10 * SOL, 10 * SOL, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    let lp_rent = lamports(&svm, &pool.user_lp(&lp.pubkey()));
+    assert_eq!(before - lamports(&svm, &lp.pubkey()), 10 * SOL + lp_rent + TX_FEE);
+    assert_eq!(token_balance(&svm, &pool.vault_x), 10 * SOL);
+    assert_eq!(lamports(&svm, &pda::wsol(&lp.pubkey())), 0);
+    assert_eq!(lamports(&svm, &pool.user_x(&lp.pubkey())), 0);
+
+    (svm, admin, pool)
+}
+
+#[test]
+fn deposits_and_withdrawals_move_lamports() {
+    let (mut svm, admin, pool) = setup_pool();
+    let user = create_user(&mut svm, &admin, &pool, 2 * SOL);
+
+    // Wrapped SOL beyond what the deposit takes comes back.
+    let lp = mint_supply(&svm, &pool.mint_lp) / 10;
+    let (before, vault_before) = (lamports(&svm, &user.pubkey()), token_balance(&svm, &pool.vault_x));
+    let ix = pool.deposit_sol(&user.pubkey(), lp, 5 * SOL, 2 * SOL, NO_EXPIRATION);
+    send(&mut svm, &user, &[ix]).unwrap();
+
+    let deposited = token_balance(&svm, &pool.vault_x) - vault_before;
+    assert!(deposited > 0 && deposited < 5 * SOL / 4);
+    let lp_rent = lamports(&svm, &pool.user_lp(&user.pubkey()));
+    assert_eq!(before - lamports(&svm, &user.pubkey()), deposited + lp_rent + TX_FEE);
+    assert_eq!(lamports(&svm, &pda::wsol(&user.pubkey())), 0);
+    assert_eq!(token_balance(&svm, &pool.user_lp(&user.pubkey())), lp);
+
+    let (before, vault_before) = (lamports(&svm, &user.pubkey()), token_balance(&svm, &pool.vault_x));
+    let ix = pool.withdraw_sol(&user.pubkey(), lp, 1, 1, NO_EXPIRATION);
+    send(&mut svm, &user, &[ix]).unwrap();
+
+    let withdrawn = vault_before - token_balance(&svm, &pool.vault_x);
+    assert!(withdrawn > 0);
+    assert_eq!(lamports(&svm, &user.pubkey()) - before, withdrawn - TX_FEE);
+    assert_eq!(lamports(&svm, &pda::wsol(&user.pubkey())), 0);
+    assert_eq!(lamports(&svm, &pool.user_x(&user.pubkey())), 0);
+    assert_eq!(token_balance(&svm, &pool.user_lp(&user.pubkey())), 0);
+}
+
+#[test]
+fn swaps_send_and_receive_lamports() {
+    let (mut svm, admin, pool) = setup_pool();
+    let user = create_user(&mut svm, &admin, &pool, SOL);
+
+    // Selling SOL.
+    let before = lamports(&svm, &user.pubkey());
+    let ix = pool.swap_sol(&user.pubkey(), true, SOL / 10, 1, NO_EXPIRATION);
+    send(&mut svm, &user, &[ix]).unwrap();
+
+    assert_eq!(before - lamports(&svm, &user.pubkey()), SOL / 10 + TX_FEE);
+    assert!(token_balance(&svm, &pool.user_y(&user.pubkey())) > SOL);
+    assert_eq!(lamports(&svm, &pda::wsol(&user.pubkey())), 0);
+
+    // Buying SOL.
+    let (before, vault_before) = (lamports(&svm, &user.pubkey()), token_balance(&svm, &pool.vault_x));
+    let ix = pool.swap_sol(&user.pubkey(), false, SOL / 10, 1, NO_EXPIRATION);
+    send(&mut svm, &user, &[ix]).unwrap();
+
+    let out = vault_before - token_balance(&svm, &pool.vault_x);
+    assert!(out > 0);
+    assert_eq!(lamports(&svm, &user.pubkey()) - before, out - TX_FEE);
+    assert_eq!(lamports(&svm, &pda::wsol(&user.pubkey())), 0);
+    assert_eq!(lamports(&svm, &pool.user_x(&user.pubkey())), 0);
+
+    let ix = pool.swap_sol(&user.pubkey(), true, SOL / 10, u64::MAX, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &user, &[ix]), AmmError::SlippageExceeded);
+}
+
+#[test]
+fn wrapped_sol_already_held_is_left_alone() {
+    let (mut svm, admin, pool) = setup_pool();
+    let user = create_user(&mut svm, &admin, &pool, SOL);
+
+    let user_x = create_ata(&mut svm, &admin, &user.pubkey(), &pool.mint_x);
+    let ixs = [
+        system_instruction::transfer(&user.pubkey(), &user_x, SOL),
+        spl_token::instruction::sync_native(&spl_token::ID, &user_x).unwrap(),
+    ];
+    send(&mut svm, &user, &ixs).unwrap();
+    assert_eq!(token_balance(&svm, &user_x), SOL);
+
+    let held = lamports(&svm, &user_x);
+    let (before, vault_before) = (lamports(&svm, &user.pubkey()), token_balance(&svm, &pool.vault_x));
+    let ix = pool.swap_sol(&user.pubkey(), false, SOL / 10, 1, NO_EXPIRATION);
+    send(&mut svm, &user, &[ix]).unwrap();
+
+    // The output comes back as lamports, past the user's own account.
+    let out = vault_before - token_balance(&svm, &pool.vault_x);
+    assert_eq!(lamports(&svm, &user.pubkey()) - before, out - TX_FEE);
+    assert_eq!(token_balance(&svm, &user_x), SOL);
+    assert_eq!(lamports(&svm, &user_x), held);
+
+    let ix = pool.swap_sol(&user.pubkey(), true, SOL / 10, 1, NO_EXPIRATION);
+    send(&mut svm, &user, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &user_x), SOL);
+    assert_eq!(lamports(&svm, &user_x), held);
+}
+
+#[test]
+fn pools_without_wrapped_sol_are_rejected() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 1, 30);
+    let user = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+    let ix = pool.deposit(&user.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &user, &[ix]).unwrap();
+
+    let ix = pool.swap_sol(&user.pubkey(), true, 1_000, 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &user, &[ix]), AmmError::NotNativePool);
+    let ix = pool.deposit_sol(&user.pubkey(), 1_000, 1_000_000, 1_000_000, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &user, &[ix]), AmmError::NotNativePool);
+    let ix = pool.withdraw_sol(&user.pubkey(), 1_000, 1, 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &user, &[ix]), AmmError::NotNativePool);
+}
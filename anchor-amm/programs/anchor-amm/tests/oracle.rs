@@ -0,0 +1,120 @@
+mod common;
+
+use anchor_amm::{
+    error::AmmError,
+    state::{Observation, Oracle},
+    Twap,
+};
+use anchor_lang::{solana_program::clock::Clock, AccountDeserialize};
+use common::*;
+use solana_sdk::signer::Signer;
+
+const ONE: u128 = 1 << 64;
+
+fn spot(svm: &litesvm::LiteSVM, pool: &Pool) -> (u128, u128) {
+    let x = token_balance(svm, &pool.vault_x) as u128;
+    let y = token_balance(svm, &pool.vault_y) as u128;
+    ((y << 64) / x, (x << 64) / y)
+}
+
+#[test]
+fn observe_returns_time_weighted_prices() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 1, 30);
+    let lp = pool.create_user(&mut svm, &admin, 1_000_000, 4_000_000);
+    let trader = pool.create_user(&mut svm, &admin, 100_000, 0);
+
+    let ix = pool.deposit(&lp.pubkey(), 1, 1_000_000, 4_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    // A constant price averages to itself.
+    warp_seconds(&mut svm, 100);
+    let twap = simulate::<Twap>(&svm, &admin, pool.observe(100)).unwrap();
+    assert_eq!(twap, Twap { price_x: 4 * ONE, price_y: ONE / 4 });
+
+    let ix = pool.swap(&trader.pubkey(), true, 100_000, 1, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    let (price_x, price_y) = spot(&svm, &pool);
+    warp_seconds(&mut svm, 100);
+
+    // 100 seconds at the initial price, 100 at the post-swap price.
+    let twap = simulate::<Twap>(&svm, &admin, pool.observe(200)).unwrap();
+    assert_eq!(twap.price_x, (4 * ONE + price_x) / 2);
+    assert_eq!(twap.price_y, (ONE / 4 + price_y) / 2);
+
+    // Windows inside the latest period see only the current price.
+    let twap = simulate::<Twap>(&svm, &admin, pool.observe(50)).unwrap();
+    assert_eq!(twap, Twap { price_x, price_y });
+
+    // Windows starting between observations are interpolated.
+    let twap = simulate::<Twap>(&svm, &admin, pool.observe(150)).unwrap();
+    assert_eq!(twap.price_x, (4 * ONE * 50 + price_x * 100) / 150);
+
+    let config = fetch_config(&svm, &pool.config);
+    let clock: Clock = svm.get_sysvar();
+    assert_eq!(config.last_update_timestamp, clock.unix_timestamp - 100);
+    assert_eq!(config.price_x_cumulative, 4 * ONE * 100);
+}
+
+#[test]
+fn observe_rejects_windows_beyond_history() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 2, 30);
+    let lp = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+
+    let ix = pool.deposit(&lp.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    warp_seconds(&mut svm, 100);
+
+    assert_amm_error(simulate::<Twap>(&svm, &admin, pool.observe(101)), AmmError::OracleHistoryTooShort);
+    assert_amm_error(simulate::<Twap>(&svm, &admin, pool.observe(0)), AmmError::InvalidAmount);
+}
+
+#[test]
+fn observations_are_spaced_by_interval() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 3, 30);
+    let lp = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+    let trader = pool.create_user(&mut svm, &admin, 100_000, 100_000);
+
+    let ix = pool.deposit(&lp.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    // Trades every 10 seconds only record an observation every 30.
+    for i in 0..9 {
+        warp_seconds(&mut svm, 10);
+        let ix = pool.swap(&trader.pubkey(), i % 2 == 0, 1_000, 1, NO_EXPIRATION);
+        send(&mut svm, &trader, &[ix]).unwrap();
+    }
+
+    let account = svm.get_account(&pool.oracle).unwrap();
+    let oracle = Oracle::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(oracle.observations.len(), 4);
+    assert_eq!(oracle.index, 3);
+    let timestamps: Vec<i64> = oracle.observations.iter().map(|o| o.timestamp).collect();
+    assert!(timestamps.windows(2).all(|w| w[1] - w[0] == 30));
+}
+
+#[test]
+fn interpolation_wraps_near_max_prices() {
+    // A price just under u128::MAX / 10 per second, accrued from just below
+    // the wrap point.
+    let price = u128::MAX / 10 - 7;
+    let before = Observation {
+        timestamp: 100,
+        price_x_cumulative: u128::MAX - 3,
+        price_y_cumulative: 5,
+    };
+    let after = Observation {
+        timestamp: 110,
+        price_x_cumulative: before.price_x_cumulative.wrapping_add(price * 10),
+        price_y_cumulative: before.price_y_cumulative.wrapping_add(10),
+    };
+
+    assert_eq!(before.interpolate(&after, 100), (u128::MAX - 3, 5));
+    assert_eq!(before.interpolate(&after, 107), ((u128::MAX - 3).wrapping_add(price * 7), 12));
+    assert_eq!(before.interpolate(&after, 110), (after.price_x_cumulative, after.price_y_cumulative));
+
+    // Extrapolating past `after` wraps too instead of overflowing.
+    assert_eq!(before.interpolate(&after, 125).0, (u128::MAX - 3).wrapping_add(price.wrapping_mul(25)));
+}
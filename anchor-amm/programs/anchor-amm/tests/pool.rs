@@ -0,0 +1,229 @@
+mod common;
+
+use anchor_amm::{constants::{MAX_FEE, MINIMUM_LIQUIDITY}, error::AmmError, state::CurveType};
+use anchor_lang::prelude::Clock;
+use common::*;
+use solana_sdk::signer::Signer;
+
+#[test]
+fn pool_lifecycle() {
+    let (mut svm, admin) = setup();
+    let created = Pool::create(&mut svm, &admin, 1, 30);
+    let lp = created.create_user(&mut svm, &admin, 1_000_000, 4_000_000);
+    let trader = created.create_user(&mut svm, &admin, 50_000, 50_000);
+
+    // Drive the pool with instructions built by hand rather than by the
+    // client, so its builders are not the only ones the program is run with.
+    let pool = hand_built::Pool::new(
+        created.seed,
+        (created.mint_x, created.token_program_x),
+        (created.mint_y, created.token_program_y),
+    );
+
+    // The first deposit sets the price and mints sqrt(x * y) LP.
+    let ix = pool.deposit_ix(&lp.pubkey(), 1_000_000, 1_000_000, 4_000_000);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.vault_x), 1_000_000);
+    assert_eq!(token_balance(&svm, &pool.vault_y), 4_000_000);
+    assert_eq!(mint_supply(&svm, &pool.mint_lp), 2_000_000);
+    assert_eq!(token_balance(&svm, &pool.locked_lp), MINIMUM_LIQUIDITY);
+    let lp_amount = token_balance(&svm, &pool.user_lp(&lp.pubkey()));
+    assert_eq!(lp_amount, 2_000_000 - MINIMUM_LIQUIDITY);
+
+    // Swap x -> y.
+    let ix = pool.swap_ix(&trader.pubkey(), true, 10_000, 1);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_x(&trader.pubkey())), 40_000);
+    let y_out = token_balance(&svm, &pool.user_y(&trader.pubkey())) - 50_000;
+    assert!(y_out > 0 && y_out < 40_000);
+    assert_eq!(token_balance(&svm, &pool.vault_y), 4_000_000 - y_out);
+
+    // Swap y -> x.
+    let ix = pool.swap_ix(&trader.pubkey(), false, 20_000, 1);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    let x_out = token_balance(&svm, &pool.user_x(&trader.pubkey())) - 40_000;
+    assert!(x_out > 0 && x_out < 20_000 / 3);
+
+    // Only the authority can lock, and a locked pool rejects trading.
+    let ix = pool.lock_ix(&trader.pubkey());
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InvalidAuthority);
+
+    let ix = pool.lock_ix(&admin.pubkey());
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    let ix = pool.swap_ix(&trader.pubkey(), true, 1_000, 1);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::PoolLocked);
+    let ix = pool.withdraw_ix(&lp.pubkey(), 1_000, 1, 1);
+    assert_amm_error(send(&mut svm, &lp, &[ix]), AmmError::PoolLocked);
+
+    let ix = created.unlock(&admin.pubkey());
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    // Withdrawing every redeemable LP token leaves only the locked share.
+    let vault_x = token_balance(&svm, &pool.vault_x);
+    let vault_y = token_balance(&svm, &pool.vault_y);
+    let ix = pool.withdraw_ix(&lp.pubkey(), lp_amount, 1, 1);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    assert_eq!(mint_supply(&svm, &pool.mint_lp), MINIMUM_LIQUIDITY);
+    let x_out = token_balance(&svm, &pool.user_x(&lp.pubkey()));
+    let y_out = token_balance(&svm, &pool.user_y(&lp.pubkey()));
+    assert!(token_balance(&svm, &pool.vault_x) > 0);
+    assert!(token_balance(&svm, &pool.vault_y) > 0);
+    assert_eq!(x_out + token_balance(&svm, &pool.vault_x), vault_x);
+    assert_eq!(y_out + token_balance(&svm, &pool.vault_y), vault_y);
+}
+
+#[test]
+fn second_deposit_is_proportional() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 2, 30);
+    let first = pool.create_user(&mut svm, &admin, 1_000_000, 4_000_000);
+    let second = pool.create_user(&mut svm, &admin, 1_000_000, 4_000_000);
+
+    let ix = pool.deposit(&first.pubkey(), 1, 1_000_000, 4_000_000, NO_EXPIRATION);
+    send(&mut svm, &first, &[ix]).unwrap();
+
+    // Half the LP supply costs half of each reserve.
+    let ix = pool.deposit(&second.pubkey(), 1_000_000, 500_000, 2_000_000, NO_EXPIRATION);
+    send(&mut svm, &second, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.vault_x), 1_500_000);
+    assert_eq!(token_balance(&svm, &pool.vault_y), 6_000_000);
+    assert_eq!(token_balance(&svm, &pool.user_lp(&second.pubkey())), 1_000_000);
+
+    let ix = pool.deposit(&second.pubkey(), 100_000, 1, 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &second, &[ix]), AmmError::SlippageExceeded);
+}
+
+#[test]
+fn swap_respects_min_out() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 3, 30);
+    let lp = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+    let trader = pool.create_user(&mut svm, &admin, 10_000, 0);
+
+    let ix = pool.deposit(&lp.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    let ix = pool.swap(&trader.pubkey(), true, 10_000, 10_000, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::SlippageExceeded);
+
+    let ix = pool.swap(&trader.pubkey(), true, 0, 0, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InvalidAmount);
+}
+
+#[test]
+fn swap_exact_out_rounds_input_up() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 5, 30);
+    let lp = pool.create_user(&mut svm, &admin, 1_000_000, 4_000_000);
+    let trader = pool.create_user(&mut svm, &admin, 50_000, 0);
+
+    let ix = pool.deposit(&lp.pubkey(), 1, 1_000_000, 4_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    // 40,000 y needs ceil(1,000,000 * 40,000 / 3,960,000) = 10,102 x after
+    // the fee, or ceil(10,102 / 0.997) = 10,133 before it.
+    let ix = pool.swap_exact_out(&trader.pubkey(), true, 40_000, 10_132, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::SlippageExceeded);
+    let ix = pool.swap_exact_out(&trader.pubkey(), true, 40_000, 10_133, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_x(&trader.pubkey())), 50_000 - 10_133);
+    assert_eq!(token_balance(&svm, &pool.user_y(&trader.pubkey())), 40_000);
+    assert_eq!(token_balance(&svm, &pool.vault_x), 1_010_133);
+    assert_eq!(token_balance(&svm, &pool.vault_y), 3_960_000);
+
+    let ix = pool.swap_exact_out(&trader.pubkey(), true, 0, 10_000, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InvalidAmount);
+    let ix = pool.swap_exact_out(&trader.pubkey(), false, 1_010_133, u64::MAX, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InsufficientLiquidity);
+
+    let ix = pool.lock(&admin.pubkey());
+    send(&mut svm, &admin, &[ix]).unwrap();
+    let ix = pool.swap_exact_out(&trader.pubkey(), true, 1_000, 10_000, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::PoolLocked);
+}
+
+#[test]
+fn single_sided_deposit_and_withdraw() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 7, 30);
+    let lp = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+    let zapper = pool.create_user(&mut svm, &admin, 100_000, 0);
+
+    let ix = pool.deposit_single(&zapper.pubkey(), true, 100_000, 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &zapper, &[ix]), AmmError::NoLiquidityInPool);
+
+    let ix = pool.deposit(&lp.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    // Swapping s of a into x leaves (a - s) / (x + s) of the pool to add:
+    // s = (sqrt(x^2 (2 - f)^2 + 4 (1 - f) a x) - x (2 - f)) / (2 (1 - f)).
+    let (x, a, f) = (1_000_000f64, 100_000f64, 0.003);
+    let s = (((x * (2.0 - f)).powi(2) + 4.0 * (1.0 - f) * a * x).sqrt() - x * (2.0 - f))
+        / (2.0 * (1.0 - f));
+    let expected = 1_000_000.0 * (a - s) / (x + s);
+
+    let ix = pool.deposit_single(&zapper.pubkey(), true, 0, 0, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &zapper, &[ix]), AmmError::InvalidAmount);
+    let ix = pool.deposit_single(&zapper.pubkey(), true, 100_000, (expected * 1.001) as u64, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &zapper, &[ix]), AmmError::SlippageExceeded);
+
+    let ix = pool.deposit_single(&zapper.pubkey(), true, 100_000, (expected * 0.999) as u64, NO_EXPIRATION);
+    send(&mut svm, &zapper, &[ix]).unwrap();
+    let minted = token_balance(&svm, &pool.user_lp(&zapper.pubkey()));
+    assert!(minted as f64 <= expected + 1.0);
+    assert_eq!(token_balance(&svm, &pool.vault_x), 1_100_000);
+    assert_eq!(token_balance(&svm, &pool.vault_y), 1_000_000);
+    assert_eq!(token_balance(&svm, &pool.user_x(&zapper.pubkey())), 0);
+
+    // Withdrawing it all as y pays both halves of the position in y.
+    let ix = pool.withdraw_single(&zapper.pubkey(), false, minted, 100_000, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &zapper, &[ix]), AmmError::SlippageExceeded);
+    let ix = pool.withdraw_single(&zapper.pubkey(), false, minted, 90_000, NO_EXPIRATION);
+    send(&mut svm, &zapper, &[ix]).unwrap();
+    let y_out = token_balance(&svm, &pool.user_y(&zapper.pubkey()));
+    assert!(y_out > 90_000 && y_out < 100_000);
+    assert_eq!(token_balance(&svm, &pool.vault_y), 1_000_000 - y_out);
+    assert_eq!(token_balance(&svm, &pool.vault_x), 1_100_000);
+    assert_eq!(token_balance(&svm, &pool.user_lp(&zapper.pubkey())), 0);
+}
+
+#[test]
+fn expired_transactions_are_rejected() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 6, 30);
+    let lp = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+    let now = svm.get_sysvar::<Clock>().unix_timestamp;
+
+    // Expiring at the current second still executes.
+    let ix = pool.deposit(&lp.pubkey(), 1, 500_000, 500_000, now);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    warp_seconds(&mut svm, 60);
+    for ix in [
+        pool.deposit(&lp.pubkey(), 1_000, 500_000, 500_000, NO_EXPIRATION),
+        pool.withdraw(&lp.pubkey(), 1_000, 1, 1, NO_EXPIRATION),
+        pool.swap(&lp.pubkey(), true, 1_000, 1, NO_EXPIRATION),
+        pool.swap_exact_out(&lp.pubkey(), true, 1_000, 10_000, NO_EXPIRATION),
+    ] {
+        let expired = with_expiration(ix.clone(), now + 59);
+        assert_amm_error(send(&mut svm, &lp, &[expired]), AmmError::OfferExpired);
+        send(&mut svm, &lp, &[with_expiration(ix, now + 60)]).unwrap();
+    }
+}
+
+#[test]
+fn pools_are_only_created_at_fee_tiers() {
+    let (mut svm, admin) = setup();
+    let ix = initialize_factory(&admin.pubkey(), vec![30, MAX_FEE + 1]);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidFee);
+
+    let pool_count = factory_with_tier(&mut svm, &admin, 30);
+    let (mint_x, mint_y) = sorted_mints(&mut svm, &admin);
+    let pool = Pool::for_mints(&svm, 4, mint_x, mint_y);
+    for fee in [MAX_FEE + 1, 10_000] {
+        let ix = pool.create_pool(&admin.pubkey(), fee, CurveType::ConstantProduct, 0, pool_count);
+        assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidFeeTier);
+    }
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use anchor_amm::error::AmmError;
+use common::*;
+use solana_sdk::signer::Signer;
+
+#[test]
+fn swaps_accrue_and_authority_collects_protocol_fees() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 1, 30);
+    let lp = pool.create_user(&mut svm, &admin, 10_000_000, 10_000_000);
+    let trader = pool.create_user(&mut svm, &admin, 100_000, 100_000);
+
+    let ix = pool.deposit(&lp.pubkey(), 1, 10_000_000, 10_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    let ix = pool.set_protocol_fee(&admin.pubkey(), 5_000);
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    // Half of the 0.3% fee on 100_000 goes to the protocol.
+    let ix = pool.swap(&trader.pubkey(), true, 100_000, 1, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    let ix = pool.swap(&trader.pubkey(), false, 50_000, 1, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+
+    let config = fetch_config(&svm, &pool.config);
+    assert!((149..=150).contains(&config.protocol_fees_x));
+    assert!((74..=75).contains(&config.protocol_fees_y));
+    let (fees_x, fees_y) = (config.protocol_fees_x, config.protocol_fees_y);
+
+    // Accrued fees are not part of the reserves LPs withdraw from.
+    let vault_x = token_balance(&svm, &pool.vault_x);
+    let vault_y = token_balance(&svm, &pool.vault_y);
+    assert_eq!(
+        config.reserves(vault_x, vault_y).unwrap(),
+        (vault_x - fees_x, vault_y - fees_y)
+    );
+
+    let treasury_x = create_ata(&mut svm, &admin, &admin.pubkey(), &pool.mint_x);
+    let treasury_y = create_ata(&mut svm, &admin, &admin.pubkey(), &pool.mint_y);
+
+    let ix = pool.collect_protocol_fees(&trader.pubkey(), &treasury_x, &treasury_y);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InvalidAuthority);
+
+    let ix = pool.collect_protocol_fees(&admin.pubkey(), &treasury_x, &treasury_y);
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    assert_eq!(token_balance(&svm, &treasury_x), fees_x);
+    assert_eq!(token_balance(&svm, &treasury_y), fees_y);
+    assert_eq!(token_balance(&svm, &pool.vault_x), vault_x - fees_x);
+    let config = fetch_config(&svm, &pool.config);
+    assert_eq!((config.protocol_fees_x, config.protocol_fees_y), (0, 0));
+}
+
+#[test]
+fn protocol_fee_is_authority_gated_and_bounded() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create(&mut svm, &admin, 2, 30);
+    let user = pool.create_user(&mut svm, &admin, 0, 0);
+
+    let ix = pool.set_protocol_fee(&user.pubkey(), 1_000);
+    assert_amm_error(send(&mut svm, &user, &[ix]), AmmError::InvalidAuthority);
+
+    let ix = pool.set_protocol_fee(&admin.pubkey(), 10_001);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidFee);
+
+    let ix = pool.set_protocol_fee(&admin.pubkey(), 1_666);
+    send(&mut svm, &admin, &[ix]).unwrap();
+    assert_eq!(fetch_config(&svm, &pool.config).protocol_fee, 1_666);
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use anchor_amm::{error::AmmError, state::CurveType};
+use common::*;
+use solana_sdk::signer::Signer;
+
+#[test]
+fn quotes_match_execution() {
+    let (mut svm, admin) = setup();
+    // 1% on every transfer of x, so quotes must account for it.
+    let (mint_x, mint_y) = sort_mints(
+        &mut svm,
+        |svm| create_transfer_fee_mint(svm, &admin, 100, u64::MAX),
+        |svm| create_mint(svm, &admin),
+    );
+    let pool = Pool::create_with_mints(&mut svm, &admin, 1, 30, mint_x, mint_y);
+    let lp = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+    let other = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+    let trader = pool.create_user(&mut svm, &admin, 100_000, 100_000);
+
+    // The first deposit sets the price and has no quote.
+    assert_amm_error(simulate::<LiquidityQuote>(&svm, &admin, pool.quote_deposit(1_000)), AmmError::NoLiquidityInPool);
+    let ix = pool.deposit(&lp.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    // Exact input, both directions: the quoted output is the minimum that
+    // still succeeds and exactly what arrives.
+    for is_x in [true, false] {
+        let quote = simulate::<SwapQuote>(&svm, &trader, pool.quote_swap(is_x, 10_000, true)).unwrap();
+        assert_eq!(quote.amount_in, 10_000);
+        // x loses its transfer fee before the 0.3% swap fee is taken.
+        assert!((29..=30).contains(&quote.fee));
+
+        let user_out = match is_x {
+            true => pool.user_y(&trader.pubkey()),
+            false => pool.user_x(&trader.pubkey()),
+        };
+        let before = token_balance(&svm, &user_out);
+        let ix = pool.swap(&trader.pubkey(), is_x, 10_000, quote.amount_out + 1, NO_EXPIRATION);
+        assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::SlippageExceeded);
+        let ix = pool.swap(&trader.pubkey(), is_x, 10_000, quote.amount_out, NO_EXPIRATION);
+        send(&mut svm, &trader, &[ix]).unwrap();
+        assert_eq!(token_balance(&svm, &user_out), before + quote.amount_out);
+    }
+
+    // Exact output: the quoted input is the maximum that still succeeds.
+    let quote = simulate::<SwapQuote>(&svm, &trader, pool.quote_swap(true, 5_000, false)).unwrap();
+    assert_eq!(quote.amount_out, 5_000);
+    let ix = pool.swap_exact_out(&trader.pubkey(), true, 5_000, quote.amount_in - 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::SlippageExceeded);
+    let before = token_balance(&svm, &pool.user_x(&trader.pubkey()));
+    let ix = pool.swap_exact_out(&trader.pubkey(), true, 5_000, quote.amount_in, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_x(&trader.pubkey())), before - quote.amount_in);
+
+    // Deposits take exactly the quoted amounts.
+    let quote = simulate::<LiquidityQuote>(&svm, &other, pool.quote_deposit(100_000)).unwrap();
+    let ix = pool.deposit(&other.pubkey(), 100_000, quote.amount_x, quote.amount_y, NO_EXPIRATION);
+    send(&mut svm, &other, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_x(&other.pubkey())), 1_000_000 - quote.amount_x);
+    assert_eq!(token_balance(&svm, &pool.user_y(&other.pubkey())), 1_000_000 - quote.amount_y);
+
+    // Withdrawals pay out exactly the quoted amounts.
+    let quote = simulate::<LiquidityQuote>(&svm, &other, pool.quote_withdraw(100_000)).unwrap();
+    let ix = pool.withdraw(&other.pubkey(), 100_000, quote.amount_x, quote.amount_y, NO_EXPIRATION);
+    let (x, y) = (
+        token_balance(&svm, &pool.user_x(&other.pubkey())),
+        token_balance(&svm, &pool.user_y(&other.pubkey())),
+    );
+    send(&mut svm, &other, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_x(&other.pubkey())), x + quote.amount_x);
+    assert_eq!(token_balance(&svm, &pool.user_y(&other.pubkey())), y + quote.amount_y);
+}
+
+#[test]
+fn price_impact_grows_with_size() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create_with_curve(&mut svm, &admin, 1, 30, CurveType::ConstantProduct, 0);
+    let stable = Pool::create_with_curve(&mut svm, &admin, 2, 4, CurveType::StableSwap, 100);
+    for pool in [&pool, &stable] {
+        let lp = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+        let ix = pool.deposit(&lp.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+        send(&mut svm, &lp, &[ix]).unwrap();
+    }
+
+    // The fee is reported apart, so a swap of 0.1% of the reserve moves
+    // the price by about 0.1%.
+    let small = simulate::<SwapQuote>(&svm, &admin, pool.quote_swap(true, 1_000, true)).unwrap();
+    assert_eq!(small.fee, 3);
+    assert!(small.price_impact <= 10, "{}", small.price_impact);
+
+    // Swapping 10% of the reserve moves a constant product pool by about
+    // as much, a StableSwap pool far less.
+    let large = simulate::<SwapQuote>(&svm, &admin, pool.quote_swap(true, 100_000, true)).unwrap();
+    assert!((900..=920).contains(&large.price_impact), "{}", large.price_impact);
+    let large_stable = simulate::<SwapQuote>(&svm, &admin, stable.quote_swap(true, 100_000, true)).unwrap();
+    assert!(large_stable.price_impact < large.price_impact / 10);
+
+    // Quotes ignore locks, and running them leaves the pool as it was.
+    send(&mut svm, &admin, &[pool.lock(&admin.pubkey())]).unwrap();
+    assert_eq!(simulate::<SwapQuote>(&svm, &admin, pool.quote_swap(true, 100_000, true)).unwrap(), large);
+    assert_amm_error(simulate::<SwapQuote>(&svm, &admin, pool.quote_swap(true, 0, true)), AmmError::InvalidAmount);
+}
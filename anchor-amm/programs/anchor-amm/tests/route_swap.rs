@@ -0,0 +1,96 @@
+mod common;
+
+use anchor_amm::error::AmmError;
+use anchor_lang::prelude::Clock;
+use common::*;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+/// Constant product output for `amount_in` after the 0.3% pool fee.
+fn quote(reserve_in: u64, reserve_out: u64, amount_in: f64) -> f64 {
+    let amount_in = amount_in * 0.997;
+    reserve_out as f64 * amount_in / (reserve_in as f64 + amount_in)
+}
+
+fn hop(pool: &Pool, is_x: bool) -> RouteHop<'_> {
+    RouteHop { pool, is_x, ticks: &[] }
+}
+
+/// Pools for a/b and b/c, each holding 1,000,000 of both sides.
+fn setup_route() -> (litesvm::LiteSVM, Keypair, Pool, Pool) {
+    let (mut svm, admin) = setup();
+    let mut mints = [
+        create_mint(&mut svm, &admin),
+        create_mint(&mut svm, &admin),
+        create_mint(&mut svm, &admin),
+    ];
+    mints.sort();
+    let [a, b, c] = mints;
+    let ab = Pool::create_with_mints(&mut svm, &admin, 1, 30, a, b);
+    let bc = Pool::create_with_mints(&mut svm, &admin, 2, 30, b, c);
+
+    let lp = ab.create_user(&mut svm, &admin, 1_000_000, 2_000_000);
+    let lp_c = create_ata(&mut svm, &admin, &lp.pubkey(), &c);
+    mint_to(&mut svm, &admin, &c, &lp_c, 1_000_000);
+    let ix = ab.deposit(&lp.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    let ix = bc.deposit(&lp.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+
+    (svm, admin, ab, bc)
+}
+
+#[test]
+fn route_swap_chains_pools() {
+    let (mut svm, admin, ab, bc) = setup_route();
+    let trader = ab.create_user(&mut svm, &admin, 10_000, 0);
+
+    // a -> b -> c, the b received feeding the second hop.
+    let expected = quote(1_000_000, 1_000_000, quote(1_000_000, 1_000_000, 10_000.0));
+    let hops = [hop(&ab, true), hop(&bc, true)];
+    let ix = route_swap(&trader.pubkey(), &hops, 10_000, (expected * 1.001) as u64, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::SlippageExceeded);
+    let ix = route_swap(&trader.pubkey(), &hops, 10_000, (expected * 0.999) as u64, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+
+    let c_out = token_balance(&svm, &bc.user_y(&trader.pubkey()));
+    assert!((c_out as f64 - expected).abs() <= 2.0);
+    assert_eq!(token_balance(&svm, &ab.user_x(&trader.pubkey())), 0);
+    assert_eq!(token_balance(&svm, &ab.user_y(&trader.pubkey())), 0);
+    assert_eq!(token_balance(&svm, &ab.vault_x), 1_010_000);
+    let b_moved = token_balance(&svm, &bc.vault_x) - 1_000_000;
+    assert_eq!(token_balance(&svm, &ab.vault_y), 1_000_000 - b_moved);
+    assert_eq!(token_balance(&svm, &bc.vault_y), 1_000_000 - c_out);
+
+    // And back, sending the pools' y.
+    let hops = [hop(&bc, false), hop(&ab, false)];
+    let ix = route_swap(&trader.pubkey(), &hops, c_out, 1, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    let a_out = token_balance(&svm, &ab.user_x(&trader.pubkey()));
+    assert!(a_out > 9_800 && a_out < 10_000);
+    assert_eq!(token_balance(&svm, &bc.user_y(&trader.pubkey())), 0);
+}
+
+#[test]
+fn route_swap_rejects_broken_routes() {
+    let (mut svm, admin, ab, bc) = setup_route();
+    let trader = ab.create_user(&mut svm, &admin, 10_000, 0);
+
+    let ix = route_swap(&trader.pubkey(), &[], 10_000, 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InvalidRoute);
+
+    // The second hop would send a, but the first paid out b.
+    let ix = route_swap(&trader.pubkey(), &[hop(&ab, true), hop(&ab, true)], 10_000, 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InvalidRoute);
+    let ix = route_swap(&trader.pubkey(), &[hop(&ab, true), hop(&bc, false)], 10_000, 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::InvalidRoute);
+
+    // Every hop respects the pool's lock.
+    let ix = bc.lock(&admin.pubkey());
+    send(&mut svm, &admin, &[ix]).unwrap();
+    let ix = route_swap(&trader.pubkey(), &[hop(&ab, true), hop(&bc, true)], 10_000, 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::PoolLocked);
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp;
+    let ix = route_swap(&trader.pubkey(), &[hop(&ab, true)], 10_000, 1, now - 1);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::OfferExpired);
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use anchor_amm::{
+    constants::{MINIMUM_LIQUIDITY, MIN_RAMP_DURATION},
+    curves::stable_swap,
+    error::AmmError,
+    state::CurveType,
+};
+use anchor_lang::solana_program::clock::Clock;
+use common::*;
+use solana_sdk::signer::Signer;
+
+const ONE: u128 = 1 << 64;
+
+#[test]
+fn invariant_of_balanced_reserves_is_their_sum() {
+    for amp in [1, 100, 10_000] {
+        assert_eq!(stable_swap::compute_d(amp, 1_000_000, 1_000_000), Some(2_000_000));
+    }
+    let d = stable_swap::compute_d(100, 1_000_000, 3_000_000).unwrap();
+    assert!(d > 2 * 1_732_050 && d < 4_000_000);
+}
+
+#[test]
+fn higher_amplification_flattens_the_curve() {
+    let constant_product = 10_000_000u64 * 1_000_000 / 11_000_000;
+    let low = stable_swap::swap_out(1, 10_000_000, 10_000_000, 1_000_000).unwrap();
+    let high = stable_swap::swap_out(1_000, 10_000_000, 10_000_000, 1_000_000).unwrap();
+
+    assert!(constant_product < low && low < high && high < 1_000_000);
+    assert!(high > 999_000);
+
+    let (price_x, price_y) = stable_swap::spot_prices(100, 5_000_000, 5_000_000).unwrap();
+    assert_eq!((price_x, price_y), (ONE, ONE));
+    let (price_x, _) = stable_swap::spot_prices(100, 6_000_000, 4_000_000).unwrap();
+    assert!(price_x < ONE && price_x > ONE * 99 / 100);
+}
+
+#[test]
+fn swap_in_covers_the_requested_output() {
+    for amp in [1, 100, 10_000] {
+        for out in [1, 1_000, 500_000, 5_000_000] {
+            let amount_in = stable_swap::swap_in(amp, 10_000_000, 7_000_000, out).unwrap();
+            let back = stable_swap::swap_out(amp, 10_000_000, 7_000_000, amount_in).unwrap();
+            assert!(back >= out && back <= out + 1);
+        }
+    }
+    assert_eq!(stable_swap::swap_in(100, 10_000_000, 7_000_000, 7_000_000), None);
+}
+
+#[test]
+fn stable_pool_trades_near_the_peg() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create_with_curve(&mut svm, &admin, 1, 4, CurveType::StableSwap, 100);
+    let lp = pool.create_user(&mut svm, &admin, 10_000_000, 10_000_000);
+    let trader = pool.create_user(&mut svm, &admin, 1_000_000, 0);
+
+    // The first deposit mints the invariant D as LP.
+    let ix = pool.deposit(&lp.pubkey(), 1, 10_000_000, 10_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    assert_eq!(mint_supply(&svm, &pool.mint_lp), 20_000_000);
+    assert_eq!(token_balance(&svm, &pool.locked_lp), MINIMUM_LIQUIDITY);
+
+    let ix = pool.swap(&trader.pubkey(), true, 1_000_000, 1_000_000, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::SlippageExceeded);
+
+    let fee = 400;
+    let expected =
+        stable_swap::swap_out(100, 10_000_000, 10_000_000, 1_000_000 - fee).unwrap();
+    let ix = pool.swap(&trader.pubkey(), true, 1_000_000, expected, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_y(&trader.pubkey())), expected);
+    assert!(expected > 990_000);
+
+    // Exact-output swaps gross the required input up by the fee.
+    let amount_in = stable_swap::swap_in(100, 10_000_000 - expected, 11_000_000, 100_000).unwrap();
+    let max_in = (amount_in * 10_000).div_ceil(9_996);
+    let ix = pool.swap_exact_out(&trader.pubkey(), false, 100_000, max_in - 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::SlippageExceeded);
+    let ix = pool.swap_exact_out(&trader.pubkey(), false, 100_000, max_in, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_x(&trader.pubkey())), 100_000);
+    assert_eq!(token_balance(&svm, &pool.user_y(&trader.pubkey())), expected - max_in);
+
+    // Proportional withdrawals work as on any other pool.
+    let ix = pool.withdraw(&lp.pubkey(), 10_000_000, 1, 1, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    assert_eq!(mint_supply(&svm, &pool.mint_lp), 10_000_000);
+}
+
+#[test]
+fn initialize_checks_amplification() {
+    let (mut svm, admin) = setup();
+    let (mint_x, mint_y) = sorted_mints(&mut svm, &admin);
+    let pool = Pool::for_mints(&svm, 1, mint_x, mint_y);
+
+    factory_with_tier(&mut svm, &admin, 4);
+    let ix = pool.create_pool(&admin.pubkey(), 4, CurveType::StableSwap, 0, 0);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidAmp);
+
+    factory_with_tier(&mut svm, &admin, 30);
+    let ix = pool.create_pool(&admin.pubkey(), 30, CurveType::ConstantProduct, 100, 0);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidAmp);
+}
+
+#[test]
+fn authority_ramps_amplification() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create_with_curve(&mut svm, &admin, 1, 4, CurveType::StableSwap, 100);
+    let user = pool.create_user(&mut svm, &admin, 0, 0);
+    let now = svm.get_sysvar::<Clock>().unix_timestamp;
+    let stop = now + 2 * MIN_RAMP_DURATION;
+
+    let ix = pool.ramp_amp(&user.pubkey(), 200, stop);
+    assert_amm_error(send(&mut svm, &user, &[ix]), AmmError::InvalidAuthority);
+
+    let ix = pool.ramp_amp(&admin.pubkey(), 200, now + MIN_RAMP_DURATION - 1);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidRamp);
+
+    let ix = pool.ramp_amp(&admin.pubkey(), 1_001, stop);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidRamp);
+
+    let ix = pool.ramp_amp(&admin.pubkey(), 200, stop);
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    // Halfway through the ramp the amplification is halfway there.
+    warp_seconds(&mut svm, MIN_RAMP_DURATION);
+    let now = svm.get_sysvar::<Clock>().unix_timestamp;
+    assert_eq!(fetch_config(&svm, &pool.config).amp(now), 150);
+
+    let ix = pool.stop_ramp_amp(&admin.pubkey());
+    send(&mut svm, &admin, &[ix]).unwrap();
+
+    warp_seconds(&mut svm, MIN_RAMP_DURATION);
+    let now = svm.get_sysvar::<Clock>().unix_timestamp;
+    assert_eq!(fetch_config(&svm, &pool.config).amp(now), 150);
+
+    // Constant product pools have nothing to ramp.
+    let pool = Pool::create(&mut svm, &admin, 2, 30);
+    let ix = pool.ramp_amp(&admin.pubkey(), 200, stop);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidCurve);
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use anchor_amm::{constants::MINIMUM_LIQUIDITY, error::AmmError, math, state::CurveType};
+use common::*;
+use solana_sdk::signer::Signer;
+
+/// Constant product output for `amount_in` after the 0.3% pool fee.
+fn quote(reserve_in: u64, reserve_out: u64, amount_in: u64) -> f64 {
+    let amount_in = amount_in as f64 * 0.997;
+    reserve_out as f64 * amount_in / (reserve_in as f64 + amount_in)
+}
+
+#[test]
+fn transfer_fee_mint_pool() {
+    let (mut svm, admin) = setup();
+    // 1% on every transfer of x.
+    let (mint_x, mint_y) = sort_mints(
+        &mut svm,
+        |svm| create_transfer_fee_mint(svm, &admin, 100, u64::MAX),
+        |svm| create_mint(svm, &admin),
+    );
+    let pool = Pool::create_with_mints(&mut svm, &admin, 1, 30, mint_x, mint_y);
+    let lp = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+    let other = pool.create_user(&mut svm, &admin, 1_000_000, 1_000_000);
+    let trader = pool.create_user(&mut svm, &admin, 100_000, 100_000);
+
+    // The first deposit is priced on what reaches the vaults.
+    let ix = pool.deposit(&lp.pubkey(), 1, 1_000_000, 1_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.vault_x), 990_000);
+    assert_eq!(token_balance(&svm, &pool.vault_y), 1_000_000);
+    let lp_amount = token_balance(&svm, &pool.user_lp(&lp.pubkey()));
+    assert_eq!(lp_amount, math::sqrt(990_000 * 1_000_000) as u64 - MINIMUM_LIQUIDITY);
+
+    // Later deposits send enough for the vault to receive its share.
+    let ix = pool.deposit(&other.pubkey(), 100_000, 200_000, 200_000, NO_EXPIRATION);
+    send(&mut svm, &other, &[ix]).unwrap();
+    let sent_x = 1_000_000 - token_balance(&svm, &pool.user_x(&other.pubkey()));
+    let received_x = token_balance(&svm, &pool.vault_x) - 990_000;
+    assert_eq!(received_x, sent_x - transfer_fee_of(&svm, &mint_x, sent_x));
+    let received_y = token_balance(&svm, &pool.vault_y) - 1_000_000;
+    assert!(received_x * 1_000_000 / received_y >= 989_000);
+
+    // A maximum covering only the received amount is not enough.
+    let ix = pool.deposit(&other.pubkey(), 100_000, received_x, 200_000, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &other, &[ix]), AmmError::SlippageExceeded);
+
+    // x -> y: the curve prices the 99% of the input that arrives.
+    let (x, y) = (
+        token_balance(&svm, &pool.vault_x),
+        token_balance(&svm, &pool.vault_y),
+    );
+    let gross = quote(x, y, 10_000);
+    let ix = pool.swap(&trader.pubkey(), true, 10_000, (gross * 0.998) as u64, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::SlippageExceeded);
+    let ix = pool.swap(&trader.pubkey(), true, 10_000, (gross * 0.985) as u64, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.vault_x), x + 9_900);
+    let y_out = token_balance(&svm, &pool.user_y(&trader.pubkey())) - 100_000;
+    assert_eq!(token_balance(&svm, &pool.vault_y), y - y_out);
+
+    // y -> x: the minimum applies to what the trader receives.
+    let (x, y) = (
+        token_balance(&svm, &pool.vault_x),
+        token_balance(&svm, &pool.vault_y),
+    );
+    let gross = quote(y, x, 10_000);
+    let ix = pool.swap(&trader.pubkey(), false, 10_000, (gross * 0.995) as u64, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::SlippageExceeded);
+    let ix = pool.swap(&trader.pubkey(), false, 10_000, (gross * 0.985) as u64, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    let x_sent = x - token_balance(&svm, &pool.vault_x);
+    let x_out = token_balance(&svm, &pool.user_x(&trader.pubkey())) - 90_000;
+    assert_eq!(x_out, x_sent - transfer_fee_of(&svm, &mint_x, x_sent));
+
+    // Exact-output swaps deliver the amount asked for after the fee.
+    let x_before = token_balance(&svm, &pool.user_x(&trader.pubkey()));
+    let ix = pool.swap_exact_out(&trader.pubkey(), false, 5_000, 10_000, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_x(&trader.pubkey())), x_before + 5_000);
+
+    let y_before = token_balance(&svm, &pool.user_y(&trader.pubkey()));
+    let (x, y) = (
+        token_balance(&svm, &pool.vault_x),
+        token_balance(&svm, &pool.vault_y),
+    );
+    let ix = pool.swap_exact_out(&trader.pubkey(), true, 5_000, 10_000, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_y(&trader.pubkey())), y_before + 5_000);
+    assert_eq!(token_balance(&svm, &pool.vault_y), y - 5_000);
+    // The curve needs at least 5,000 * x / y of x to arrive, plus the fees.
+    let received = token_balance(&svm, &pool.vault_x) - x;
+    assert!(received as u128 * y as u128 >= 5_000 * x as u128);
+
+    // Withdrawals check the minimum after the transfer fee too.
+    let x = token_balance(&svm, &pool.vault_x);
+    let supply = mint_supply(&svm, &pool.mint_lp);
+    let share = x * lp_amount / supply;
+    let ix = pool.withdraw(&lp.pubkey(), lp_amount, share * 995 / 1_000, 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &lp, &[ix]), AmmError::SlippageExceeded);
+    let ix = pool.withdraw(&lp.pubkey(), lp_amount, share * 985 / 1_000, 1, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    let x_sent = x - token_balance(&svm, &pool.vault_x);
+    assert_eq!(
+        token_balance(&svm, &pool.user_x(&lp.pubkey())),
+        x_sent - transfer_fee_of(&svm, &mint_x, x_sent)
+    );
+}
+
+#[test]
+fn interest_bearing_mint_pool() {
+    let (mut svm, admin) = setup();
+    let (mint_x, mint_y) = sort_mints(
+        &mut svm,
+        |svm| create_interest_bearing_mint(svm, &admin, 500),
+        |svm| create_mint(svm, &admin),
+    );
+    let pool = Pool::create_with_mints(&mut svm, &admin, 1, 30, mint_x, mint_y);
+    let lp = pool.create_user(&mut svm, &admin, 1_000_000, 4_000_000);
+    let trader = pool.create_user(&mut svm, &admin, 10_000, 0);
+
+    // Interest only changes the displayed amount, reserves stay raw.
+    let ix = pool.deposit(&lp.pubkey(), 1, 1_000_000, 4_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    assert_eq!(
+        token_balance(&svm, &pool.user_lp(&lp.pubkey())),
+        2_000_000 - MINIMUM_LIQUIDITY
+    );
+
+    warp_seconds(&mut svm, 365 * 86_400);
+    let ix = pool.swap(&trader.pubkey(), true, 10_000, 1, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.vault_x), 1_010_000);
+    let y_out = token_balance(&svm, &pool.user_y(&trader.pubkey()));
+    assert!((y_out as f64 - quote(1_000_000, 4_000_000, 10_000)).abs() <= 1.0);
+}
+
+#[test]
+fn unsupported_extensions_are_rejected() {
+    let (mut svm, admin) = setup();
+    let (mint_x, mint_y) = sort_mints(
+        &mut svm,
+        |svm| create_permanent_delegate_mint(svm, &admin),
+        |svm| create_mint(svm, &admin),
+    );
+    let pool = Pool::for_mints(&svm, 1, mint_x, mint_y);
+
+    let pool_count = factory_with_tier(&mut svm, &admin, 30);
+    let ix = pool.create_pool(&admin.pubkey(), 30, CurveType::ConstantProduct, 0, pool_count);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::UnsupportedMint);
+}
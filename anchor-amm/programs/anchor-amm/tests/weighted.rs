@@ -0,0 +1,123 @@
+mod common;
+
+use anchor_amm::{
+    constants::{MINIMUM_LIQUIDITY, MIN_WEIGHT, TOTAL_WEIGHT},
+    curves::weighted,
+    error::AmmError,
+    state::CurveType,
+};
+use common::*;
+use solana_sdk::signer::Signer;
+
+const ONE: u128 = 1 << 64;
+
+#[test]
+fn even_weights_price_like_the_constant_product() {
+    for (x, y, amount) in [
+        (1_000_000u64, 1_000_000u64, 10_000u64),
+        (1_000_000_000_000, 500_000_000_000, 1_000_000_000),
+        (1_000, 1_000, 1),
+    ] {
+        let constant_product = (y as u128 * amount as u128 / (x as u128 + amount as u128)) as u64;
+        let out = weighted::swap_out(5_000, 5_000, x, y, amount).unwrap();
+        assert!(out <= constant_product && out + 2 >= constant_product);
+
+        let constant_product = (x as u128 * amount as u128).div_ceil((y - amount) as u128) as u64;
+        let amount_in = weighted::swap_in(5_000, 5_000, x, y, amount).unwrap();
+        assert!(amount_in >= constant_product && amount_in <= constant_product + 2);
+    }
+
+    let invariant = weighted::compute_invariant(5_000, 1_000_000, 4_000_000).unwrap();
+    assert!((1_999_999..=2_000_000).contains(&invariant));
+}
+
+#[test]
+fn uneven_weights_round_in_the_pools_favour() {
+    let (x, y) = (1_000_000_000u64, 250_000_000u64);
+    for (weight_x, amount) in [(8_000u16, 1_000_000u64), (2_000, 1_000_000), (9_900, 50_000_000), (100, 7)] {
+        let weight_y = TOTAL_WEIGHT - weight_x;
+        let exponent = weight_x as f64 / weight_y as f64;
+
+        // y * (1 - (x / (x + amount))^(w_x / w_y))
+        let exact = y as f64 * (1.0 - (x as f64 / (x + amount) as f64).powf(exponent));
+        let out = weighted::swap_out(weight_x, weight_y, x, y, amount).unwrap();
+        assert!(out as f64 <= exact && out as f64 >= exact * (1.0 - 1e-9) - 2.0);
+
+        // x * ((y / (y - amount))^(w_y / w_x) - 1)
+        let exact = x as f64 * ((y as f64 / (y - amount) as f64).powf(1.0 / exponent) - 1.0);
+        let amount_in = weighted::swap_in(weight_x, weight_y, x, y, amount).unwrap();
+        assert!(amount_in as f64 >= exact && amount_in as f64 <= exact * (1.0 + 1e-9) + 2.0);
+    }
+
+    // At 80/20 the pool values 1_000 x as much as 250 y.
+    let (price_x, price_y) = weighted::spot_prices(8_000, 1_000, 250).unwrap();
+    assert_eq!((price_x, price_y), (ONE, ONE));
+    let invariant = weighted::compute_invariant(8_000, x, y).unwrap();
+    let exact = (x as f64).powf(0.8) * (y as f64).powf(0.2);
+    assert!(invariant as f64 <= exact && invariant as f64 >= exact - 1.0);
+}
+
+#[test]
+fn weights_are_validated() {
+    let (mut svm, admin) = setup();
+
+    for (seed, weight_x) in [(1, MIN_WEIGHT - 1), (2, TOTAL_WEIGHT - MIN_WEIGHT + 1), (3, 0)] {
+        let (mint_x, mint_y) = sorted_mints(&mut svm, &admin);
+        let pool = Pool::for_mints(&svm, seed, mint_x, mint_y);
+        let pool_count = factory_with_tier(&mut svm, &admin, 30);
+        let ix = pool.create_weighted_pool(&admin.pubkey(), 30, weight_x, pool_count);
+        assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidWeight);
+    }
+
+    // Weighted pools need their weights, which `create_pool` does not take.
+    let (mint_x, mint_y) = sorted_mints(&mut svm, &admin);
+    let pool = Pool::for_mints(&svm, 4, mint_x, mint_y);
+    let ix = pool.create_pool(&admin.pubkey(), 30, CurveType::Weighted, 0, 0);
+    assert_amm_error(send(&mut svm, &admin, &[ix]), AmmError::InvalidCurve);
+}
+
+#[test]
+fn weighted_pool_trades_at_its_weights() {
+    let (mut svm, admin) = setup();
+    let pool = Pool::create_weighted(&mut svm, &admin, 1, 30, 8_000);
+    let lp = pool.create_user(&mut svm, &admin, 8_000_000, 2_000_000);
+    let trader = pool.create_user(&mut svm, &admin, 100_000, 100_000);
+
+    let config = fetch_config(&svm, &pool.config);
+    assert_eq!(config.curve, CurveType::Weighted);
+    assert_eq!((config.weight_x, config.weight_y), (8_000, 2_000));
+
+    // A treasury provides 80% of the value in its own token. The first
+    // deposit mints the weighted invariant as LP.
+    let ix = pool.deposit(&lp.pubkey(), 1, 8_000_000, 2_000_000, NO_EXPIRATION);
+    send(&mut svm, &lp, &[ix]).unwrap();
+    let invariant = weighted::compute_invariant(8_000, 8_000_000, 2_000_000).unwrap() as u64;
+    assert_eq!(mint_supply(&svm, &pool.mint_lp), invariant);
+    assert_eq!(token_balance(&svm, &pool.locked_lp), MINIMUM_LIQUIDITY);
+
+    // At the 1:1 spot price, 100_000 x buys under 100_000 y: the y side is
+    // only 20% of the pool, so it moves the price four times as much.
+    let expected = weighted::swap_out(8_000, 2_000, 8_000_000, 2_000_000, 100_000 - 300).unwrap();
+    let ix = pool.swap(&trader.pubkey(), true, 100_000, expected + 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::SlippageExceeded);
+    let ix = pool.swap(&trader.pubkey(), true, 100_000, expected, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_y(&trader.pubkey())), 100_000 + expected);
+    assert!(expected > 96_000 && expected < 97_000);
+
+    // Exact-output swaps gross the required input up by the fee.
+    let (x, y) = (8_100_000, 2_000_000 - expected);
+    let amount_in = weighted::swap_in(2_000, 8_000, y, x, 50_000).unwrap();
+    let max_in = (amount_in * 10_000).div_ceil(9_970);
+    let ix = pool.swap_exact_out(&trader.pubkey(), false, 50_000, max_in - 1, NO_EXPIRATION);
+    assert_amm_error(send(&mut svm, &trader, &[ix]), AmmError::SlippageExceeded);
+    let ix = pool.swap_exact_out(&trader.pubkey(), false, 50_000, max_in, NO_EXPIRATION);
+    send(&mut svm, &trader, &[ix]).unwrap();
+    assert_eq!(token_balance(&svm, &pool.user_x(&trader.pubkey())), 50_000);
+
+    // Quotes price weighted pools too.
+    let quote = simulate::<SwapQuote>(&svm, &trader, pool.quote_swap(true, 10_000, true)).unwrap();
+    let x = token_balance(&svm, &pool.vault_x);
+    let y = token_balance(&svm, &pool.vault_y);
+    assert_eq!(quote.amount_out, weighted::swap_out(8_000, 2_000, x, y, 10_000 - 30).unwrap());
+}